@@ -0,0 +1,331 @@
+//! API keys and per-caller rate limits. Both services authenticate the same
+//! way, and with the `axum` feature share the middleware that does it.
+
+use http::{HeaderMap, header};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    /// A liquidity provider: a user that may also post maker intents.
+    Maker,
+    Admin,
+}
+
+impl Role {
+    pub fn grants(self, required: Role) -> bool {
+        self == Role::Admin || required == Role::User || self == required
+    }
+
+    /// Whether intents submitted with this key may be maker intents.
+    pub fn may_make(self) -> bool {
+        matches!(self, Role::Maker | Role::Admin)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Role::User => "user",
+            Role::Maker => "maker",
+            Role::Admin => "admin",
+        })
+    }
+}
+
+/// The caller behind an authenticated request. Handlers can read it from
+/// the request extensions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKey {
+    /// Stable, non-secret identifier derived from the key hash.
+    pub id: String,
+    pub role: Role,
+}
+
+/// API keys indexed by their SHA-256 hash; raw keys are never stored.
+#[derive(Default)]
+pub struct ApiKeyStore {
+    keys: RwLock<HashMap<String, ApiKey>>,
+}
+
+impl ApiKeyStore {
+    pub fn insert(&self, raw_key: &str, role: Role) -> ApiKey {
+        let hash = hash_key(raw_key);
+        let key = ApiKey {
+            id: format!("key_{}", &hash[..12]),
+            role,
+        };
+        self.keys
+            .write()
+            .expect("api key lock poisoned")
+            .insert(hash, key.clone());
+        key
+    }
+
+    pub fn authenticate(&self, raw_key: &str) -> Option<ApiKey> {
+        self.keys
+            .read()
+            .expect("api key lock poisoned")
+            .get(&hash_key(raw_key))
+            .cloned()
+    }
+}
+
+fn hash_key(raw_key: &str) -> String {
+    hex::encode(Sha256::digest(raw_key.as_bytes()))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Sustained requests allowed per second.
+    pub per_second: f64,
+    /// Requests that may be made at once before the sustained rate applies.
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            per_second: 10.0,
+            burst: 20,
+        }
+    }
+}
+
+impl RateLimit {
+    /// How long an empty bucket takes to fill up again.
+    fn refill(&self) -> Duration {
+        Duration::from_secs_f64(f64::from(self.burst) / self.per_second)
+    }
+}
+
+/// Whom a bucket belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RateKey {
+    Key(String),
+    Ip(Option<IpAddr>),
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    by_key: HashMap<RateKey, Bucket>,
+    swept: Instant,
+}
+
+/// Token bucket per API key, or per client IP for unauthenticated callers.
+/// A bucket that has filled up again is no different from a new one, so
+/// such buckets are dropped once per refill period; callers that stop
+/// sending requests do not accumulate.
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                swept: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    pub fn acquire(&self, key: RateKey, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.limit.burst);
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+        if now.duration_since(buckets.swept) >= self.limit.refill() {
+            let per_second = self.limit.per_second;
+            buckets.by_key.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * per_second < capacity
+            });
+            buckets.swept = now;
+        }
+
+        let bucket = buckets.by_key.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.limit.per_second;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+
+    /// Callers currently holding a bucket.
+    pub fn tracked(&self) -> usize {
+        self.buckets
+            .lock()
+            .expect("rate limiter lock poisoned")
+            .by_key
+            .len()
+    }
+}
+
+/// Key store plus rate limiter shared by the auth middleware.
+pub struct Authenticator {
+    pub keys: ApiKeyStore,
+    pub limiter: RateLimiter,
+}
+
+impl Default for Authenticator {
+    fn default() -> Self {
+        Self::new(RateLimit::default())
+    }
+}
+
+impl Authenticator {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            keys: ApiKeyStore::default(),
+            limiter: RateLimiter::new(limit),
+        }
+    }
+
+    pub fn with_key(self, raw_key: &str, role: Role) -> Self {
+        self.keys.insert(raw_key, role);
+        self
+    }
+
+    /// Rate-limits the caller, then checks that its key grants `required`.
+    pub fn authorize(
+        &self,
+        headers: &HeaderMap,
+        ip: Option<IpAddr>,
+        required: Role,
+    ) -> Result<ApiKey, AuthError> {
+        let key = presented_key(headers).and_then(|raw| self.keys.authenticate(raw));
+        let rate_key = match &key {
+            Some(key) => RateKey::Key(key.id.clone()),
+            None => RateKey::Ip(ip),
+        };
+        self.limiter
+            .acquire(rate_key, Instant::now())
+            .map_err(AuthError::RateLimited)?;
+
+        let key = key.ok_or(AuthError::Unauthenticated)?;
+        if !key.role.grants(required) {
+            return Err(AuthError::Forbidden(required));
+        }
+        Ok(key)
+    }
+}
+
+/// Reads the key from `x-api-key`, falling back to `Authorization: Bearer`.
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(value) = headers.get(API_KEY_HEADER) {
+        return value.to_str().ok();
+    }
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AuthError {
+    #[error("missing or invalid API key")]
+    Unauthenticated,
+    /// The key is valid but does not grant the role the route needs.
+    #[error("{0} API key required")]
+    Forbidden(Role),
+    #[error("rate limit exceeded")]
+    RateLimited(Duration),
+}
+
+impl AuthError {
+    /// Whole seconds to put in `Retry-After`, at least one.
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            AuthError::RateLimited(wait) => Some(wait.as_secs_f64().ceil().max(1.0) as u64),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+mod middleware {
+    use super::{AuthError, Authenticator, Role};
+    use crate::error::{ApiError, ErrorCode};
+    use axum::extract::{ConnectInfo, Request};
+    use axum::http::{HeaderValue, header};
+    use axum::middleware::Next;
+    use axum::response::{IntoResponse, Response};
+    use serde_json::json;
+    use std::net::SocketAddr;
+
+    /// Renders a refused request as an [`ApiError`], with `Retry-After` when
+    /// it was rate limited.
+    impl IntoResponse for AuthError {
+        fn into_response(self) -> Response {
+            let code = match self {
+                AuthError::Unauthenticated => ErrorCode::Unauthorized,
+                AuthError::Forbidden(_) => ErrorCode::Forbidden,
+                AuthError::RateLimited(_) => ErrorCode::RateLimited,
+            };
+            let retry_after = self.retry_after_secs();
+            let mut error = ApiError::new(code, self.to_string());
+            if let Some(seconds) = retry_after {
+                error = error.with_details(json!({ "retry_after_secs": seconds }));
+            }
+            let mut response = error.into_response();
+            if let Some(seconds) = retry_after {
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            }
+            response
+        }
+    }
+
+    /// Runs `next` if the request's key grants `required`, with the
+    /// [`super::ApiKey`] in the request extensions; refuses it otherwise.
+    /// Callers without a key are rate-limited by the peer address axum was
+    /// served with, if any.
+    pub async fn authorize(
+        auth: &Authenticator,
+        mut req: Request,
+        next: Next,
+        required: Role,
+    ) -> Response {
+        let ip = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        match auth.authorize(req.headers(), ip, required) {
+            Ok(key) => {
+                req.extensions_mut().insert(key);
+                next.run(req).await
+            }
+            Err(err) => err.into_response(),
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use middleware::authorize;
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, RwLock};
+use thiserror::Error;
+use utoipa::openapi::RefOr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::{PartialSchema, ToSchema};
+
+/// Blocks a transfer needs on top of it before it counts as final, unless
+/// the registry says otherwise.
+pub const DEFAULT_FINALITY_DEPTH: u64 = 12;
+
+/// EIP-155 id of an EVM network. Names and metadata live in the
+/// [`ChainRegistry`]; on the wire a chain is written as its registry name
+/// and read from a name, an alias or the numeric id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChainId(u64);
+
+impl ChainId {
+    pub const ETHEREUM: ChainId = ChainId(1);
+    pub const SEPOLIA: ChainId = ChainId(11_155_111);
+    pub const BASE: ChainId = ChainId(8453);
+    pub const BASE_SEPOLIA: ChainId = ChainId(84_532);
+    pub const ARBITRUM: ChainId = ChainId(42_161);
+    pub const ARBITRUM_SEPOLIA: ChainId = ChainId(421_614);
+
+    pub const fn new(id: u64) -> Self {
+        ChainId(id)
+    }
+
+    /// The EIP-155 id, as reported by `eth_chainId`.
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// The chain's entry in the installed [`registry`].
+    pub fn info(self) -> Option<ChainInfo> {
+        registry().get(self).cloned()
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match registry().get(*self) {
+            Some(info) => f.write_str(&info.name),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl FromStr for ChainId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        registry()
+            .resolve(s)
+            .ok_or_else(|| format!("invalid chain '{s}'"))
+    }
+}
+
+impl Serialize for ChainId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = ChainId;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a chain name or EVM chain id")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<ChainId, E> {
+                ChainId::from_str(value).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<ChainId, E> {
+                let chain = ChainId(value);
+                if registry().get(chain).is_none() {
+                    return Err(E::custom(format!("invalid chain '{value}'")));
+                }
+                Ok(chain)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<ChainId, E> {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(E::custom(format!("invalid chain '{value}'"))),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl PartialSchema for ChainId {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some(
+                "Chain name or alias from the chain registry; a registered EVM chain id is \
+                 accepted on input.",
+            ))
+            .examples(["Base"])
+            .into()
+    }
+}
+
+impl ToSchema for ChainId {}
+
+/// What the services know about one chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainInfo {
+    pub id: ChainId,
+    /// Display name, also used on the wire.
+    pub name: String,
+    /// Other names accepted on input.
+    pub aliases: Vec<String>,
+    pub rpc_url: Option<String>,
+    /// Blocks a transfer needs on top of it before it counts as final.
+    pub finality_depth: u64,
+    /// Block explorer root, e.g. `https://basescan.org`.
+    pub explorer_url: Option<String>,
+}
+
+impl ChainInfo {
+    pub fn new(id: ChainId, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            aliases: Vec::new(),
+            rpc_url: None,
+            finality_depth: DEFAULT_FINALITY_DEPTH,
+            explorer_url: None,
+        }
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    pub fn with_rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_url = Some(url.into());
+        self
+    }
+
+    pub fn with_finality_depth(mut self, depth: u64) -> Self {
+        self.finality_depth = depth;
+        self
+    }
+
+    pub fn with_explorer_url(mut self, url: impl Into<String>) -> Self {
+        self.explorer_url = Some(url.into());
+        self
+    }
+
+    /// Explorer page for transaction `hash`, if an explorer is known.
+    pub fn tx_url(&self, hash: &str) -> Option<String> {
+        let root = self.explorer_url.as_deref()?.trim_end_matches('/');
+        Some(format!("{root}/tx/{hash}"))
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum RegistryError {
+    #[error("chain name '{0}' must contain a letter")]
+    InvalidName(String),
+    #[error("name '{name}' already belongs to chain {owner}")]
+    NameTaken { name: String, owner: u64 },
+}
+
+/// Every chain the services can name, keyed by EVM chain id.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainRegistry {
+    chains: BTreeMap<ChainId, ChainInfo>,
+}
+
+impl ChainRegistry {
+    /// Mainnets and testnets OceanLink knows out of the box, without RPC
+    /// endpoints.
+    pub fn builtin() -> Self {
+        let chains = [
+            ChainInfo::new(ChainId::ETHEREUM, "Ethereum")
+                .with_alias("mainnet")
+                .with_finality_depth(64)
+                .with_explorer_url("https://etherscan.io"),
+            ChainInfo::new(ChainId::SEPOLIA, "Sepolia")
+                .with_finality_depth(64)
+                .with_explorer_url("https://sepolia.etherscan.io"),
+            ChainInfo::new(ChainId::BASE, "Base")
+                .with_finality_depth(10)
+                .with_explorer_url("https://basescan.org"),
+            ChainInfo::new(ChainId::BASE_SEPOLIA, "Base Sepolia")
+                .with_finality_depth(10)
+                .with_explorer_url("https://sepolia.basescan.org"),
+            ChainInfo::new(ChainId::ARBITRUM, "Arbitrum")
+                .with_alias("Arbitrum One")
+                .with_finality_depth(20)
+                .with_explorer_url("https://arbiscan.io"),
+            ChainInfo::new(ChainId::ARBITRUM_SEPOLIA, "Arbitrum Sepolia")
+                .with_finality_depth(20)
+                .with_explorer_url("https://sepolia.arbiscan.io"),
+        ];
+        Self {
+            chains: chains.into_iter().map(|info| (info.id, info)).collect(),
+        }
+    }
+
+    /// Adds `info`, replacing any entry with the same id. Fails if one of
+    /// its names already refers to another chain.
+    pub fn insert(&mut self, info: ChainInfo) -> Result<(), RegistryError> {
+        for name in info.names() {
+            if !name.chars().any(char::is_alphabetic) {
+                return Err(RegistryError::InvalidName(name.to_string()));
+            }
+            if let Some(owner) = self.by_name(name).filter(|owner| *owner != info.id) {
+                return Err(RegistryError::NameTaken {
+                    name: name.to_string(),
+                    owner: owner.0,
+                });
+            }
+        }
+        self.chains.insert(info.id, info);
+        Ok(())
+    }
+
+    pub fn get(&self, id: ChainId) -> Option<&ChainInfo> {
+        self.chains.get(&id)
+    }
+
+    /// Looks `name` up among names and aliases, ignoring case and treating
+    /// spaces, dashes and underscores alike, then as a registered numeric
+    /// chain id.
+    pub fn resolve(&self, name: &str) -> Option<ChainId> {
+        self.by_name(name).or_else(|| {
+            let id = ChainId(name.trim().parse().ok()?);
+            self.chains.contains_key(&id).then_some(id)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ChainInfo> {
+        self.chains.values()
+    }
+
+    fn by_name(&self, name: &str) -> Option<ChainId> {
+        let wanted = normalize(name);
+        self.chains
+            .values()
+            .find(|info| info.names().any(|name| normalize(name) == wanted))
+            .map(|info| info.id)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+static REGISTRY: LazyLock<RwLock<Arc<ChainRegistry>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(ChainRegistry::builtin())));
+
+/// The process-wide registry used to name and parse [`ChainId`]s; the
+/// built-in chains until [`install`] replaces it.
+pub fn registry() -> Arc<ChainRegistry> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Makes `registry`, usually loaded from configuration, the process-wide
+/// one. Call it at startup, before any chain is parsed.
+pub fn install(registry: ChainRegistry) {
+    *REGISTRY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(registry);
+}
@@ -0,0 +1,125 @@
+//! Error codes and the error body shared by the backend and the feeder, so
+//! clients can handle both services alike.
+
+use crate::signing::SignatureError;
+use http::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Machine-readable error codes. The serialized names are part of the public
+/// API; never rename an existing variant. `invalid_solution` is only
+/// produced by the backend and `transfer_would_revert` only by the feeder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidChain,
+    InvalidAmount,
+    InvalidSignature,
+    SignatureExpired,
+    NonceReused,
+    Unauthorized,
+    Forbidden,
+    RateLimited,
+    NotFound,
+    Conflict,
+    TransferFailed,
+    TransferWouldRevert,
+    ChainUnavailable,
+    InvalidSolution,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::InvalidChain
+            | ErrorCode::InvalidAmount
+            | ErrorCode::SignatureExpired => StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidSignature | ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::NonceReused | ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::TransferWouldRevert => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::TransferFailed => StatusCode::BAD_GATEWAY,
+            ErrorCode::ChainUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::InvalidSolution | ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Error returned by every handler, rendered as
+/// `{"code": ..., "message": ..., "details": ...}`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl From<SignatureError> for ApiError {
+    fn from(err: SignatureError) -> Self {
+        let code = match err {
+            SignatureError::InvalidAddress(_) | SignatureError::DeadlineTooFar { .. } => {
+                ErrorCode::InvalidRequest
+            }
+            SignatureError::Malformed(_) | SignatureError::SignerMismatch { .. } => {
+                ErrorCode::InvalidSignature
+            }
+            SignatureError::Expired { .. } => ErrorCode::SignatureExpired,
+            SignatureError::NonceReused { .. } => ErrorCode::NonceReused,
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+#[cfg(feature = "axum")]
+mod response {
+    use super::{ApiError, ErrorCode};
+    use axum::Json;
+    use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+    use axum::response::{IntoResponse, Response};
+
+    impl IntoResponse for ApiError {
+        fn into_response(self) -> Response {
+            (self.code.status(), Json(self)).into_response()
+        }
+    }
+
+    impl From<JsonRejection> for ApiError {
+        fn from(rejection: JsonRejection) -> Self {
+            ApiError::new(ErrorCode::InvalidRequest, rejection.body_text())
+        }
+    }
+
+    impl From<PathRejection> for ApiError {
+        fn from(rejection: PathRejection) -> Self {
+            ApiError::new(ErrorCode::InvalidRequest, rejection.body_text())
+        }
+    }
+
+    impl From<QueryRejection> for ApiError {
+        fn from(rejection: QueryRejection) -> Self {
+            ApiError::new(ErrorCode::InvalidRequest, rejection.body_text())
+        }
+    }
+}
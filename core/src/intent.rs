@@ -0,0 +1,81 @@
+use crate::amount::Amount;
+use crate::chain::ChainId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Which side of the book an intent is on: makers post inventory, takers
+/// move their own funds.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IntentKind {
+    Maker,
+    #[default]
+    Taker,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum IntentStatus {
+    #[default]
+    PendingRisk,
+    PendingReview {
+        reason: String,
+    },
+    InOrderbook,
+    Matched,
+    /// `filled` of it has settled; the rest waits in the orderbook.
+    PartiallyFilled {
+        filled: Amount,
+    },
+    Executed,
+    Rejected {
+        reason: String,
+    },
+    /// Matched, but its solution could not be executed. Legs sent before the
+    /// failure are not unwound.
+    Failed {
+        reason: String,
+    },
+}
+
+impl IntentStatus {
+    /// No further transitions follow.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Executed | Self::Rejected { .. } | Self::Failed { .. }
+        )
+    }
+}
+
+/// A request to move `amount` from `from_chain` to `to_chain`, in the form
+/// both services read and write.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Intent {
+    pub id: Uuid,
+    #[serde(alias = "user")]
+    pub user_id: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: Amount,
+    #[serde(default)]
+    pub kind: IntentKind,
+    #[serde(default)]
+    pub status: IntentStatus,
+}
+
+impl Intent {
+    pub fn route(&self) -> Route {
+        Route {
+            from_chain: self.from_chain,
+            to_chain: self.to_chain,
+        }
+    }
+}
+
+/// Direction of an intent: funds leave `from_chain` and arrive on `to_chain`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Route {
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+}
@@ -0,0 +1,60 @@
+use crate::amount::Amount;
+use crate::chain::ChainId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One transfer of a netting solution, made entirely on `chain`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub struct Leg {
+    pub chain: ChainId,
+    #[serde(alias = "from")]
+    pub from_user: String,
+    #[serde(alias = "to")]
+    pub to_user: String,
+    pub amount: Amount,
+}
+
+/// Volume of one intent that could not be netted and is moved across chains
+/// by a bridge or liquidity pool instead. The user sends `amount` on
+/// `from_chain` and receives it, less the bridge's fees, on `to_chain`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub struct BridgeTransfer {
+    pub intent_id: Uuid,
+    pub user: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: Amount,
+    /// Name of the adapter that carries the transfer.
+    pub via: String,
+}
+
+/// Volume of one intent filled by the solver from its own inventory. The
+/// user pays `amount` on `from_chain` and is paid `amount - fee` on
+/// `to_chain`; both legs are in the solution's `txs`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub struct InventoryFill {
+    pub intent_id: Uuid,
+    pub user: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: Amount,
+    /// The solver's spread, kept on `to_chain`.
+    pub fee: Amount,
+}
+
+/// The legs that settle a set of intents against each other, plus any
+/// residual volume filled from inventory or bridged for them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct NettingSolution {
+    /// Content hash of the solution and of everything it was computed from,
+    /// as `0x`-prefixed hex. Identical inputs give identical ids.
+    #[serde(default)]
+    pub id: String,
+    pub txs: Vec<Leg>,
+    pub involved_intent_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub inventory_fills: Vec<InventoryFill>,
+    #[serde(default)]
+    pub bridged: Vec<BridgeTransfer>,
+}
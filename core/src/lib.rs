@@ -0,0 +1,15 @@
+//! Types shared by the OceanLink backend and the feeder, with the serde
+//! formats both speak on the wire.
+
+pub mod amount;
+pub mod auth;
+pub mod chain;
+pub mod error;
+pub mod intent;
+pub mod leg;
+pub mod signing;
+
+pub use amount::Amount;
+pub use chain::{ChainId, ChainInfo, ChainRegistry};
+pub use intent::{Intent, IntentKind, IntentStatus, Route};
+pub use leg::{BridgeTransfer, InventoryFill, Leg, NettingSolution};
@@ -0,0 +1,197 @@
+//! EIP-712 intent signatures. Both services verify the same message in the
+//! same domain, so one signed intent is valid for either.
+
+use crate::chain::ChainId;
+use ethers::abi::{self, Token};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Signature, U256};
+use ethers::utils::keccak256;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+pub const DOMAIN_NAME: &str = "OceanLink";
+pub const DOMAIN_VERSION: &str = "1";
+/// Chains are signed by their EVM chain id, not their display name, so
+/// renaming a chain in one service's registry breaks no signatures.
+pub const INTENT_TYPE: &str = "Intent(address user,uint256 fromChainId,uint256 toChainId,uint256 amount,uint256 nonce,uint256 deadline)";
+
+/// How far ahead a deadline may lie. Spent nonces are kept until their
+/// deadline, so this also bounds how long the registry remembers them.
+pub const MAX_DEADLINE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("invalid user address: {0}")]
+    InvalidAddress(String),
+    #[error("malformed signature: {0}")]
+    Malformed(String),
+    #[error("signature was produced by {recovered}, not {claimed}")]
+    SignerMismatch { claimed: String, recovered: String },
+    #[error("intent deadline {deadline} has passed")]
+    Expired { deadline: u64 },
+    #[error("intent deadline {deadline} is more than {window}s ahead")]
+    DeadlineTooFar { deadline: u64, window: u64 },
+    #[error("nonce {nonce} was already used by {user}")]
+    NonceReused { user: String, nonce: u64 },
+}
+
+/// Where intents are signed: the EIP-712 domain carries the `from_chain`'s
+/// id and the vault contract that holds the user's funds there, so a
+/// signature cannot be replayed against another chain or deployment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SigningDomain {
+    vaults: BTreeMap<ChainId, Address>,
+}
+
+impl SigningDomain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_vault(mut self, chain: ChainId, vault: Address) -> Self {
+        self.vaults.insert(chain, vault);
+        self
+    }
+
+    /// The vault on `chain`, or the zero address where none is configured.
+    pub fn verifying_contract(&self, chain: ChainId) -> Address {
+        self.vaults.get(&chain).copied().unwrap_or_default()
+    }
+}
+
+/// The EIP-712 `Intent` struct users sign.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntentMessage {
+    pub user: Address,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: u64,
+    pub nonce: u64,
+    pub deadline: u64,
+    /// See [`SigningDomain::verifying_contract`].
+    pub verifying_contract: Address,
+}
+
+impl Eip712 for IntentMessage {
+    type Error = Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: Some(DOMAIN_NAME.to_string()),
+            version: Some(DOMAIN_VERSION.to_string()),
+            chain_id: Some(U256::from(self.from_chain.as_u64())),
+            verifying_contract: Some(self.verifying_contract),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(INTENT_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded = abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.user),
+            Token::Uint(U256::from(self.from_chain.as_u64())),
+            Token::Uint(U256::from(self.to_chain.as_u64())),
+            Token::Uint(U256::from(self.amount)),
+            Token::Uint(U256::from(self.nonce)),
+            Token::Uint(U256::from(self.deadline)),
+        ]);
+        Ok(keccak256(encoded))
+    }
+}
+
+pub fn parse_address(value: &str) -> Result<Address, SignatureError> {
+    Address::from_str(value).map_err(|_| SignatureError::InvalidAddress(value.to_string()))
+}
+
+/// Recovers the address that signed `message`.
+pub fn recover_signer(message: &IntentMessage, signature: &str) -> Result<Address, SignatureError> {
+    let signature =
+        Signature::from_str(signature).map_err(|err| SignatureError::Malformed(err.to_string()))?;
+    let digest = match message.encode_eip712() {
+        Ok(digest) => digest,
+        Err(never) => match never {},
+    };
+    signature
+        .recover(digest)
+        .map_err(|err| SignatureError::Malformed(err.to_string()))
+}
+
+/// Checks that `signature` over `message` was produced by `message.user` and
+/// that the deadline lies between `now` and [`MAX_DEADLINE_WINDOW`] ahead.
+/// The nonce is left for [`NonceRegistry::consume`], once the caller has
+/// decided to accept the intent.
+pub fn verify_signature(
+    message: &IntentMessage,
+    signature: &str,
+    now: u64,
+) -> Result<Address, SignatureError> {
+    let recovered = recover_signer(message, signature)?;
+    if recovered != message.user {
+        return Err(SignatureError::SignerMismatch {
+            claimed: format!("{:#x}", message.user),
+            recovered: format!("{recovered:#x}"),
+        });
+    }
+    if message.deadline < now {
+        return Err(SignatureError::Expired {
+            deadline: message.deadline,
+        });
+    }
+    let window = MAX_DEADLINE_WINDOW.as_secs();
+    if message.deadline > now.saturating_add(window) {
+        return Err(SignatureError::DeadlineTooFar {
+            deadline: message.deadline,
+            window,
+        });
+    }
+    Ok(recovered)
+}
+
+/// Remembers which nonces each user has spent. Entries are dropped once their
+/// deadline passes, since an expired signature is refused regardless.
+#[derive(Default)]
+pub struct NonceRegistry {
+    used: Mutex<HashMap<(Address, u64), u64>>,
+}
+
+impl NonceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spends `nonce` for `user` until `deadline`, which
+    /// [`verify_signature`] has already bounded.
+    pub fn consume(
+        &self,
+        user: Address,
+        nonce: u64,
+        deadline: u64,
+        now: u64,
+    ) -> Result<(), SignatureError> {
+        let mut used = self.used.lock().expect("nonce registry lock poisoned");
+        used.retain(|_, expires| *expires >= now);
+        if used.contains_key(&(user, nonce)) {
+            return Err(SignatureError::NonceReused {
+                user: format!("{user:#x}"),
+                nonce,
+            });
+        }
+        used.insert((user, nonce), deadline);
+        Ok(())
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
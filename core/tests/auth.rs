@@ -0,0 +1,83 @@
+use http::{HeaderMap, HeaderValue};
+use oceanlink_core::auth::{
+    API_KEY_HEADER, AuthError, Authenticator, RateKey, RateLimit, RateLimiter, Role,
+};
+use std::time::{Duration, Instant};
+
+const LIMIT: RateLimit = RateLimit {
+    per_second: 10.0,
+    burst: 2,
+};
+
+fn key(name: &str) -> RateKey {
+    RateKey::Key(name.to_string())
+}
+
+#[test]
+fn roles_grant_themselves_and_admin_grants_everything() {
+    assert!(Role::User.grants(Role::User));
+    assert!(!Role::User.grants(Role::Maker));
+    assert!(!Role::User.grants(Role::Admin));
+    assert!(Role::Maker.grants(Role::User));
+    assert!(!Role::Maker.grants(Role::Admin));
+    assert!(Role::Admin.grants(Role::Maker));
+    assert!(Role::Admin.may_make() && Role::Maker.may_make() && !Role::User.may_make());
+}
+
+#[test]
+fn refusals_name_the_role_the_route_needs() {
+    let auth = Authenticator::new(RateLimit::default()).with_key("user-secret", Role::User);
+    let mut headers = HeaderMap::new();
+    headers.insert(API_KEY_HEADER, HeaderValue::from_static("user-secret"));
+
+    for (required, message) in [
+        (Role::Maker, "maker API key required"),
+        (Role::Admin, "admin API key required"),
+    ] {
+        let err = auth
+            .authorize(&headers, None, required)
+            .expect_err("user keys grant neither");
+        assert_eq!(err, AuthError::Forbidden(required));
+        assert_eq!(err.to_string(), message);
+    }
+}
+
+#[test]
+fn buckets_refill_at_the_sustained_rate() {
+    let limiter = RateLimiter::new(LIMIT);
+    let start = Instant::now();
+    assert_eq!(limiter.acquire(key("a"), start), Ok(()));
+    assert_eq!(limiter.acquire(key("a"), start), Ok(()));
+    let wait = limiter.acquire(key("a"), start).expect_err("burst spent");
+    assert_eq!(wait, Duration::from_millis(100));
+    assert_eq!(
+        limiter.acquire(key("b"), start),
+        Ok(()),
+        "buckets are per key"
+    );
+    assert_eq!(limiter.acquire(key("a"), start + wait), Ok(()));
+}
+
+#[test]
+fn idle_buckets_are_evicted_once_full_again() {
+    let limiter = RateLimiter::new(LIMIT);
+    let start = Instant::now();
+    for n in 0..100 {
+        limiter
+            .acquire(RateKey::Ip(Some([10, 0, 0, n].into())), start)
+            .expect("first request passes");
+    }
+    limiter.acquire(key("busy"), start).expect("first request");
+    limiter.acquire(key("busy"), start).expect("second request");
+    assert_eq!(limiter.tracked(), 101);
+
+    // A bucket fills up in 200ms. The busy caller drains its bucket again
+    // at 150ms, so at 250ms only it is still refilling.
+    limiter
+        .acquire(key("busy"), start + Duration::from_millis(150))
+        .expect("refilled one token");
+    limiter
+        .acquire(key("new"), start + Duration::from_millis(250))
+        .expect("first request");
+    assert_eq!(limiter.tracked(), 2, "only the busy and new callers remain");
+}
@@ -0,0 +1,82 @@
+use oceanlink_core::chain::{self, ChainId, ChainInfo, ChainRegistry, RegistryError};
+
+#[test]
+fn chains_are_written_by_name_and_read_by_name_alias_or_id() {
+    assert_eq!(
+        serde_json::to_string(&ChainId::BASE_SEPOLIA).unwrap(),
+        r#""Base Sepolia""#
+    );
+    for input in [
+        r#""Base Sepolia""#,
+        r#""base-sepolia""#,
+        r#""BASE_SEPOLIA""#,
+        r#""84532""#,
+        "84532",
+    ] {
+        let chain: ChainId = serde_json::from_str(input).unwrap();
+        assert_eq!(chain, ChainId::BASE_SEPOLIA, "{input}");
+    }
+    assert_eq!("arbitrum one".parse(), Ok(ChainId::ARBITRUM));
+
+    let err = serde_json::from_str::<ChainId>(r#""solana""#).unwrap_err();
+    assert!(err.to_string().contains("invalid chain 'solana'"), "{err}");
+    assert!(serde_json::from_str::<ChainId>("999").is_err());
+}
+
+#[test]
+fn names_cannot_be_shared_between_chains() {
+    let mut registry = ChainRegistry::builtin();
+    let nova = ChainId::new(42_170);
+
+    let err = registry
+        .insert(ChainInfo::new(nova, "Arbitrum Nova").with_alias("arbitrum"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RegistryError::NameTaken {
+            name: "arbitrum".into(),
+            owner: 42_161,
+        }
+    );
+    assert_eq!(
+        registry.insert(ChainInfo::new(nova, "42170")),
+        Err(RegistryError::InvalidName("42170".into()))
+    );
+
+    registry
+        .insert(ChainInfo::new(nova, "Arbitrum Nova").with_alias("nova"))
+        .unwrap();
+    assert_eq!(registry.resolve("Nova"), Some(nova));
+    assert_eq!(registry.resolve("42170"), Some(nova));
+
+    // Re-inserting an id replaces its entry.
+    let base = registry.get(ChainId::BASE).unwrap().clone();
+    registry
+        .insert(
+            base.with_rpc_url("https://base.example.com")
+                .with_finality_depth(3),
+        )
+        .unwrap();
+    let base = registry.get(ChainId::BASE).unwrap();
+    assert_eq!(base.rpc_url.as_deref(), Some("https://base.example.com"));
+    assert_eq!(base.finality_depth, 3);
+}
+
+#[test]
+fn installed_registries_name_new_chains() {
+    let zora = ChainId::new(7_777_777);
+    assert_eq!(zora.to_string(), "7777777", "unknown chains print their id");
+
+    let mut registry = ChainRegistry::builtin();
+    registry
+        .insert(ChainInfo::new(zora, "Zora").with_explorer_url("https://explorer.zora.energy/"))
+        .unwrap();
+    chain::install(registry);
+
+    assert_eq!(zora.to_string(), "Zora");
+    assert_eq!("zora".parse(), Ok(zora));
+    assert_eq!(
+        zora.info().and_then(|info| info.tx_url("0x01")).as_deref(),
+        Some("https://explorer.zora.energy/tx/0x01")
+    );
+}
@@ -0,0 +1,32 @@
+use http::StatusCode;
+use oceanlink_core::error::ErrorCode;
+
+#[test]
+fn codes_keep_their_wire_names_and_statuses() {
+    for (code, name, status) in [
+        (
+            ErrorCode::InvalidRequest,
+            "invalid_request",
+            StatusCode::BAD_REQUEST,
+        ),
+        (ErrorCode::NonceReused, "nonce_reused", StatusCode::CONFLICT),
+        (
+            ErrorCode::RateLimited,
+            "rate_limited",
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        (
+            ErrorCode::TransferWouldRevert,
+            "transfer_would_revert",
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            ErrorCode::InvalidSolution,
+            "invalid_solution",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    ] {
+        assert_eq!(serde_json::to_value(code).unwrap(), name);
+        assert_eq!(code.status(), status, "{name}");
+    }
+}
@@ -0,0 +1,80 @@
+use oceanlink_core::{Amount, ChainId, Intent, IntentKind, IntentStatus, Leg};
+use uuid::Uuid;
+
+#[test]
+fn amounts_are_read_from_integers_or_digit_strings() {
+    assert_eq!(
+        serde_json::from_str::<Amount>("1000000").unwrap(),
+        1_000_000
+    );
+    assert_eq!(
+        serde_json::from_str::<Amount>(r#""18446744073709551615""#).unwrap(),
+        u64::MAX
+    );
+    assert_eq!(serde_json::to_string(&Amount::new(5)).unwrap(), "5");
+
+    for input in [
+        "-1",
+        r#""-1""#,
+        r#""1e6""#,
+        r#""""#,
+        "1.5",
+        r#""18446744073709551616""#,
+    ] {
+        assert!(serde_json::from_str::<Amount>(input).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn feeder_intents_and_legs_read_with_their_old_field_names() {
+    let id = Uuid::new_v4();
+    let intent: Intent = serde_json::from_value(serde_json::json!({
+        "id": id,
+        "user": "0xabc",
+        "from_chain": "Sepolia",
+        "to_chain": "Base Sepolia",
+        "amount": 250_000,
+    }))
+    .unwrap();
+    assert_eq!(intent.user_id, "0xabc");
+    assert_eq!(intent.route().to_chain, ChainId::BASE_SEPOLIA);
+    assert_eq!(intent.kind, IntentKind::Taker);
+    assert_eq!(intent.status, IntentStatus::PendingRisk);
+
+    let leg: Leg = serde_json::from_value(serde_json::json!({
+        "chain": "base-sepolia",
+        "from": "0xabc",
+        "to": "0xdef",
+        "amount": "250000",
+    }))
+    .unwrap();
+    assert_eq!(
+        leg,
+        Leg {
+            chain: ChainId::BASE_SEPOLIA,
+            from_user: "0xabc".into(),
+            to_user: "0xdef".into(),
+            amount: Amount::new(250_000),
+        }
+    );
+}
+
+#[test]
+fn intents_round_trip_in_the_backend_format() {
+    let intent = Intent {
+        id: Uuid::new_v4(),
+        user_id: "maker-1".into(),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: Amount::new(42),
+        kind: IntentKind::Maker,
+        status: IntentStatus::Rejected {
+            reason: "limit".into(),
+        },
+    };
+    let json = serde_json::to_value(&intent).unwrap();
+    assert_eq!(json["user_id"], "maker-1");
+    assert_eq!(json["from_chain"], "Base");
+    assert_eq!(json["kind"], "maker");
+    assert_eq!(serde_json::from_value::<Intent>(json).unwrap(), intent);
+}
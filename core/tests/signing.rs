@@ -0,0 +1,27 @@
+use ethers::types::Address;
+use ethers::types::transaction::eip712::Eip712;
+use oceanlink_core::chain::{self, ChainId, ChainInfo, ChainRegistry};
+use oceanlink_core::signing::IntentMessage;
+
+#[test]
+fn renaming_a_chain_keeps_signed_digests() {
+    let message = IntentMessage {
+        user: Address::repeat_byte(0x11),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 1_000,
+        nonce: 7,
+        deadline: 1_767_225_600,
+        verifying_contract: Address::zero(),
+    };
+    let before = message.encode_eip712().unwrap();
+
+    let mut registry = ChainRegistry::builtin();
+    registry
+        .insert(ChainInfo::new(ChainId::BASE, "Base Mainnet"))
+        .unwrap();
+    chain::install(registry);
+    assert_eq!(ChainId::BASE.to_string(), "Base Mainnet");
+
+    assert_eq!(message.encode_eip712().unwrap(), before);
+}
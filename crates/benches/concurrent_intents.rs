@@ -0,0 +1,97 @@
+//! Measures `create_intent` throughput while a match round is executing.
+//!
+//! Run with `cargo bench -p ocean-link-backend --bench concurrent_intents`.
+
+use ocean_link_backend::app::{AppContext, CreateIntentRequest};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::models::{ChainId, IntentKind};
+use ocean_link_backend::risk::{AlwaysPassRiskEngine, RiskEngine};
+use ocean_link_backend::state::State;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const MATCHED_PAIRS: usize = 50;
+const LEG_LATENCY: Duration = Duration::from_millis(5);
+const WRITERS: usize = 16;
+const INTENTS_PER_WRITER: usize = 500;
+
+fn request(user: String, from_chain: ChainId, to_chain: ChainId) -> CreateIntentRequest {
+    CreateIntentRequest {
+        user_id: user,
+        from_chain,
+        to_chain,
+        amount: 1_000,
+        kind: IntentKind::Taker,
+    }
+}
+
+fn context() -> AppContext {
+    let state = Arc::new(State::new());
+    let risk_engine: Arc<dyn RiskEngine + Send + Sync> = Arc::new(AlwaysPassRiskEngine);
+    let blockchain = MockBlockchainClient::with_latency(LEG_LATENCY);
+    for pair in 0..MATCHED_PAIRS {
+        blockchain.set_balance(ChainId::BASE, &format!("base-{pair}"), 1_000);
+        blockchain.set_balance(ChainId::ARBITRUM, &format!("arb-{pair}"), 1_000);
+    }
+    let executor = Arc::new(Executor::new(blockchain));
+    AppContext::new(state, risk_engine, executor)
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let ctx = context();
+    for pair in 0..MATCHED_PAIRS {
+        for req in [
+            request(format!("base-{pair}"), ChainId::BASE, ChainId::ARBITRUM),
+            request(format!("arb-{pair}"), ChainId::ARBITRUM, ChainId::BASE),
+        ] {
+            let intent = ctx.create_intent(req).await;
+            ctx.wait_for_risk(intent.id).await;
+        }
+    }
+
+    let round_ctx = ctx.clone();
+    let round_started = Instant::now();
+    let round = tokio::spawn(async move {
+        round_ctx
+            .match_and_execute()
+            .await
+            .expect("round should succeed");
+        round_started.elapsed()
+    });
+
+    let intake_started = Instant::now();
+    let writers: Vec<_> = (0..WRITERS)
+        .map(|writer| {
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                for n in 0..INTENTS_PER_WRITER {
+                    // Same-direction flow so the new intents cannot join
+                    // the running round.
+                    ctx.create_intent(request(
+                        format!("writer-{writer}-{n}"),
+                        ChainId::BASE,
+                        ChainId::ARBITRUM,
+                    ))
+                    .await;
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.await.expect("writer task panicked");
+    }
+    let intake = intake_started.elapsed();
+    let round = round.await.expect("round task panicked");
+
+    let total = WRITERS * INTENTS_PER_WRITER;
+    println!(
+        "created {total} intents in {intake:?} ({:.0} intents/s) while a {}-leg round took {round:?}",
+        total as f64 / intake.as_secs_f64(),
+        MATCHED_PAIRS * 2,
+    );
+    if intake < round {
+        println!("intake finished before the round: create_intent did not wait on execution");
+    }
+}
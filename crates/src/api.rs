@@ -1,46 +1,601 @@
-use crate::app::{AppContext, CreateIntentRequest};
-use crate::models::{Intent, NettingSolution};
+use crate::app::AppContext;
+use crate::auth::{API_KEY_HEADER, ApiKey, require_admin, require_user};
+use crate::error::{
+    ApiError, ApiJson, ApiPath, ApiQuery, ErrorCode, parse_served_chain, require_distinct_chains,
+    require_positive_amount,
+};
+use crate::events::{Event, EventFilter};
+use crate::health::{self, Check, Readiness};
+use crate::inventory::ChainInventory;
+use crate::models::{
+    BridgeTransfer, ChainId, Intent, IntentKind, IntentStatus, Leg, NettingSolution, Route,
+};
+use crate::quote::Quote;
+use crate::signing::{SignedIntent, SignedIntentRequest};
+use crate::simulation::{BalanceDelta, ResidualView, SimulatedLeg, Simulation};
+use crate::webhooks::{DeadLetter, Webhook, WebhookCreated, WebhookDispatcher, WebhookPayload};
 use axum::{
     Json, Router,
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    routing::{get, post},
+    extract::{Extension, State},
+    http::{StatusCode, header},
+    middleware,
+    response::{
+        IntoResponse, Response,
+        sse::{self, KeepAlive, Sse},
+    },
+    routing::{delete, get, post},
 };
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::{Stream, StreamExt};
+use tower_http::trace::TraceLayer;
+use utoipa::openapi::security::{self, ApiKeyValue, SecurityScheme};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
+use uuid::Uuid;
+
+/// OpenAPI description of [`router`], served at `/openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "OceanLink backend"),
+    paths(
+        create_intent,
+        list_intents,
+        get_solution,
+        get_quote,
+        stream_events,
+        register_webhook,
+        list_webhooks,
+        delete_webhook,
+        match_and_execute,
+        simulate_match,
+        list_reviews,
+        approve_intent,
+        reject_intent,
+        list_dead_letters,
+        get_inventory,
+        metrics,
+        healthz,
+        readyz
+    ),
+    components(schemas(
+        ApiError,
+        BalanceDelta,
+        BridgeTransfer,
+        ChainId,
+        ChainInventory,
+        Check,
+        DeadLetter,
+        ErrorCode,
+        Event,
+        Intent,
+        IntentKind,
+        IntentStatus,
+        Leg,
+        NettingSolution,
+        Quote,
+        Readiness,
+        RejectIntentRequest,
+        RegisterWebhookRequest,
+        ResidualView,
+        SignedIntentRequest,
+        SimulateRequest,
+        SimulatedIntent,
+        SimulatedLeg,
+        Simulation,
+        Webhook,
+        WebhookCreated,
+        WebhookPayload
+    )),
+    modifiers(&ApiKeyAuth),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(security::ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
 
+/// Builds the HTTP API. Every route except `/openapi.json`, `/healthz` and
+/// `/readyz` needs an API key; matching, review and operational routes need
+/// an admin key.
 pub fn router(ctx: AppContext) -> Router {
-    Router::new()
+    let user_routes = Router::new()
         .route("/intents", post(create_intent).get(list_intents))
         .route("/solutions", get(get_solution))
+        .route("/quote", get(get_quote))
+        .route("/events", get(stream_events))
+        .route("/webhooks", post(register_webhook).get(list_webhooks))
+        .route("/webhooks/:id", delete(delete_webhook))
+        .route_layer(middleware::from_fn_with_state(ctx.clone(), require_user));
+
+    let admin_routes = Router::new()
         .route("/match-and-execute", post(match_and_execute))
+        .route("/match/simulate", post(simulate_match))
+        .route("/admin/reviews", get(list_reviews))
+        .route("/admin/intents/:id/approve", post(approve_intent))
+        .route("/admin/intents/:id/reject", post(reject_intent))
+        .route("/admin/webhooks/dead-letters", get(list_dead_letters))
+        .route("/admin/inventory", get(get_inventory))
+        .route("/metrics", get(metrics))
+        .route_layer(middleware::from_fn_with_state(ctx.clone(), require_admin));
+
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .merge(user_routes)
+        .merge(admin_routes)
+        .layer(TraceLayer::new_for_http())
         .with_state(ctx)
 }
 
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Submits a signed intent. Risk checks run in the background, so the intent
+/// is returned as `PendingRisk`.
+#[utoipa::path(
+    post,
+    path = "/intents",
+    request_body = SignedIntentRequest,
+    responses(
+        (status = 202, description = "Intent accepted for risk checks", body = Intent),
+        (status = 400, description = "Invalid chain, amount or quote, or expired signature", body = ApiError),
+        (status = 401, description = "Missing API key or bad signature", body = ApiError),
+        (status = 403, description = "Maker intent without a maker API key", body = ApiError),
+        (status = 409, description = "Nonce already used", body = ApiError)
+    )
+)]
 async fn create_intent(
     State(ctx): State<AppContext>,
-    Json(payload): Json<CreateIntentRequest>,
-) -> impl IntoResponse {
-    let intent = ctx.create_intent(payload).await;
-    (StatusCode::CREATED, Json(intent))
+    Extension(key): Extension<ApiKey>,
+    ApiJson(mut payload): ApiJson<SignedIntentRequest>,
+) -> Result<(StatusCode, Json<Intent>), ApiError> {
+    let quote = payload.quote.take();
+    let signed = validate_intent(payload, ctx.chains())?;
+    if signed.kind == IntentKind::Maker && !key.role.may_make() {
+        return Err(ApiError::new(
+            ErrorCode::Forbidden,
+            "maker intents need a maker API key",
+        ));
+    }
+    // Checked before the signature so a stale quote leaves the nonce unspent.
+    if let Some(quote) = &quote {
+        let route = Route {
+            from_chain: signed.from_chain,
+            to_chain: signed.to_chain,
+        };
+        ctx.verify_quote(quote, route, signed.amount.into())?;
+    }
+    let request = ctx.verify_signed_intent(signed)?;
+    let intent = ctx.submit_intent(&key, request).await;
+    Ok((StatusCode::ACCEPTED, Json(intent)))
+}
+
+fn validate_intent(req: SignedIntentRequest, served: &[ChainId]) -> Result<SignedIntent, ApiError> {
+    let from_chain = parse_served_chain("from_chain", &req.from_chain, served)?;
+    let to_chain = parse_served_chain("to_chain", &req.to_chain, served)?;
+    require_distinct_chains(from_chain, to_chain)?;
+    require_positive_amount("amount", req.amount)?;
+    Ok(SignedIntent {
+        user_id: req.user_id,
+        from_chain,
+        to_chain,
+        amount: req.amount,
+        nonce: req.nonce,
+        deadline: req.deadline,
+        signature: req.signature,
+        kind: req.kind,
+    })
 }
 
-async fn list_intents(State(ctx): State<AppContext>) -> impl IntoResponse {
-    let intents = ctx.list_intents().await;
+/// Admin keys list every intent; other keys only those they submitted.
+#[utoipa::path(
+    get,
+    path = "/intents",
+    responses((status = 200, description = "Intents visible to the calling API key", body = Vec<Intent>))
+)]
+async fn list_intents(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+) -> impl IntoResponse {
+    let intents = ctx.intents_visible_to(&key).await;
     Json(intents)
 }
 
-async fn get_solution(State(ctx): State<AppContext>) -> impl IntoResponse {
-    let solution = ctx.last_solution().await;
+/// Admin keys get the most recent solution. Other keys get the most recent
+/// one settling an intent they submitted, with only their intents, legs,
+/// fills and bridge transfers in it.
+#[utoipa::path(
+    get,
+    path = "/solutions",
+    responses((status = 200, description = "Most recent netting solution visible to the calling API key", body = Option<NettingSolution>))
+)]
+async fn get_solution(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+) -> impl IntoResponse {
+    let solution = ctx.last_solution_visible_to(&key).await;
     Json(solution)
 }
 
-async fn match_and_execute(State(ctx): State<AppContext>) -> Response {
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QuoteQuery {
+    #[param(example = "base")]
+    pub from_chain: String,
+    #[param(example = "arbitrum")]
+    pub to_chain: String,
+    pub amount: u64,
+}
+
+/// Estimates what an intent would get if submitted now: the amount the
+/// orderbook nets straight away, the inventory fill and its fee, whether
+/// the rest would be bridged, and the expected wait. The quote is signed
+/// and expires after a short window.
+#[utoipa::path(
+    get,
+    path = "/quote",
+    params(QuoteQuery),
+    responses(
+        (status = 200, description = "Signed quote", body = Quote),
+        (status = 400, description = "Invalid chain or amount", body = ApiError)
+    )
+)]
+async fn get_quote(
+    State(ctx): State<AppContext>,
+    ApiQuery(query): ApiQuery<QuoteQuery>,
+) -> Result<Json<Quote>, ApiError> {
+    let route = Route {
+        from_chain: parse_served_chain("from_chain", &query.from_chain, ctx.chains())?,
+        to_chain: parse_served_chain("to_chain", &query.to_chain, ctx.chains())?,
+    };
+    require_positive_amount("amount", query.amount)?;
+    Ok(Json(ctx.quote(route, query.amount.into()).await))
+}
+
+/// Server-sent stream of [`Event`]s, optionally filtered by user and intent.
+/// Admin keys see every event; other keys only events about intents they
+/// submitted, without other users' intents and legs. Each message's `event:` field is the event type and its data the JSON
+/// event. A `Lagged` message carries the number of events a slow client
+/// missed.
+#[utoipa::path(
+    get,
+    path = "/events",
+    params(EventFilter),
+    responses(
+        (status = 200, description = "Event stream", content_type = "text/event-stream", body = Event),
+        (status = 400, description = "Malformed filter", body = ApiError)
+    )
+)]
+async fn stream_events(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+    ApiQuery(filter): ApiQuery<EventFilter>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let events = ctx.events().subscribe();
+    let stream = BroadcastStream::new(events).filter_map(move |received| match received {
+        Ok(event) if filter.matches(&event) => ctx
+            .visible_to(&key, &event)
+            .and_then(|event| sse_event(&event))
+            .map(Ok),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(missed)) => Some(Ok(sse::Event::default()
+            .event("Lagged")
+            .data(missed.to_string()))),
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn sse_event(event: &Event) -> Option<sse::Event> {
+    sse::Event::default()
+        .event(event.name())
+        .json_data(event)
+        .ok()
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    /// Absolute http(s) url that receives `POST`ed [`WebhookPayload`]s.
+    pub url: String,
+}
+
+fn webhooks(ctx: &AppContext) -> Result<&WebhookDispatcher, ApiError> {
+    ctx.webhooks()
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "webhooks are not enabled"))
+}
+
+/// Registers a webhook for intents submitted with the calling API key. Each
+/// delivery is signed with the returned secret in `x-oceanlink-signature`.
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = WebhookCreated),
+        (status = 400, description = "Invalid url, or one on a local or private host", body = ApiError)
+    )
+)]
+async fn register_webhook(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+    ApiJson(payload): ApiJson<RegisterWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookCreated>), ApiError> {
+    let created = webhooks(&ctx)?.register(&key.id, &payload.url)?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses((status = 200, description = "Webhooks of the calling API key", body = Vec<Webhook>))
+)]
+async fn list_webhooks(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    Ok(Json(webhooks(&ctx)?.list(&key.id)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(("id" = Uuid, Path, description = "Webhook id")),
+    responses(
+        (status = 204, description = "Webhook removed"),
+        (status = 404, description = "No such webhook for this API key", body = ApiError)
+    )
+)]
+async fn delete_webhook(
+    State(ctx): State<AppContext>,
+    Extension(key): Extension<ApiKey>,
+    ApiPath(id): ApiPath<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    webhooks(&ctx)?.remove(&key.id, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Prometheus metrics in the text exposition format. Scrapers authenticate
+/// with `Authorization: Bearer <admin key>`.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Metrics", content_type = "text/plain", body = String),
+        (status = 403, description = "Admin key required", body = ApiError)
+    )
+)]
+async fn metrics(State(ctx): State<AppContext>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        ctx.metrics().render(&ctx.state),
+    )
+}
+
+/// Liveness: answers as long as the process serves requests.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    security(()),
+    responses((status = 200, description = "Process is alive"))
+)]
+async fn healthz() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Readiness: checks the store, every chain's RPC endpoint, chain id and
+/// signer, and the scheduler heartbeat. Failures only carry a generic
+/// reason; the details are logged.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    security(()),
+    responses(
+        (status = 200, description = "Ready for traffic", body = Readiness),
+        (status = 503, description = "A dependency check failed", body = Readiness)
+    )
+)]
+async fn readyz(State(ctx): State<AppContext>) -> (StatusCode, Json<Readiness>) {
+    let readiness = health::readiness(&ctx).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+/// Deliveries that exhausted their retries.
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/dead-letters",
+    responses(
+        (status = 200, description = "Failed deliveries", body = Vec<DeadLetter>),
+        (status = 403, description = "Admin key required", body = ApiError)
+    )
+)]
+async fn list_dead_letters(
+    State(ctx): State<AppContext>,
+) -> Result<Json<Vec<DeadLetter>>, ApiError> {
+    Ok(Json(webhooks(&ctx)?.dead_letters()))
+}
+
+/// The solver's inventory on each chain it tracks, against its targets.
+#[utoipa::path(
+    get,
+    path = "/admin/inventory",
+    responses(
+        (status = 200, description = "Inventory per chain", body = Vec<ChainInventory>),
+        (status = 403, description = "Admin key required", body = ApiError),
+        (status = 404, description = "Inventory mode is off", body = ApiError),
+        (status = 503, description = "A balance could not be read", body = ApiError)
+    )
+)]
+async fn get_inventory(
+    State(ctx): State<AppContext>,
+) -> Result<Json<Vec<ChainInventory>>, ApiError> {
+    ctx.inventory()
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "inventory mode is off"))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RejectIntentRequest {
+    pub reason: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/reviews",
+    responses(
+        (status = 200, description = "Intents held for manual review", body = Vec<Intent>),
+        (status = 403, description = "Admin key required", body = ApiError)
+    )
+)]
+async fn list_reviews(State(ctx): State<AppContext>) -> impl IntoResponse {
+    Json(ctx.pending_reviews().await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/intents/{id}/approve",
+    params(("id" = Uuid, Path, description = "Intent id")),
+    responses(
+        (status = 200, description = "Intent moved to the orderbook", body = Intent),
+        (status = 404, description = "Unknown intent", body = ApiError),
+        (status = 409, description = "Intent is not under review", body = ApiError)
+    )
+)]
+async fn approve_intent(
+    State(ctx): State<AppContext>,
+    ApiPath(id): ApiPath<Uuid>,
+) -> Result<Json<Intent>, ApiError> {
+    Ok(Json(ctx.approve_intent(id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/intents/{id}/reject",
+    params(("id" = Uuid, Path, description = "Intent id")),
+    request_body = RejectIntentRequest,
+    responses(
+        (status = 200, description = "Intent rejected", body = Intent),
+        (status = 404, description = "Unknown intent", body = ApiError),
+        (status = 409, description = "Intent is not under review", body = ApiError)
+    )
+)]
+async fn reject_intent(
+    State(ctx): State<AppContext>,
+    ApiPath(id): ApiPath<Uuid>,
+    ApiJson(payload): ApiJson<RejectIntentRequest>,
+) -> Result<Json<Intent>, ApiError> {
+    Ok(Json(ctx.reject_intent(id, payload.reason).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/match-and-execute",
+    responses(
+        (status = 200, description = "Solution computed and executed", body = NettingSolution),
+        (status = 204, description = "Nothing to match"),
+        (status = 500, description = "The solution broke an invariant and was not sent", body = ApiError),
+        (status = 502, description = "A transfer failed", body = ApiError)
+    )
+)]
+async fn match_and_execute(State(ctx): State<AppContext>) -> Result<Response, ApiError> {
     match ctx.match_and_execute().await {
-        Ok(Some(solution)) => (StatusCode::OK, Json(solution)).into_response(),
-        Ok(None) => StatusCode::NO_CONTENT.into_response(),
+        Ok(Some(solution)) => Ok((StatusCode::OK, Json(solution)).into_response()),
+        Ok(None) => Ok(StatusCode::NO_CONTENT.into_response()),
         Err(err) => {
-            eprintln!("match_and_execute error: {err:?}");
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            tracing::error!(error = %err, "match round failed");
+            Err(err.into())
         }
     }
 }
+
+/// An intent to simulate matching with. It needs no signature and is never
+/// stored.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SimulatedIntent {
+    /// Made up when missing.
+    #[serde(default)]
+    pub id: Option<Uuid>,
+    pub user_id: String,
+    #[schema(example = "base")]
+    pub from_chain: String,
+    #[schema(example = "arbitrum")]
+    pub to_chain: String,
+    pub amount: u64,
+    #[serde(default)]
+    pub kind: IntentKind,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SimulateRequest {
+    /// Orderbook to match, oldest first. The current orderbook when missing.
+    #[serde(default)]
+    pub intents: Option<Vec<SimulatedIntent>>,
+}
+
+fn simulated_intents(
+    intents: Vec<SimulatedIntent>,
+    served: &[ChainId],
+) -> Result<Vec<Intent>, ApiError> {
+    intents
+        .into_iter()
+        .enumerate()
+        .map(|(i, req)| {
+            let from_chain =
+                parse_served_chain(&format!("intents[{i}].from_chain"), &req.from_chain, served)?;
+            let to_chain =
+                parse_served_chain(&format!("intents[{i}].to_chain"), &req.to_chain, served)?;
+            require_positive_amount(&format!("intents[{i}].amount"), req.amount)?;
+            Ok(Intent {
+                id: req.id.unwrap_or_else(Uuid::new_v4),
+                user_id: req.user_id,
+                from_chain,
+                to_chain,
+                amount: req.amount.into(),
+                kind: req.kind,
+                status: IntentStatus::InOrderbook,
+            })
+        })
+        .collect()
+}
+
+/// Computes the solution for the orderbook, or for the intents supplied, and
+/// plays its legs against the current balances. Nothing is sent and no state
+/// changes.
+#[utoipa::path(
+    post,
+    path = "/match/simulate",
+    request_body = SimulateRequest,
+    responses(
+        (status = 200, description = "What a round would do", body = Simulation),
+        (status = 400, description = "Invalid chain or amount", body = ApiError),
+        (status = 403, description = "Admin key required", body = ApiError),
+        (status = 503, description = "A balance could not be read", body = ApiError)
+    )
+)]
+async fn simulate_match(
+    State(ctx): State<AppContext>,
+    ApiJson(payload): ApiJson<SimulateRequest>,
+) -> Result<Json<Simulation>, ApiError> {
+    let intents = payload
+        .intents
+        .map(|intents| simulated_intents(intents, ctx.chains()))
+        .transpose()?;
+    Ok(Json(ctx.simulate(intents).await?))
+}
@@ -1,19 +1,62 @@
-use crate::blockchain::MockBlockchainClient;
-use crate::executor::{ExecutionError, Executor};
-use crate::models::{Intent, IntentStatus, NettingSolution};
-use crate::risk::RiskEngine;
-use crate::solver::compute_solution;
+use crate::auth::{ApiKey, Authenticator, Role};
+use crate::blockchain::{BlockchainError, ChainProbe, MockBlockchainClient};
+use crate::bridge::Rebalancer;
+use crate::events::{Event, EventBus};
+use crate::executor::{ExecutionError, Executor, LegProgress};
+use crate::invariants::check_solution;
+use crate::inventory::{ChainInventory, Inventory};
+use crate::metrics::Metrics;
+use crate::models::{
+    Amount, ChainId, Intent, IntentKind, IntentStatus, Leg, NettingSolution, Route,
+};
+use crate::quote::{DEFAULT_QUOTE_TTL, Quote, QuoteError, Quoter};
+use crate::risk::{RiskDecision, RiskEngine};
+use crate::scheduler::Heartbeat;
+use crate::signing::{self, NonceRegistry, SignatureError, SignedIntent, SigningDomain, unix_now};
+use crate::simulation::{Simulation, simulate};
+use crate::solver::{ReplayError, SolverSnapshot, compute_netting, replay, solve, unsettled};
 use crate::state::SharedState;
+use crate::webhooks::WebhookDispatcher;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
+use thiserror::Error;
+use tokio::sync::{Mutex, Notify};
+use tracing::{Instrument, error, field, info, info_span, warn};
 use uuid::Uuid;
 
+/// Chains served when none are configured.
+pub const DEFAULT_CHAINS: [ChainId; 2] = [ChainId::BASE, ChainId::ARBITRUM];
+
 #[derive(Clone)]
 pub struct AppContext {
     pub state: SharedState,
     pub risk_engine: Arc<dyn RiskEngine + Send + Sync>,
     pub executor: Arc<Executor<MockBlockchainClient>>,
+    pub auth: Arc<Authenticator>,
+    rebalancer: Option<Arc<Rebalancer>>,
+    inventory: Option<Arc<Inventory>>,
+    quoter: Arc<Quoter>,
+    nonces: Arc<NonceRegistry>,
+    signing: Arc<SigningDomain>,
+    round_lock: Arc<Mutex<()>>,
+    orderbook_changed: Arc<Notify>,
+    risk_settled: Arc<Notify>,
+    events: EventBus,
+    webhooks: Option<WebhookDispatcher>,
+    metrics: Arc<Metrics>,
+    heartbeat: Arc<Heartbeat>,
+    chains: Arc<Vec<ChainId>>,
+    probe: Arc<dyn ChainProbe>,
+}
+
+#[derive(Debug, Error)]
+pub enum ReviewError {
+    #[error("intent {0} not found")]
+    NotFound(Uuid),
+    #[error("intent {id} is not awaiting review (status: {status:?})")]
+    NotUnderReview { id: Uuid, status: IntentStatus },
 }
 
 impl AppContext {
@@ -22,90 +65,761 @@ impl AppContext {
         risk_engine: Arc<dyn RiskEngine + Send + Sync>,
         executor: Arc<Executor<MockBlockchainClient>>,
     ) -> Self {
+        let events = EventBus::default();
+        state.add_status_listener(Arc::new(events.clone()));
         Self {
             state,
             risk_engine,
+            auth: Arc::new(Authenticator::default()),
+            rebalancer: None,
+            inventory: None,
+            quoter: Arc::new(Quoter::new(
+                Uuid::new_v4().simple().to_string(),
+                DEFAULT_QUOTE_TTL,
+            )),
+            nonces: Arc::new(NonceRegistry::new()),
+            signing: Arc::new(SigningDomain::new()),
+            round_lock: Arc::new(Mutex::new(())),
+            orderbook_changed: Arc::new(Notify::new()),
+            risk_settled: Arc::new(Notify::new()),
+            events,
+            webhooks: None,
+            metrics: Arc::new(Metrics::new()),
+            heartbeat: Arc::new(Heartbeat::default()),
+            chains: Arc::new(DEFAULT_CHAINS.to_vec()),
+            probe: Arc::new(executor.client()),
             executor,
         }
     }
 
+    /// Chains this backend settles on, usually the configured ones. Intents
+    /// on other registry chains are rejected.
+    pub fn with_chains(mut self, chains: impl IntoIterator<Item = ChainId>) -> Self {
+        self.chains = Arc::new(chains.into_iter().collect());
+        self
+    }
+
+    pub fn chains(&self) -> &[ChainId] {
+        &self.chains
+    }
+
+    /// Checks the served chains for readiness in place of the executor's
+    /// client, e.g. an [`crate::blockchain::RpcProbe`] over the configured
+    /// endpoints and keys.
+    pub fn with_chain_probe(mut self, probe: impl ChainProbe + 'static) -> Self {
+        self.probe = Arc::new(probe);
+        self
+    }
+
+    pub fn chain_probe(&self) -> &dyn ChainProbe {
+        self.probe.as_ref()
+    }
+
+    pub fn with_authenticator(mut self, auth: Authenticator) -> Self {
+        self.auth = Arc::new(auth);
+        self
+    }
+
+    /// Bridges volume that netting leaves over, see [`Rebalancer`].
+    pub fn with_rebalancer(mut self, rebalancer: Rebalancer) -> Self {
+        self.rebalancer = Some(Arc::new(rebalancer));
+        self
+    }
+
+    /// Fills intents netting cannot settle from the solver's own funds,
+    /// before any are bridged.
+    pub fn with_inventory(mut self, inventory: Inventory) -> Self {
+        self.inventory = Some(Arc::new(inventory));
+        self
+    }
+
+    /// Binds intent signatures to the configured vaults, see
+    /// [`SigningDomain`].
+    pub fn with_signing_domain(mut self, domain: SigningDomain) -> Self {
+        self.signing = Arc::new(domain);
+        self
+    }
+
+    /// Signs quotes with a shared secret instead of one made up at startup,
+    /// so other instances can verify them.
+    pub fn with_quoter(mut self, quoter: Quoter) -> Self {
+        self.quoter = Arc::new(quoter);
+        self
+    }
+
+    /// The solver's inventory on each tracked chain, if it keeps one.
+    pub async fn inventory(&self) -> Result<Option<Vec<ChainInventory>>, BlockchainError> {
+        let Some(inventory) = &self.inventory else {
+            return Ok(None);
+        };
+        let balances = inventory.balances(&self.executor.client()).await?;
+        let report = inventory.report(&balances);
+        self.metrics.record_inventory(&report);
+        Ok(Some(report))
+    }
+
+    /// Updates the inventory gauges and warns about chains a round has just
+    /// depleted.
+    async fn record_inventory(&self, inventory: &Inventory, before: &BTreeMap<ChainId, Amount>) {
+        let after = match inventory.balances(&self.executor.client()).await {
+            Ok(after) => after,
+            Err(err) => {
+                warn!(error = %err, "solver inventory unavailable");
+                return;
+            }
+        };
+        let was_depleted: HashSet<ChainId> = inventory
+            .report(before)
+            .into_iter()
+            .filter(|chain| chain.depleted)
+            .map(|chain| chain.chain)
+            .collect();
+        let report = inventory.report(&after);
+        for chain in report.iter().filter(|chain| chain.depleted) {
+            if !was_depleted.contains(&chain.chain) {
+                warn!(
+                    chain = %chain.chain,
+                    balance = chain.balance.get(),
+                    min_balance = chain.min_balance.get(),
+                    "solver inventory depleted"
+                );
+            }
+        }
+        self.metrics.record_inventory(&report);
+    }
+
+    /// Delivers status changes of intents to the webhooks registered by the
+    /// API key that submitted them.
+    pub fn with_webhooks(mut self, webhooks: WebhookDispatcher) -> Self {
+        self.state.add_status_listener(Arc::new(webhooks.clone()));
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    pub fn webhooks(&self) -> Option<&WebhookDispatcher> {
+        self.webhooks.as_ref()
+    }
+
+    /// Signalled whenever an intent enters the orderbook.
+    pub fn orderbook_changed(&self) -> Arc<Notify> {
+        self.orderbook_changed.clone()
+    }
+
+    /// Intent and solution lifecycle events, as streamed on `/events`.
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// What `key` may see of `event`: all of it for admin keys. Other keys
+    /// see only events about intents they submitted, stripped of every
+    /// other intent and of legs they take no part in.
+    pub fn visible_to(&self, key: &ApiKey, event: &Event) -> Option<Event> {
+        if key.role == Role::Admin {
+            return Some(event.clone());
+        }
+        match event {
+            Event::IntentCreated { intent } => self
+                .state
+                .submitted_by(&intent.id, &key.id)
+                .then(|| event.clone()),
+            Event::IntentStatusChanged { intent_id, .. } => self
+                .state
+                .submitted_by(intent_id, &key.id)
+                .then(|| event.clone()),
+            Event::SolutionComputed { solution } => self
+                .solution_part(key, solution)
+                .map(|solution| Event::SolutionComputed { solution }),
+            Event::SolutionFailed { solution, reason } => {
+                self.solution_part(key, solution)
+                    .map(|solution| Event::SolutionFailed {
+                        solution,
+                        reason: reason.clone(),
+                    })
+            }
+            Event::LegSent {
+                solution_id,
+                intent_ids,
+                leg,
+                tx,
+                ..
+            }
+            | Event::LegConfirmed {
+                solution_id,
+                intent_ids,
+                leg,
+                tx,
+                ..
+            } => {
+                let (own, index) = self.leg_part(key, solution_id, intent_ids, *leg, tx)?;
+                let mut event = event.clone();
+                if let Event::LegSent {
+                    intent_ids, leg, ..
+                }
+                | Event::LegConfirmed {
+                    intent_ids, leg, ..
+                } = &mut event
+                {
+                    *intent_ids = own;
+                    *leg = index;
+                }
+                Some(event)
+            }
+            Event::BridgeSent { transfer, .. } => self
+                .state
+                .submitted_by(&transfer.intent_id, &key.id)
+                .then(|| event.clone()),
+        }
+    }
+
+    /// The part of `solution` that settles intents `key` submitted, or
+    /// `None` if it settles none of them.
+    fn solution_part(&self, key: &ApiKey, solution: &NettingSolution) -> Option<NettingSolution> {
+        let own = self.own_intents(key, &solution.involved_intent_ids);
+        if own.is_empty() {
+            return None;
+        }
+        let users = self.users_of(&own);
+        Some(NettingSolution {
+            id: solution.id.clone(),
+            txs: solution
+                .txs
+                .iter()
+                .filter(|leg| takes_part(&users, leg))
+                .cloned()
+                .collect(),
+            inventory_fills: solution
+                .inventory_fills
+                .iter()
+                .filter(|fill| own.contains(&fill.intent_id))
+                .cloned()
+                .collect(),
+            bridged: solution
+                .bridged
+                .iter()
+                .filter(|transfer| own.contains(&transfer.intent_id))
+                .cloned()
+                .collect(),
+            involved_intent_ids: own,
+        })
+    }
+
+    /// The caller's intents among `intent_ids` and the index of leg `leg`
+    /// among the legs of `solution_id` they see, if they take part in it.
+    fn leg_part(
+        &self,
+        key: &ApiKey,
+        solution_id: &str,
+        intent_ids: &[Uuid],
+        leg: usize,
+        tx: &Leg,
+    ) -> Option<(Vec<Uuid>, usize)> {
+        let own = self.own_intents(key, intent_ids);
+        let users = self.users_of(&own);
+        if !takes_part(&users, tx) {
+            return None;
+        }
+        let solution = self.state.solutions().solution(solution_id)?;
+        let seen = solution.txs.get(..leg)?;
+        let index = seen.iter().filter(|leg| takes_part(&users, leg)).count();
+        Some((own, index))
+    }
+
+    fn own_intents(&self, key: &ApiKey, ids: &[Uuid]) -> Vec<Uuid> {
+        ids.iter()
+            .filter(|id| self.state.submitted_by(id, &key.id))
+            .copied()
+            .collect()
+    }
+
+    fn users_of(&self, ids: &[Uuid]) -> Vec<String> {
+        ids.iter()
+            .filter_map(|id| self.state.get(id))
+            .map(|intent| intent.user_id)
+            .collect()
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Beaten by the [`crate::scheduler::Scheduler`] running on this context.
+    pub fn scheduler_heartbeat(&self) -> &Heartbeat {
+        &self.heartbeat
+    }
+
+    /// Verifies the user's signature over a submitted intent and spends its
+    /// nonce, yielding the request to pass to [`Self::create_intent`].
+    pub fn verify_signed_intent(
+        &self,
+        req: SignedIntent,
+    ) -> Result<CreateIntentRequest, SignatureError> {
+        signing::verify(&self.nonces, &self.signing, req)
+    }
+
+    /// Records the intent and returns it in `PendingRisk` straight away;
+    /// risk checks finish in the background. Use [`Self::wait_for_risk`] to
+    /// observe the outcome.
     pub async fn create_intent(&self, req: CreateIntentRequest) -> Intent {
-        let mut intent = Intent {
+        self.create(req, None).await
+    }
+
+    /// Like [`Self::create_intent`], remembering that `key` submitted the
+    /// intent so that its scoped `/events` stream and its webhooks include
+    /// it.
+    pub async fn submit_intent(&self, key: &ApiKey, req: CreateIntentRequest) -> Intent {
+        self.create(req, Some(key)).await
+    }
+
+    async fn create(&self, req: CreateIntentRequest, submitter: Option<&ApiKey>) -> Intent {
+        let intent = Intent {
             id: Uuid::new_v4(),
             user_id: req.user_id,
             from_chain: req.from_chain,
             to_chain: req.to_chain,
-            amount: req.amount,
+            amount: req.amount.into(),
+            kind: req.kind,
             status: IntentStatus::PendingRisk,
         };
 
-        {
-            let mut guard = self.state.lock().await;
-            guard.insert_intent(intent.clone());
+        self.state.insert_intent(intent.clone());
+        if let Some(key) = submitter {
+            self.state.set_submitter(intent.id, &key.id);
+            if let Some(webhooks) = &self.webhooks {
+                webhooks.track(intent.id, &key.id);
+            }
         }
+        self.events.publish(Event::IntentCreated {
+            intent: intent.clone(),
+        });
+        info!(intent_id = %intent.id, user_id = %intent.user_id, amount = intent.amount.get(), "intent created");
 
-        if self.risk_engine.check_intent(&intent) {
-            let mut guard = self.state.lock().await;
-            let should_enqueue = if let Some(stored) = guard.get_mut(&intent.id) {
-                stored.status = IntentStatus::InOrderbook;
-                intent = stored.clone();
-                true
-            } else {
-                false
-            };
+        let ctx = self.clone();
+        let pending = intent.clone();
+        let span = info_span!("risk_check", intent_id = %intent.id);
+        tokio::spawn(async move { ctx.evaluate_risk(pending).await }.instrument(span));
 
-            if should_enqueue {
-                guard.add_to_orderbook(intent.id);
+        intent
+    }
+
+    async fn evaluate_risk(&self, intent: Intent) {
+        let status = match self.risk_engine.check_intent(&intent).await {
+            RiskDecision::Approve => IntentStatus::InOrderbook,
+            RiskDecision::Review { reason } => IntentStatus::PendingReview { reason },
+            RiskDecision::Reject(rejection) => IntentStatus::Rejected {
+                reason: rejection.to_string(),
+            },
+        };
+        let enqueue = status == IntentStatus::InOrderbook;
+        info!(status = ?status, "risk check finished");
+
+        // The intent joins the book while its entry is still locked, so no
+        // reader sees it in the orderbook status before it is queued.
+        let updated = self.state.update_intent(&intent.id, |stored| {
+            stored.status = status;
+            if enqueue {
+                self.state.add_to_orderbook(stored);
             }
+        });
+        if updated.is_some() && enqueue {
+            self.orderbook_changed.notify_one();
         }
+        self.risk_settled.notify_waiters();
+    }
 
-        intent
+    fn enqueue(&self, intent: &Intent) {
+        self.state.add_to_orderbook(intent);
+        self.orderbook_changed.notify_one();
+    }
+
+    /// Frees everything held for an intent that passed risk checks but will
+    /// not execute.
+    fn release_turned_away(&self, intent: &Intent) {
+        self.risk_engine.release(intent);
+    }
+
+    /// Waits until risk checks for `id` have finished and returns the intent.
+    pub async fn wait_for_risk(&self, id: Uuid) -> Option<Intent> {
+        loop {
+            let settled = self.risk_settled.notified();
+            tokio::pin!(settled);
+            settled.as_mut().enable();
+
+            let intent = self.state.get(&id)?;
+            if intent.status != IntentStatus::PendingRisk {
+                return Some(intent);
+            }
+            settled.await;
+        }
+    }
+
+    pub async fn pending_reviews(&self) -> Vec<Intent> {
+        self.state
+            .intents()
+            .into_iter()
+            .filter(|intent| matches!(intent.status, IntentStatus::PendingReview { .. }))
+            .collect()
+    }
+
+    /// Moves an intent held for review into the orderbook.
+    pub async fn approve_intent(&self, id: Uuid) -> Result<Intent, ReviewError> {
+        let intent = self.resolve_review(id, IntentStatus::InOrderbook)?;
+        self.enqueue(&intent);
+        Ok(intent)
+    }
+
+    /// Rejects an intent held for review and frees its balance reservation
+    /// and risk limits.
+    pub async fn reject_intent(&self, id: Uuid, reason: String) -> Result<Intent, ReviewError> {
+        let intent = self.resolve_review(id, IntentStatus::Rejected { reason })?;
+        self.release_turned_away(&intent);
+        Ok(intent)
+    }
+
+    fn resolve_review(&self, id: Uuid, next: IntentStatus) -> Result<Intent, ReviewError> {
+        let mut current = None;
+        let updated = self
+            .state
+            .update_intent(&id, |intent| {
+                if matches!(intent.status, IntentStatus::PendingReview { .. }) {
+                    intent.status = next;
+                } else {
+                    current = Some(intent.status.clone());
+                }
+            })
+            .ok_or(ReviewError::NotFound(id))?;
+        match current {
+            Some(status) => Err(ReviewError::NotUnderReview { id, status }),
+            None => Ok(updated),
+        }
     }
 
     pub async fn list_intents(&self) -> Vec<Intent> {
-        let guard = self.state.lock().await;
-        guard.intents()
+        self.state.intents()
+    }
+
+    /// Every intent for admin keys, otherwise those submitted with `key`.
+    pub async fn intents_visible_to(&self, key: &ApiKey) -> Vec<Intent> {
+        let mut intents = self.state.intents();
+        if key.role != Role::Admin {
+            intents.retain(|intent| self.state.submitted_by(&intent.id, &key.id));
+        }
+        intents
     }
 
     pub async fn last_solution(&self) -> Option<NettingSolution> {
-        let guard = self.state.lock().await;
-        guard.last_solution()
+        self.state.last_solution()
+    }
+
+    /// The most recent solution for admin keys. Other keys get the most
+    /// recent one settling an intent they submitted, cut down to their
+    /// part of it.
+    pub async fn last_solution_visible_to(&self, key: &ApiKey) -> Option<NettingSolution> {
+        if key.role == Role::Admin {
+            return self.state.last_solution();
+        }
+        self.state
+            .solutions()
+            .all()
+            .iter()
+            .rev()
+            .find_map(|solution| self.solution_part(key, solution))
+    }
+
+    /// Number of open intents and their total notional.
+    pub async fn orderbook_depth(&self) -> (usize, u64) {
+        self.state.orderbook_depth()
     }
 
     pub async fn match_and_execute(&self) -> Result<Option<NettingSolution>, ExecutionError> {
-        let snapshot = {
-            let guard = self.state.lock().await;
-            guard.orderbook_snapshot()
+        // Only one round may run at a time, whether triggered by the API or
+        // the scheduler.
+        let _round = self.round_lock.lock().await;
+
+        let started = Instant::now();
+        let span = info_span!("match_round", intent_ids = field::Empty);
+        let result = self.run_round().instrument(span).await;
+        // Empty rounds would drown out the time spent settling a solution.
+        if !matches!(result, Ok(None)) {
+            self.metrics.observe_match_round(started.elapsed());
+        }
+        result
+    }
+
+    /// What a round would do with `intents`, or with the orderbook when none
+    /// are given, against the chains' current balances and with the same
+    /// inventory and bridge settings. Nothing is sent and no state changes.
+    pub async fn simulate(
+        &self,
+        intents: Option<Vec<Intent>>,
+    ) -> Result<Simulation, BlockchainError> {
+        let intents = intents.unwrap_or_else(|| self.state.orderbook_snapshot());
+        let snapshot = self.snapshot_of(intents).await;
+        simulate(&self.executor.client(), &snapshot).await
+    }
+
+    /// Quotes `amount` on `route` against the orderbook, the solver's
+    /// inventory and the bridge as they stand.
+    pub async fn quote(&self, route: Route, amount: Amount) -> Quote {
+        let intents = self.state.orderbook_snapshot();
+        let balances = match &self.inventory {
+            Some(inventory) => match inventory.balances(&self.executor.client()).await {
+                Ok(balances) => Some(balances),
+                // Quote without the inventory.
+                Err(err) => {
+                    warn!(error = %err, "solver inventory unavailable");
+                    None
+                }
+            },
+            None => None,
         };
+        let inventory = self.inventory.as_deref().zip(balances.as_ref());
+        self.quoter.quote(
+            route,
+            amount,
+            &intents,
+            inventory,
+            self.rebalancer.as_deref(),
+            unix_now(),
+        )
+    }
+
+    /// Checks a quote submitted with an intent for `amount` on `route`.
+    pub fn verify_quote(
+        &self,
+        quote: &Quote,
+        route: Route,
+        amount: Amount,
+    ) -> Result<(), QuoteError> {
+        self.quoter.verify(quote, route, amount, unix_now())
+    }
 
-        let Some(solution) = compute_solution(&snapshot) else {
+    /// Everything the next round is computed from.
+    async fn snapshot(&self) -> SolverSnapshot {
+        self.snapshot_of(self.state.orderbook_snapshot()).await
+    }
+
+    /// A snapshot of `intents` with the current inventory and bridge
+    /// settings. Intents not in the orderbook have not waited for a bridge.
+    async fn snapshot_of(&self, intents: Vec<Intent>) -> SolverSnapshot {
+        let inventory = match &self.inventory {
+            Some(inventory) if !intents.is_empty() => {
+                match inventory.balances(&self.executor.client()).await {
+                    Ok(balances) => Some(balances),
+                    // Netting goes ahead without the inventory.
+                    Err(err) => {
+                        warn!(error = %err, "solver inventory unavailable");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let bridge = self
+            .rebalancer
+            .as_ref()
+            .map(|rebalancer| rebalancer.policy());
+        let bridge_due = match &bridge {
+            Some(bridge) => intents
+                .iter()
+                .map(|intent| intent.id)
+                .filter(|id| {
+                    let waited = self.state.orderbook().waited(id);
+                    waited.is_some_and(|waited| bridge.is_due(waited))
+                })
+                .collect(),
+            None => BTreeSet::new(),
+        };
+        SolverSnapshot {
+            intents,
+            inventory_config: self.inventory.as_deref().cloned(),
+            inventory,
+            bridge,
+            bridge_due,
+        }
+    }
+
+    /// Re-runs the solver on the snapshot a logged solution was computed
+    /// from, inventory and bridge settings included, checking that it
+    /// yields the same solution.
+    pub fn replay(&self, solution_id: &str) -> Result<NettingSolution, ReplayError> {
+        let (snapshot, recorded) = self
+            .state
+            .solutions()
+            .get(solution_id)
+            .ok_or_else(|| ReplayError::NotFound(solution_id.to_string()))?;
+        replay(&snapshot, &recorded)
+    }
+
+    async fn run_round(&self) -> Result<Option<NettingSolution>, ExecutionError> {
+        let snapshot = self.snapshot().await;
+        let Some(solution) = solve(&snapshot) else {
             return Ok(None);
         };
 
+        let solver = self.inventory.as_deref().map(Inventory::account);
+        if let Err(report) = check_solution(&snapshot.intents, &solution, solver) {
+            error!(%report, "solution rejected before execution");
+            self.events.publish(Event::SolutionFailed {
+                solution,
+                reason: report.to_string(),
+            });
+            return Err(report.into());
+        }
+
         let involved: HashSet<Uuid> = solution.involved_intent_ids.iter().copied().collect();
+        tracing::Span::current().record("intent_ids", field::debug(&solution.involved_intent_ids));
+        info!(
+            legs = solution.txs.len(),
+            inventory_fills = solution.inventory_fills.len(),
+            bridged = solution.bridged.len(),
+            "solution computed"
+        );
 
-        {
-            let mut guard = self.state.lock().await;
-            guard.mark_status(&involved, IntentStatus::Matched);
-            guard.set_last_solution(solution.clone());
-        }
+        // Their legs are signed by whoever holds the maker keys.
+        let makers: HashSet<String> = snapshot
+            .intents
+            .iter()
+            .filter(|i| i.kind == IntentKind::Maker && involved.contains(&i.id))
+            .map(|i| i.user_id.clone())
+            .collect();
 
-        self.executor.execute_solution(&solution).await?;
+        self.state.mark_status(&involved, IntentStatus::Matched);
+        self.state
+            .record_solution(snapshot.clone(), solution.clone());
+        self.events.publish(Event::SolutionComputed {
+            solution: solution.clone(),
+        });
+
+        let executed = self
+            .executor
+            .execute_solution_with(&solution, &makers, |progress| {
+                let intent_ids = solution.involved_intent_ids.clone();
+                let event = match progress {
+                    LegProgress::Sent { leg, tx, tx_hash } => Event::LegSent {
+                        solution_id: solution.id.clone(),
+                        intent_ids,
+                        leg,
+                        tx: tx.clone(),
+                        tx_hash: tx_hash.clone(),
+                    },
+                    LegProgress::Confirmed { leg, tx, tx_hash } => Event::LegConfirmed {
+                        solution_id: solution.id.clone(),
+                        intent_ids,
+                        leg,
+                        tx: tx.clone(),
+                        tx_hash: tx_hash.clone(),
+                    },
+                    // The round reports the failure as `SolutionFailed`.
+                    LegProgress::Failed { tx, .. } => {
+                        self.metrics.record_leg_failure(tx.chain);
+                        return;
+                    }
+                };
+                self.events.publish(event);
+            })
+            .await;
+        let executed = match (executed, &self.rebalancer) {
+            (Ok(()), Some(rebalancer)) => {
+                rebalancer
+                    .send(&solution.bridged, |transfer, tx_hash| {
+                        self.events.publish(Event::BridgeSent {
+                            transfer: transfer.clone(),
+                            tx_hash,
+                        });
+                    })
+                    .await
+            }
+            (executed, _) => executed,
+        };
+        if let Err(err) = executed {
+            // The intents leave the book, so nothing holds their funds or
+            // limits back.
+            self.state.mark_status(
+                &involved,
+                IntentStatus::Failed {
+                    reason: err.to_string(),
+                },
+            );
+            self.state.remove_from_orderbook(&involved);
+            for intent in snapshot.intents.iter().filter(|i| involved.contains(&i.id)) {
+                self.release_turned_away(intent);
+            }
+            self.events.publish(Event::SolutionFailed {
+                solution,
+                reason: err.to_string(),
+            });
+            return Err(err);
+        }
 
-        {
-            let mut guard = self.state.lock().await;
-            guard.mark_status(&involved, IntentStatus::Executed);
-            guard.remove_from_orderbook(&involved);
+        // What the round left of an intent keeps its place in the book.
+        let left: HashMap<Uuid, Amount> = unsettled(&snapshot.intents, Some(&solution))
+            .into_iter()
+            .map(|residual| (residual.intent.id, residual.amount))
+            .collect();
+        let mut executed = HashSet::new();
+        for intent in snapshot.intents.iter().filter(|i| involved.contains(&i.id)) {
+            let left = left.get(&intent.id).copied().unwrap_or(Amount::ZERO);
+            self.risk_engine.settle(&Intent {
+                amount: intent.amount - left,
+                ..intent.clone()
+            });
+            if left.is_zero() {
+                executed.insert(intent.id);
+                continue;
+            }
+            self.state.orderbook().fill_partially(intent.id, left);
+            self.state.update_intent(&intent.id, |stored| {
+                stored.status = IntentStatus::PartiallyFilled {
+                    filled: stored.amount - left,
+                };
+            });
+        }
+        self.state.mark_status(&executed, IntentStatus::Executed);
+        self.state.remove_from_orderbook(&executed);
+        self.metrics
+            .record_netted_volume(netted_volume(&snapshot.intents).get());
+        if let (Some(inventory), Some(before)) = (&self.inventory, &snapshot.inventory) {
+            self.record_inventory(inventory, before).await;
+            for fill in &solution.inventory_fills {
+                self.metrics.record_spread(fill.fee.get());
+            }
         }
+        info!("solution executed");
 
         Ok(Some(solution))
     }
 }
 
+/// Volume netting matched between `intents`. Both sides of a pair fill the
+/// same amount, so only the low -> high side is counted.
+fn netted_volume(intents: &[Intent]) -> Amount {
+    let (_, residuals) = compute_netting(intents);
+    intents
+        .iter()
+        .filter(|intent| intent.from_chain < intent.to_chain)
+        .map(|intent| {
+            let left = residuals
+                .iter()
+                .find(|residual| residual.intent.id == intent.id)
+                .map_or(Amount::ZERO, |residual| residual.amount);
+            intent.amount - left
+        })
+        .sum()
+}
+
+/// Whether one of `users` sends or receives `leg`.
+fn takes_part(users: &[String], leg: &Leg) -> bool {
+    users.iter().any(|user| {
+        leg.from_user.eq_ignore_ascii_case(user) || leg.to_user.eq_ignore_ascii_case(user)
+    })
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateIntentRequest {
     pub user_id: String,
     pub from_chain: crate::models::ChainId,
     pub to_chain: crate::models::ChainId,
     pub amount: u64,
+    #[serde(default)]
+    pub kind: IntentKind,
 }
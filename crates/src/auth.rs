@@ -0,0 +1,19 @@
+use crate::app::AppContext;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+pub use oceanlink_core::auth::{
+    API_KEY_HEADER, ApiKey, ApiKeyStore, AuthError, Authenticator, RateKey, RateLimit, RateLimiter,
+    Role, authorize,
+};
+
+pub async fn require_user(State(ctx): State<AppContext>, req: Request, next: Next) -> Response {
+    authorize(&ctx.auth, req, next, Role::User).await
+}
+
+pub async fn require_admin(State(ctx): State<AppContext>, req: Request, next: Next) -> Response {
+    authorize(&ctx.auth, req, next, Role::Admin).await
+}
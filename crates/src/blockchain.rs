@@ -1,11 +1,14 @@
 use crate::models::ChainId;
 use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::Address;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     sync::{
         Arc, Mutex,
         atomic::{AtomicU64, Ordering},
     },
+    time::Duration,
 };
 use thiserror::Error;
 
@@ -15,6 +18,12 @@ pub type TxHash = String;
 pub enum BlockchainError {
     #[error("transfer failed: {0}")]
     TransferFailed(String),
+    #[error("balance query failed: {0}")]
+    BalanceUnavailable(String),
+    #[error("rpc unreachable: {0}")]
+    RpcUnavailable(String),
+    #[error("no signer: {0}")]
+    SignerUnavailable(String),
 }
 
 #[derive(Clone, Debug)]
@@ -35,6 +44,68 @@ pub trait BlockchainClient: Send + Sync + Clone + 'static {
         to_user: &str,
         amount: u64,
     ) -> Result<TxHash, BlockchainError>;
+
+    /// Spendable balance of `user` on `chain`.
+    async fn balance_of(&self, chain: ChainId, user: &str) -> Result<u64, BlockchainError>;
+}
+
+/// What readiness asks of every served chain.
+#[async_trait]
+pub trait ChainProbe: Send + Sync {
+    /// EVM chain id reported by the RPC endpoint configured for `chain`.
+    async fn chain_id(&self, chain: ChainId) -> Result<u64, BlockchainError>;
+
+    /// Address of the key that signs transfers on `chain`.
+    async fn signer_address(&self, chain: ChainId) -> Result<String, BlockchainError>;
+}
+
+/// Asks each chain's configured RPC endpoint for its chain id and reports
+/// the configured signing keys, whichever client sends the transfers.
+#[derive(Clone, Debug, Default)]
+pub struct RpcProbe {
+    rpc_urls: BTreeMap<ChainId, String>,
+    signers: BTreeMap<ChainId, Address>,
+}
+
+impl RpcProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rpc_url(mut self, chain: ChainId, url: impl Into<String>) -> Self {
+        self.rpc_urls.insert(chain, url.into());
+        self
+    }
+
+    pub fn with_signer(mut self, chain: ChainId, address: Address) -> Self {
+        self.signers.insert(chain, address);
+        self
+    }
+}
+
+#[async_trait]
+impl ChainProbe for RpcProbe {
+    async fn chain_id(&self, chain: ChainId) -> Result<u64, BlockchainError> {
+        let url = self.rpc_urls.get(&chain).ok_or_else(|| {
+            BlockchainError::RpcUnavailable(format!("no rpc_url configured for {chain}"))
+        })?;
+        let provider = Provider::<Http>::try_from(url.as_str())
+            .map_err(|err| BlockchainError::RpcUnavailable(err.to_string()))?;
+        let id = provider
+            .get_chainid()
+            .await
+            .map_err(|err| BlockchainError::RpcUnavailable(err.to_string()))?;
+        Ok(id.as_u64())
+    }
+
+    async fn signer_address(&self, chain: ChainId) -> Result<String, BlockchainError> {
+        self.signers
+            .get(&chain)
+            .map(|address| format!("{address:#x}"))
+            .ok_or_else(|| {
+                BlockchainError::SignerUnavailable(format!("no key configured for {chain}"))
+            })
+    }
 }
 
 #[derive(Default, Clone)]
@@ -47,9 +118,24 @@ struct MockBlockchainInner {
     recorded: Mutex<Vec<RecordedTx>>,
     balances: Mutex<HashMap<(ChainId, String), u64>>,
     counter: AtomicU64,
+    latency: Option<Duration>,
+    chain_ids: Mutex<HashMap<ChainId, u64>>,
+    unreachable: Mutex<HashSet<ChainId>>,
+    signerless: Mutex<HashSet<ChainId>>,
 }
 
 impl MockBlockchainClient {
+    /// A client whose transfers take `latency` to land, to stand in for RPC
+    /// round trips in load tests.
+    pub fn with_latency(latency: Duration) -> Self {
+        Self {
+            inner: Arc::new(MockBlockchainInner {
+                latency: Some(latency),
+                ..MockBlockchainInner::default()
+            }),
+        }
+    }
+
     pub fn set_balance(&self, chain: ChainId, user: &str, amount: u64) {
         let mut balances = self.inner.balances.lock().expect("balances lock poisoned");
         balances.insert((chain, user.to_string()), amount);
@@ -60,6 +146,38 @@ impl MockBlockchainClient {
         *balances.get(&(chain, user.to_string())).unwrap_or(&0)
     }
 
+    /// Makes the mock RPC for `chain` report `id` instead of the real one.
+    pub fn set_chain_id(&self, chain: ChainId, id: u64) {
+        let mut chain_ids = self
+            .inner
+            .chain_ids
+            .lock()
+            .expect("chain ids lock poisoned");
+        chain_ids.insert(chain, id);
+    }
+
+    /// Makes every RPC call for `chain` fail, as if the node were down.
+    pub fn set_unreachable(&self, chain: ChainId) {
+        let mut unreachable = self.inner.unreachable.lock().expect("rpc lock poisoned");
+        unreachable.insert(chain);
+    }
+
+    /// Drops the signing key for `chain`.
+    pub fn remove_signer(&self, chain: ChainId) {
+        let mut signerless = self.inner.signerless.lock().expect("signer lock poisoned");
+        signerless.insert(chain);
+    }
+
+    fn reachable(&self, chain: ChainId) -> Result<(), BlockchainError> {
+        let unreachable = self.inner.unreachable.lock().expect("rpc lock poisoned");
+        if unreachable.contains(&chain) {
+            return Err(BlockchainError::RpcUnavailable(format!(
+                "{chain} node is not responding"
+            )));
+        }
+        Ok(())
+    }
+
     pub fn recorded_txs(&self) -> Vec<RecordedTx> {
         let recorded = self.inner.recorded.lock().expect("recorded lock poisoned");
         recorded.clone()
@@ -75,6 +193,10 @@ impl BlockchainClient for MockBlockchainClient {
         to_user: &str,
         amount: u64,
     ) -> Result<TxHash, BlockchainError> {
+        if let Some(latency) = self.inner.latency {
+            tokio::time::sleep(latency).await;
+        }
+
         let mut balances = self.inner.balances.lock().expect("balances lock poisoned");
         let from_key = (chain, from_user.to_string());
         let to_key = (chain, to_user.to_string());
@@ -95,13 +217,7 @@ impl BlockchainClient for MockBlockchainClient {
         *to_balance += amount;
         drop(balances);
 
-        let tx_hash = format!(
-            "tx-{}",
-            self.inner
-                .counter
-                .fetch_add(1, Ordering::SeqCst)
-                .to_string()
-        );
+        let tx_hash = format!("tx-{}", self.inner.counter.fetch_add(1, Ordering::SeqCst));
 
         let mut recorded = self.inner.recorded.lock().expect("recorded lock poisoned");
         recorded.push(RecordedTx {
@@ -113,4 +229,34 @@ impl BlockchainClient for MockBlockchainClient {
         });
         Ok(tx_hash)
     }
+
+    async fn balance_of(&self, chain: ChainId, user: &str) -> Result<u64, BlockchainError> {
+        Ok(self.balance(chain, user))
+    }
+}
+
+#[async_trait]
+impl ChainProbe for MockBlockchainClient {
+    async fn chain_id(&self, chain: ChainId) -> Result<u64, BlockchainError> {
+        self.reachable(chain)?;
+        let chain_ids = self
+            .inner
+            .chain_ids
+            .lock()
+            .expect("chain ids lock poisoned");
+        Ok(chain_ids
+            .get(&chain)
+            .copied()
+            .unwrap_or_else(|| chain.as_u64()))
+    }
+
+    async fn signer_address(&self, chain: ChainId) -> Result<String, BlockchainError> {
+        let signerless = self.inner.signerless.lock().expect("signer lock poisoned");
+        if signerless.contains(&chain) {
+            return Err(BlockchainError::SignerUnavailable(format!(
+                "no key configured for {chain}"
+            )));
+        }
+        Ok("mock-solver".to_string())
+    }
 }
@@ -0,0 +1,142 @@
+use crate::blockchain::{BlockchainError, TxHash};
+use crate::executor::ExecutionError;
+use crate::models::BridgeTransfer;
+use crate::solver::Residual;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{Instrument, info_span, warn};
+use uuid::Uuid;
+
+/// Moves a user's funds across chains when no counterparty can be found,
+/// through a canonical bridge or a liquidity pool.
+#[async_trait]
+pub trait BridgeAdapter: Send + Sync {
+    /// Recorded as [`BridgeTransfer::via`].
+    fn name(&self) -> &str;
+
+    /// Starts `transfer`, returning the hash of its source-chain transaction.
+    async fn bridge(&self, transfer: &BridgeTransfer) -> Result<TxHash, BlockchainError>;
+}
+
+/// Adapter that records transfers instead of bridging them.
+#[derive(Clone, Default)]
+pub struct MockBridge {
+    transfers: Arc<Mutex<Vec<BridgeTransfer>>>,
+}
+
+impl MockBridge {
+    pub fn recorded(&self) -> Vec<BridgeTransfer> {
+        self.transfers.lock().expect("bridge lock poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl BridgeAdapter for MockBridge {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn bridge(&self, transfer: &BridgeTransfer) -> Result<TxHash, BlockchainError> {
+        let mut transfers = self.transfers.lock().expect("bridge lock poisoned");
+        transfers.push(transfer.clone());
+        Ok(format!("0xbridge{:04x}", transfers.len()))
+    }
+}
+
+/// Which residual volume to bridge, and through which adapter. Solver
+/// snapshots keep it so that rounds can be replayed without the adapter.
+///
+/// What is left of a partially netted intent is bridged in the same round,
+/// since the intent settles then. An intent that nets with nothing is
+/// bridged once it has waited `wait` in the orderbook.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgePolicy {
+    /// Name of the adapter, recorded as [`BridgeTransfer::via`].
+    pub via: String,
+    pub wait: Duration,
+}
+
+impl BridgePolicy {
+    /// Whether an intent that has `waited` this long should be bridged.
+    pub fn is_due(&self, waited: Duration) -> bool {
+        waited >= self.wait
+    }
+
+    /// Transfers for the `residuals` of partially netted intents and of
+    /// those that are `due`.
+    pub fn plan(&self, residuals: &[Residual], due: &BTreeSet<Uuid>) -> Vec<BridgeTransfer> {
+        residuals
+            .iter()
+            .filter(|residual| residual.is_partial() || due.contains(&residual.intent.id))
+            .map(|residual| BridgeTransfer {
+                intent_id: residual.intent.id,
+                user: residual.intent.user_id.clone(),
+                from_chain: residual.intent.from_chain,
+                to_chain: residual.intent.to_chain,
+                amount: residual.amount,
+                via: self.via.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Bridges residual volume through its adapter, as planned by its
+/// [`BridgePolicy`].
+#[derive(Clone)]
+pub struct Rebalancer {
+    adapter: Arc<dyn BridgeAdapter>,
+    wait: Duration,
+}
+
+impl Rebalancer {
+    pub fn new(adapter: Arc<dyn BridgeAdapter>, wait: Duration) -> Self {
+        Self { adapter, wait }
+    }
+
+    pub fn wait(&self) -> Duration {
+        self.wait
+    }
+
+    pub fn policy(&self) -> BridgePolicy {
+        BridgePolicy {
+            via: self.adapter.name().to_string(),
+            wait: self.wait,
+        }
+    }
+
+    /// Bridges `transfers` in order, calling `on_sent` after each one, and
+    /// stops at the first failure.
+    pub async fn send<F>(
+        &self,
+        transfers: &[BridgeTransfer],
+        mut on_sent: F,
+    ) -> Result<(), ExecutionError>
+    where
+        F: FnMut(&BridgeTransfer, TxHash),
+    {
+        for transfer in transfers {
+            let span = info_span!(
+                "bridge",
+                intent_id = %transfer.intent_id,
+                from_chain = %transfer.from_chain,
+                to_chain = %transfer.to_chain,
+                amount = transfer.amount.get(),
+                via = %transfer.via,
+            );
+            match self.adapter.bridge(transfer).instrument(span.clone()).await {
+                Ok(tx_hash) => on_sent(transfer, tx_hash),
+                Err(err) => {
+                    span.in_scope(|| warn!(error = %err, "bridge transfer failed"));
+                    return Err(ExecutionError::Bridge {
+                        via: transfer.via.clone(),
+                        source: err,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
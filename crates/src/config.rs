@@ -0,0 +1,1055 @@
+use crate::auth::RateLimit;
+use crate::blockchain::{BlockchainClient, RpcProbe};
+use crate::bridge::{MockBridge, Rebalancer};
+use crate::inventory::{ChainTarget, Inventory};
+use crate::models::{ChainId, Route};
+use crate::quote::{DEFAULT_QUOTE_TTL, Quoter};
+use crate::risk::{
+    AllowedRoutesRule, AlwaysPassRiskEngine, BalanceCheckRule, DailyNotionalRule, ManualReviewRule,
+    MaxIntentSizeRule, RiskEngine, RuleBasedRiskEngine, SanctionsListRule, read_address_list,
+};
+use crate::scheduler::SchedulerConfig;
+use crate::signing::SigningDomain;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use oceanlink_core::chain::{ChainInfo, ChainRegistry};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use toml::{Table, Value};
+use uuid::Uuid;
+
+/// Environment variables starting with this override settings; `__`
+/// separates keys, so `OCEANLINK__SCHEDULER__INTERVAL_MS` sets
+/// `scheduler.interval_ms`.
+pub const ENV_PREFIX: &str = "OCEANLINK__";
+pub const DEFAULT_BIND: &str = "127.0.0.1:8080";
+const DEFAULT_INTERVAL_MS: u64 = 5_000;
+const DEFAULT_BRIDGE_WAIT_MS: u64 = 60_000;
+const DEFAULT_SPREAD_BPS: u64 = 10;
+/// Shortest accepted quote secret, in bytes.
+const MIN_QUOTE_SECRET_LEN: usize = 32;
+
+/// One invalid setting, named by its dotted key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Everything wrong with a configuration, so it can be fixed in one pass.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl ConfigErrors {
+    pub fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|error| error.key.as_str()).collect()
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration ({} errors):", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validated backend settings.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub bind: SocketAddr,
+    /// Built-in chains plus those defined or amended under `chains`.
+    pub registry: ChainRegistry,
+    /// Chains the backend settles on.
+    pub chains: Vec<ChainConfig>,
+    pub signers: Vec<SignerConfig>,
+    pub risk: RiskConfig,
+    pub scheduler: SchedulerConfig,
+    pub storage: StorageConfig,
+    /// Where legs paid by makers are sent; without it the backend signs them.
+    pub feeder: Option<FeederConfig>,
+    /// Bridges volume that cannot be netted; without it such intents wait.
+    pub bridge: Option<BridgeConfig>,
+    /// The solver's own funds; without it only netting and bridging fill
+    /// intents.
+    pub inventory: Option<InventoryConfig>,
+    pub quotes: QuoteConfig,
+    /// Per API key, or per IP for callers without one.
+    pub rate_limit: RateLimit,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainConfig {
+    /// Its RPC endpoint and explorer are in [`Config::registry`].
+    pub chain: ChainId,
+    pub vault: Option<Address>,
+    /// Token contracts by symbol.
+    pub tokens: BTreeMap<String, Address>,
+}
+
+/// Key that signs solver transfers on `chain`. `Debug` shows the address
+/// only.
+#[derive(Clone, Debug)]
+pub struct SignerConfig {
+    pub chain: ChainId,
+    pub wallet: LocalWallet,
+}
+
+impl SignerConfig {
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+}
+
+/// A feeder that posts maker inventory and signs its makers' legs. `Debug`
+/// hides the key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FeederConfig {
+    pub url: String,
+    /// Admin key of the feeder, sent with every delegated leg.
+    pub api_key: String,
+}
+
+impl fmt::Debug for FeederConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeederConfig")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BridgeConfig {
+    pub adapter: BridgeAdapterConfig,
+    /// How long an intent waits for a counterparty before it is bridged.
+    pub wait: Duration,
+}
+
+impl BridgeConfig {
+    pub fn rebalancer(&self) -> Rebalancer {
+        let adapter = match self.adapter {
+            BridgeAdapterConfig::Mock => Arc::new(MockBridge::default()),
+        };
+        Rebalancer::new(adapter, self.wait)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BridgeAdapterConfig {
+    /// Records transfers without moving funds.
+    Mock,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InventoryConfig {
+    /// Address holding the inventory; its transfers are signed by the
+    /// configured signers.
+    pub account: String,
+    pub spread_bps: u64,
+    pub chains: BTreeMap<ChainId, ChainTarget>,
+}
+
+impl InventoryConfig {
+    pub fn inventory(&self) -> Inventory {
+        self.chains.iter().fold(
+            Inventory::new(&self.account, self.spread_bps),
+            |inventory, (&chain, &target)| inventory.with_chain(chain, target),
+        )
+    }
+}
+
+/// How `/quote` signs its quotes. `Debug` hides the secret.
+#[derive(Clone, PartialEq, Eq)]
+pub struct QuoteConfig {
+    /// HMAC key; without it each process makes one up, and only it can
+    /// verify its quotes.
+    pub secret: Option<String>,
+    pub ttl: Duration,
+}
+
+impl Default for QuoteConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            ttl: DEFAULT_QUOTE_TTL,
+        }
+    }
+}
+
+impl fmt::Debug for QuoteConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuoteConfig")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl QuoteConfig {
+    /// `round_interval` is the scheduler's, counted into expected waits.
+    pub fn quoter(&self, round_interval: Option<Duration>) -> Quoter {
+        let secret = self
+            .secret
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+        Quoter::new(secret, self.ttl).with_round_interval(round_interval)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RiskConfig {
+    pub max_intent_size: Option<u64>,
+    pub daily_notional_limit: Option<u64>,
+    /// Intents at or above this amount wait for manual review.
+    pub manual_review_above: Option<u64>,
+    /// `None` allows every route.
+    pub allowed_routes: Option<Vec<Route>>,
+    pub sanctioned_addresses: Option<Vec<String>>,
+    /// Reserve each intent's amount against the user's balance at intake.
+    pub balance_checks: bool,
+}
+
+impl RiskConfig {
+    /// The engine enforcing the configured rules; with none, every intent
+    /// passes. Balance checks read through `client` and run last, so intents
+    /// the other rules turn away cost no balance lookup.
+    pub fn engine<C>(&self, client: C) -> Arc<dyn RiskEngine + Send + Sync>
+    where
+        C: BlockchainClient,
+    {
+        let mut engine = RuleBasedRiskEngine::new();
+        let mut rules = 0;
+        if let Some(addresses) = &self.sanctioned_addresses {
+            engine = engine.with_rule(SanctionsListRule::new(addresses));
+            rules += 1;
+        }
+        if let Some(routes) = &self.allowed_routes {
+            engine = engine.with_rule(AllowedRoutesRule::new(routes.iter().copied()));
+            rules += 1;
+        }
+        if let Some(max) = self.max_intent_size {
+            engine = engine.with_rule(MaxIntentSizeRule::new(max));
+            rules += 1;
+        }
+        if let Some(limit) = self.daily_notional_limit {
+            engine = engine.with_rule(DailyNotionalRule::new(limit));
+            rules += 1;
+        }
+        if let Some(min_amount) = self.manual_review_above {
+            engine = engine.with_rule(ManualReviewRule::new(min_amount));
+            rules += 1;
+        }
+        if self.balance_checks {
+            engine = engine.with_rule(BalanceCheckRule::new(client));
+            rules += 1;
+        }
+        if rules == 0 {
+            return Arc::new(AlwaysPassRiskEngine);
+        }
+        Arc::new(engine)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageConfig {
+    /// State lives in process memory and is lost on restart.
+    #[default]
+    Memory,
+}
+
+impl fmt::Display for StorageConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageConfig::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+impl Config {
+    /// Builds the configuration from, lowest precedence first: built-in
+    /// defaults, the TOML `file`, [`ENV_PREFIX`] variables from `env`, and
+    /// `overrides` of the form `section.key=value`. Every invalid setting is
+    /// reported, not just the first.
+    pub fn load<I>(file: Option<&Path>, env: I, overrides: &[String]) -> Result<Self, ConfigErrors>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut errors = Errors::default();
+        let mut table = match file {
+            Some(path) => read_file(path, &mut errors),
+            None => Table::new(),
+        };
+        for (name, value) in env {
+            if let Some(path) = name.strip_prefix(ENV_PREFIX) {
+                let keys: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+                set(&mut table, &keys, value, &mut errors);
+            }
+        }
+        for setting in overrides {
+            match setting.split_once('=') {
+                Some((key, value)) => {
+                    let keys: Vec<String> = key.trim().split('.').map(str::to_string).collect();
+                    set(&mut table, &keys, value.to_string(), &mut errors);
+                }
+                None => errors.push(setting, "expected key=value"),
+            }
+        }
+        let config = Self::from_table(table, &mut errors);
+        errors.finish(config)
+    }
+
+    /// Parses a TOML document on its own, without env or flag overrides.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigErrors> {
+        let mut errors = Errors::default();
+        let table = match source.parse::<Table>() {
+            Ok(table) => table,
+            Err(err) => {
+                errors.push("config", err.message());
+                Table::new()
+            }
+        };
+        let config = Self::from_table(table, &mut errors);
+        errors.finish(config)
+    }
+
+    fn from_table(table: Table, errors: &mut Errors) -> Self {
+        let mut raw = RawConfig::default();
+        for (key, value) in table {
+            match key.as_str() {
+                "bind" => raw.bind = Some(value),
+                "chains" => raw.chains = section(&key, value, errors).unwrap_or_default(),
+                "signers" => raw.signers = section(&key, value, errors).unwrap_or_default(),
+                "risk" => raw.risk = section(&key, value, errors).unwrap_or_default(),
+                "scheduler" => raw.scheduler = section(&key, value, errors).unwrap_or_default(),
+                "storage" => raw.storage = section(&key, value, errors).unwrap_or_default(),
+                "feeder" => raw.feeder = section(&key, value, errors),
+                "bridge" => raw.bridge = section(&key, value, errors),
+                "inventory" => raw.inventory = section(&key, value, errors),
+                "quotes" => raw.quotes = section(&key, value, errors).unwrap_or_default(),
+                "rate_limit" => raw.rate_limit = section(&key, value, errors).unwrap_or_default(),
+                _ => errors.push(&key, "unknown setting"),
+            }
+        }
+
+        let bind = parsed::<SocketAddr>(errors, "bind", raw.bind, "a socket address")
+            .unwrap_or_else(|| DEFAULT_BIND.parse().expect("default bind address is valid"));
+        let mut registry = ChainRegistry::builtin();
+        let chains = chains(raw.chains, &mut registry, errors);
+        let signers = signers(raw.signers, &registry, errors);
+        let risk = risk(raw.risk, &registry, errors);
+        let inventory = raw
+            .inventory
+            .and_then(|raw| inventory(raw, &registry, errors));
+        Self {
+            bind,
+            registry,
+            chains,
+            signers,
+            risk,
+            scheduler: scheduler(raw.scheduler, errors),
+            storage: storage(raw.storage, errors),
+            feeder: raw.feeder.and_then(|raw| feeder(raw, errors)),
+            bridge: raw.bridge.and_then(|raw| bridge(raw, errors)),
+            inventory,
+            quotes: quotes(raw.quotes, errors),
+            rate_limit: rate_limit(raw.rate_limit, errors),
+        }
+    }
+
+    /// Intents are signed against the vault of their `from_chain`.
+    pub fn signing_domain(&self) -> SigningDomain {
+        self.chains
+            .iter()
+            .filter_map(|chain| Some((chain.chain, chain.vault?)))
+            .fold(SigningDomain::new(), |domain, (chain, vault)| {
+                domain.with_vault(chain, vault)
+            })
+    }
+
+    /// Readiness asks the configured RPC endpoints and keys directly.
+    pub fn chain_probe(&self) -> RpcProbe {
+        let probe = self
+            .chains
+            .iter()
+            .filter_map(|chain| {
+                let url = self.registry.get(chain.chain)?.rpc_url.clone()?;
+                Some((chain.chain, url))
+            })
+            .fold(RpcProbe::new(), |probe, (chain, url)| {
+                probe.with_rpc_url(chain, url)
+            });
+        self.signers.iter().fold(probe, |probe, signer| {
+            probe.with_signer(signer.chain, signer.address())
+        })
+    }
+}
+
+/// The configuration as written, before validation. Leaves stay untyped
+/// so that every bad value can be reported instead of the first.
+#[derive(Default)]
+struct RawConfig {
+    bind: Option<Value>,
+    chains: BTreeMap<String, RawChain>,
+    signers: BTreeMap<String, RawSigner>,
+    risk: RawRisk,
+    scheduler: RawScheduler,
+    storage: RawStorage,
+    feeder: Option<RawFeeder>,
+    bridge: Option<RawBridge>,
+    inventory: Option<RawInventory>,
+    quotes: RawQuotes,
+    rate_limit: RawRateLimit,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawChain {
+    chain_id: Option<Value>,
+    name: Option<Value>,
+    aliases: Option<Value>,
+    rpc_url: Option<Value>,
+    finality_depth: Option<Value>,
+    explorer_url: Option<Value>,
+    vault: Option<Value>,
+    tokens: BTreeMap<String, Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawSigner {
+    private_key: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRisk {
+    max_intent_size: Option<Value>,
+    daily_notional_limit: Option<Value>,
+    manual_review_above: Option<Value>,
+    allowed_routes: Option<Value>,
+    sanctions_file: Option<Value>,
+    balance_checks: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawScheduler {
+    interval_ms: Option<Value>,
+    min_intents: Option<Value>,
+    min_notional: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawStorage {
+    backend: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawFeeder {
+    url: Option<Value>,
+    api_key: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawBridge {
+    adapter: Option<Value>,
+    wait_ms: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawInventory {
+    account: Option<Value>,
+    spread_bps: Option<Value>,
+    chains: BTreeMap<String, RawInventoryChain>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawQuotes {
+    secret: Option<Value>,
+    ttl_secs: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRateLimit {
+    per_second: Option<Value>,
+    burst: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawInventoryChain {
+    target_ratio: Option<Value>,
+    min_balance: Option<Value>,
+}
+
+#[derive(Default)]
+struct Errors(Vec<ConfigError>);
+
+impl Errors {
+    fn push(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigError {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, ConfigErrors> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(ConfigErrors(self.0))
+        }
+    }
+}
+
+fn read_file(path: &Path, errors: &mut Errors) -> Table {
+    let key = format!("config file {}", path.display());
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            errors.push(key, err.to_string());
+            return Table::new();
+        }
+    };
+    source.parse::<Table>().unwrap_or_else(|err| {
+        errors.push(key, err.message());
+        Table::new()
+    })
+}
+
+/// Sets `keys` in `table` to the string `value`, creating tables on the way.
+/// Typed settings accept strings, so overrides need no quoting.
+fn set(table: &mut Table, keys: &[String], value: String, errors: &mut Errors) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for (depth, key) in parents.iter().enumerate() {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        current = match entry {
+            Value::Table(table) => table,
+            _ => {
+                errors.push(keys[..=depth].join("."), "is a value, not a section");
+                return;
+            }
+        };
+    }
+    current.insert(last.clone(), Value::String(value));
+}
+
+fn section<T>(key: &str, value: Value, errors: &mut Errors) -> Option<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    T::deserialize(value)
+        .map_err(|err| errors.push(key, err.message()))
+        .ok()
+}
+
+fn text(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text),
+        other => {
+            errors.push(key, format!("expected a string, got {other}"));
+            None
+        }
+    }
+}
+
+/// Like [`text`], but pushes `required` when the setting is missing.
+fn required(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<String> {
+    if value.is_none() {
+        errors.push(key, "required");
+    }
+    text(errors, key, value)
+}
+
+fn parse_text<T: FromStr>(errors: &mut Errors, key: &str, text: &str, expected: &str) -> Option<T> {
+    text.trim()
+        .parse()
+        .map_err(|_| errors.push(key, format!("expected {expected}, got '{text}'")))
+        .ok()
+}
+
+fn parsed<T: FromStr>(
+    errors: &mut Errors,
+    key: &str,
+    value: Option<Value>,
+    expected: &str,
+) -> Option<T> {
+    let text = text(errors, key, value)?;
+    parse_text(errors, key, &text, expected)
+}
+
+fn number(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<u64> {
+    match value? {
+        Value::Integer(n) if n >= 0 => Some(n as u64),
+        Value::String(text) => parse_text(errors, key, &text, "a non-negative integer"),
+        other => {
+            errors.push(key, format!("expected a non-negative integer, got {other}"));
+            None
+        }
+    }
+}
+
+fn positive(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<u64> {
+    match number(errors, key, value)? {
+        0 => {
+            errors.push(key, "must be greater than zero");
+            None
+        }
+        n => Some(n),
+    }
+}
+
+fn flag(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<bool> {
+    match value? {
+        Value::Boolean(flag) => Some(flag),
+        Value::String(text) => parse_text(errors, key, &text, "true or false"),
+        other => {
+            errors.push(key, format!("expected true or false, got {other}"));
+            None
+        }
+    }
+}
+
+/// A number between 0 and 1.
+fn ratio(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<f64> {
+    let ratio = match value? {
+        Value::Float(ratio) => ratio,
+        Value::Integer(n) => n as f64,
+        Value::String(text) => parse_text(errors, key, &text, "a ratio between 0 and 1")?,
+        other => {
+            errors.push(
+                key,
+                format!("expected a ratio between 0 and 1, got {other}"),
+            );
+            return None;
+        }
+    };
+    if !(0.0..=1.0).contains(&ratio) {
+        errors.push(key, "must be between 0 and 1");
+        return None;
+    }
+    Some(ratio)
+}
+
+/// An array of strings, or one comma-separated string as given in env vars.
+fn list(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<Vec<String>> {
+    match value? {
+        Value::String(text) => Some(
+            text.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ),
+        Value::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| text(errors, &format!("{key}[{i}]"), Some(item)))
+            .collect(),
+        other => {
+            errors.push(key, format!("expected a list of strings, got {other}"));
+            None
+        }
+    }
+}
+
+fn address(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<Address> {
+    let text = text(errors, key, value)?;
+    let valid = text.len() == 42 && text.starts_with("0x");
+    match Address::from_str(&text) {
+        Ok(address) if valid => Some(address),
+        _ => {
+            errors.push(
+                key,
+                format!("expected a 0x-prefixed 20-byte address, got '{text}'"),
+            );
+            None
+        }
+    }
+}
+
+/// A URL whose scheme is one of `schemes`; `expected` describes them.
+fn url(
+    errors: &mut Errors,
+    key: &str,
+    value: Option<Value>,
+    schemes: &[&str],
+    expected: &str,
+) -> Option<String> {
+    let text = text(errors, key, value)?;
+    match reqwest::Url::parse(&text) {
+        Ok(parsed) if schemes.contains(&parsed.scheme()) => Some(text),
+        _ => {
+            errors.push(key, format!("expected {expected}, got '{text}'"));
+            None
+        }
+    }
+}
+
+fn chain_key(
+    errors: &mut Errors,
+    key: &str,
+    name: &str,
+    registry: &ChainRegistry,
+) -> Option<ChainId> {
+    let chain = registry.resolve(name);
+    if chain.is_none() {
+        errors.push(key, format!("invalid chain '{name}'"));
+    }
+    chain
+}
+
+/// Applies each `[chains.<name>]` section to `registry`. A name the registry
+/// knows amends that chain; any other name needs a `chain_id`, and either
+/// adds a chain or, if the id is known, becomes an alias of it.
+fn chains(
+    raw: BTreeMap<String, RawChain>,
+    registry: &mut ChainRegistry,
+    errors: &mut Errors,
+) -> Vec<ChainConfig> {
+    let mut chains = Vec::new();
+    for (name, raw) in raw {
+        let prefix = format!("chains.{name}");
+        let key = |field: &str| format!("{prefix}.{field}");
+
+        let chain_id = positive(errors, &key("chain_id"), raw.chain_id);
+        let known = registry.resolve(&name);
+        let chain = match (known, chain_id) {
+            (Some(chain), Some(id)) if chain.as_u64() != id => {
+                errors.push(
+                    key("chain_id"),
+                    format!("'{name}' is chain {}, not {id}", chain.as_u64()),
+                );
+                None
+            }
+            (Some(chain), _) => Some(chain),
+            (None, Some(id)) => Some(ChainId::new(id)),
+            (None, None) => {
+                errors.push(
+                    &prefix,
+                    format!("unknown chain '{name}'; set chain_id to add it"),
+                );
+                None
+            }
+        };
+        let display_name = text(errors, &key("name"), raw.name);
+        let aliases = list(errors, &key("aliases"), raw.aliases).unwrap_or_default();
+        if raw.rpc_url.is_none() {
+            errors.push(key("rpc_url"), "required");
+        }
+        let rpc_url = url(
+            errors,
+            &key("rpc_url"),
+            raw.rpc_url,
+            &["http", "https", "ws", "wss"],
+            "an http(s) or ws(s) url",
+        );
+        let finality_depth = number(errors, &key("finality_depth"), raw.finality_depth);
+        let explorer_url = url(
+            errors,
+            &key("explorer_url"),
+            raw.explorer_url,
+            &["http", "https"],
+            "an http(s) url",
+        );
+        let vault = address(errors, &key("vault"), raw.vault);
+        let tokens: BTreeMap<String, Option<Address>> = raw
+            .tokens
+            .into_iter()
+            .map(|(symbol, value)| {
+                let address = address(errors, &key(&format!("tokens.{symbol}")), Some(value));
+                (symbol, address)
+            })
+            .collect();
+
+        let (Some(chain), Some(rpc_url)) = (chain, rpc_url) else {
+            continue;
+        };
+        let mut info = match registry.get(chain) {
+            Some(info) => info.clone(),
+            None => ChainInfo::new(chain, &name),
+        };
+        if known.is_none() && info.name != name {
+            info.aliases.push(name.clone());
+        }
+        if let Some(display_name) = display_name {
+            // The old name keeps working on input.
+            let previous = std::mem::replace(&mut info.name, display_name);
+            info.aliases.push(previous);
+        }
+        info.aliases.extend(aliases);
+        info.rpc_url = Some(rpc_url);
+        if let Some(depth) = finality_depth {
+            info.finality_depth = depth;
+        }
+        if let Some(explorer_url) = explorer_url {
+            info.explorer_url = Some(explorer_url);
+        }
+        if let Err(err) = registry.insert(info) {
+            errors.push(&prefix, err.to_string());
+            continue;
+        }
+        chains.push(ChainConfig {
+            chain,
+            vault,
+            tokens: tokens
+                .into_iter()
+                .filter_map(|(symbol, address)| Some((symbol, address?)))
+                .collect(),
+        });
+    }
+    chains
+}
+
+fn signers(
+    raw: BTreeMap<String, RawSigner>,
+    registry: &ChainRegistry,
+    errors: &mut Errors,
+) -> Vec<SignerConfig> {
+    let mut signers = Vec::new();
+    for (name, raw) in raw {
+        let prefix = format!("signers.{name}");
+        let chain = chain_key(errors, &prefix, &name, registry);
+        let key = format!("{prefix}.private_key");
+        let Some(private_key) = required(errors, &key, raw.private_key) else {
+            continue;
+        };
+        // The message never echoes the key itself.
+        let wallet = LocalWallet::from_str(private_key.trim())
+            .map_err(|_| errors.push(&key, "expected a 32-byte hex private key"))
+            .ok();
+        if let (Some(chain), Some(wallet)) = (chain, wallet) {
+            signers.push(SignerConfig { chain, wallet });
+        }
+    }
+    signers
+}
+
+fn route(errors: &mut Errors, key: &str, text: &str, registry: &ChainRegistry) -> Option<Route> {
+    let Some((from, to)) = text.split_once("->") else {
+        errors.push(key, format!("expected 'From->To', got '{text}'"));
+        return None;
+    };
+    let from_chain = chain_key(errors, key, from.trim(), registry);
+    let to_chain = chain_key(errors, key, to.trim(), registry);
+    let (from_chain, to_chain) = (from_chain?, to_chain?);
+    if from_chain == to_chain {
+        errors.push(
+            key,
+            format!("route '{text}' starts and ends on the same chain"),
+        );
+        return None;
+    }
+    Some(Route {
+        from_chain,
+        to_chain,
+    })
+}
+
+fn risk(raw: RawRisk, registry: &ChainRegistry, errors: &mut Errors) -> RiskConfig {
+    let allowed_routes = list(errors, "risk.allowed_routes", raw.allowed_routes).map(|routes| {
+        routes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, text)| {
+                route(errors, &format!("risk.allowed_routes[{i}]"), text, registry)
+            })
+            .collect()
+    });
+    let sanctioned_addresses =
+        text(errors, "risk.sanctions_file", raw.sanctions_file).and_then(|path| {
+            let path = PathBuf::from(path);
+            read_address_list(&path)
+                .map_err(|err| {
+                    errors.push(
+                        "risk.sanctions_file",
+                        format!("cannot read {}: {err}", path.display()),
+                    )
+                })
+                .ok()
+        });
+    RiskConfig {
+        max_intent_size: positive(errors, "risk.max_intent_size", raw.max_intent_size),
+        daily_notional_limit: positive(
+            errors,
+            "risk.daily_notional_limit",
+            raw.daily_notional_limit,
+        ),
+        manual_review_above: positive(errors, "risk.manual_review_above", raw.manual_review_above),
+        allowed_routes,
+        sanctioned_addresses,
+        balance_checks: flag(errors, "risk.balance_checks", raw.balance_checks).unwrap_or(false),
+    }
+}
+
+fn scheduler(raw: RawScheduler, errors: &mut Errors) -> SchedulerConfig {
+    let interval_ms = number(errors, "scheduler.interval_ms", raw.interval_ms);
+    let config = SchedulerConfig {
+        // Zero turns the timer off, leaving only the thresholds.
+        interval: match interval_ms.unwrap_or(DEFAULT_INTERVAL_MS) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        },
+        min_intents: positive(errors, "scheduler.min_intents", raw.min_intents).map(|n| n as usize),
+        min_notional: positive(errors, "scheduler.min_notional", raw.min_notional),
+    };
+    if let Err(err) = config.validate() {
+        errors.push("scheduler", err.to_string());
+    }
+    config
+}
+
+fn storage(raw: RawStorage, errors: &mut Errors) -> StorageConfig {
+    match text(errors, "storage.backend", raw.backend).as_deref() {
+        None | Some("memory") => StorageConfig::Memory,
+        Some(other) => {
+            errors.push(
+                "storage.backend",
+                format!("unsupported backend '{other}' (expected memory)"),
+            );
+            StorageConfig::Memory
+        }
+    }
+}
+
+fn feeder(raw: RawFeeder, errors: &mut Errors) -> Option<FeederConfig> {
+    if raw.url.is_none() {
+        errors.push("feeder.url", "required");
+    }
+    let url = url(
+        errors,
+        "feeder.url",
+        raw.url,
+        &["http", "https"],
+        "an http(s) url",
+    );
+    let api_key = required(errors, "feeder.api_key", raw.api_key);
+    Some(FeederConfig {
+        url: url?,
+        api_key: api_key?,
+    })
+}
+
+fn bridge(raw: RawBridge, errors: &mut Errors) -> Option<BridgeConfig> {
+    let wait_ms = number(errors, "bridge.wait_ms", raw.wait_ms);
+    let adapter = match required(errors, "bridge.adapter", raw.adapter)?.as_str() {
+        "mock" => BridgeAdapterConfig::Mock,
+        other => {
+            errors.push(
+                "bridge.adapter",
+                format!("unsupported adapter '{other}' (expected mock)"),
+            );
+            return None;
+        }
+    };
+    Some(BridgeConfig {
+        adapter,
+        wait: Duration::from_millis(wait_ms.unwrap_or(DEFAULT_BRIDGE_WAIT_MS)),
+    })
+}
+
+fn quotes(raw: RawQuotes, errors: &mut Errors) -> QuoteConfig {
+    let secret = text(errors, "quotes.secret", raw.secret);
+    // The length only, never the secret itself.
+    if secret
+        .as_ref()
+        .is_some_and(|secret| secret.len() < MIN_QUOTE_SECRET_LEN)
+    {
+        errors.push(
+            "quotes.secret",
+            format!("must be at least {MIN_QUOTE_SECRET_LEN} bytes"),
+        );
+    }
+    let ttl = positive(errors, "quotes.ttl_secs", raw.ttl_secs).map(Duration::from_secs);
+    QuoteConfig {
+        secret,
+        ttl: ttl.unwrap_or(DEFAULT_QUOTE_TTL),
+    }
+}
+
+fn rate_limit(raw: RawRateLimit, errors: &mut Errors) -> RateLimit {
+    let default = RateLimit::default();
+    let per_second = positive(errors, "rate_limit.per_second", raw.per_second);
+    let burst = positive(errors, "rate_limit.burst", raw.burst).and_then(|burst| {
+        u32::try_from(burst)
+            .map_err(|_| errors.push("rate_limit.burst", format!("must be at most {}", u32::MAX)))
+            .ok()
+    });
+    RateLimit {
+        per_second: per_second.map_or(default.per_second, |n| n as f64),
+        burst: burst.unwrap_or(default.burst),
+    }
+}
+
+fn inventory(
+    raw: RawInventory,
+    registry: &ChainRegistry,
+    errors: &mut Errors,
+) -> Option<InventoryConfig> {
+    let account = required(errors, "inventory.account", raw.account);
+    let spread_bps = number(errors, "inventory.spread_bps", raw.spread_bps);
+    if spread_bps.is_some_and(|bps| bps >= 10_000) {
+        errors.push("inventory.spread_bps", "must be below 10000");
+    }
+    if raw.chains.is_empty() {
+        errors.push("inventory.chains", "required");
+    }
+
+    let mut chains = BTreeMap::new();
+    for (name, raw) in raw.chains {
+        let prefix = format!("inventory.chains.{name}");
+        let chain = chain_key(errors, &prefix, &name, registry);
+        let key = format!("{prefix}.target_ratio");
+        if raw.target_ratio.is_none() {
+            errors.push(&key, "required");
+        }
+        let target_ratio = ratio(errors, &key, raw.target_ratio);
+        let min_balance = number(errors, &format!("{prefix}.min_balance"), raw.min_balance);
+        if let (Some(chain), Some(target_ratio)) = (chain, target_ratio) {
+            let target = ChainTarget {
+                target_ratio,
+                min_balance: min_balance.unwrap_or(0),
+            };
+            chains.insert(chain, target);
+        }
+    }
+    let total: f64 = chains.values().map(|target| target.target_ratio).sum();
+    if !chains.is_empty() && (total - 1.0).abs() > 1e-9 {
+        errors.push(
+            "inventory.chains",
+            format!("target ratios must add up to 1, got {total}"),
+        );
+    }
+
+    Some(InventoryConfig {
+        account: account?,
+        spread_bps: spread_bps.unwrap_or(DEFAULT_SPREAD_BPS),
+        chains,
+    })
+}
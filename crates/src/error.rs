@@ -0,0 +1,130 @@
+use crate::app::ReviewError;
+use crate::blockchain::BlockchainError;
+use crate::executor::ExecutionError;
+use crate::models::ChainId;
+use crate::quote::QuoteError;
+use crate::webhooks::WebhookError;
+use axum::extract::{FromRequest, FromRequestParts};
+use serde_json::json;
+use std::str::FromStr;
+
+pub use oceanlink_core::error::{ApiError, ErrorCode};
+
+impl From<ExecutionError> for ApiError {
+    fn from(err: ExecutionError) -> Self {
+        match err {
+            ExecutionError::Blockchain(err) | ExecutionError::Bridge { source: err, .. } => {
+                err.into()
+            }
+            ExecutionError::Invariants(report) => {
+                let details = json!({
+                    "solution_id": report.solution_id,
+                    "violations": report.violations,
+                });
+                ApiError::new(ErrorCode::InvalidSolution, report.to_string()).with_details(details)
+            }
+        }
+    }
+}
+
+impl From<BlockchainError> for ApiError {
+    fn from(err: BlockchainError) -> Self {
+        let code = match err {
+            BlockchainError::TransferFailed(_) => ErrorCode::TransferFailed,
+            BlockchainError::BalanceUnavailable(_)
+            | BlockchainError::RpcUnavailable(_)
+            | BlockchainError::SignerUnavailable(_) => ErrorCode::ChainUnavailable,
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl From<QuoteError> for ApiError {
+    fn from(err: QuoteError) -> Self {
+        ApiError::new(ErrorCode::InvalidRequest, format!("quote: {err}"))
+            .with_details(json!({ "field": "quote" }))
+    }
+}
+
+impl From<ReviewError> for ApiError {
+    fn from(err: ReviewError) -> Self {
+        let code = match err {
+            ReviewError::NotFound(_) => ErrorCode::NotFound,
+            ReviewError::NotUnderReview { .. } => ErrorCode::Conflict,
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+impl From<WebhookError> for ApiError {
+    fn from(err: WebhookError) -> Self {
+        let code = match err {
+            WebhookError::InvalidUrl(_) | WebhookError::PrivateHost(_) => ErrorCode::InvalidRequest,
+            WebhookError::NotFound(_) => ErrorCode::NotFound,
+        };
+        ApiError::new(code, err.to_string())
+    }
+}
+
+/// `axum::Json` with rejections rendered as [`ApiError`].
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+/// `axum::extract::Path` with rejections rendered as [`ApiError`].
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(ApiError))]
+pub struct ApiPath<T>(pub T);
+
+/// `axum::extract::Query` with rejections rendered as [`ApiError`].
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct ApiQuery<T>(pub T);
+
+/// Parses a chain name, naming the offending `field` on failure.
+pub fn parse_chain(field: &str, value: &str) -> Result<ChainId, ApiError> {
+    ChainId::from_str(value).map_err(|err| {
+        ApiError::new(ErrorCode::InvalidChain, format!("{field}: {err}"))
+            .with_details(json!({ "field": field, "value": value }))
+    })
+}
+
+/// Like [`parse_chain`], but also rejects registry chains missing from
+/// `served`.
+pub fn parse_served_chain(
+    field: &str,
+    value: &str,
+    served: &[ChainId],
+) -> Result<ChainId, ApiError> {
+    let chain = parse_chain(field, value)?;
+    if !served.contains(&chain) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidChain,
+            format!("{field}: chain '{chain}' is not served by this backend"),
+        )
+        .with_details(json!({ "field": field, "value": value })));
+    }
+    Ok(chain)
+}
+
+pub fn require_distinct_chains(from_chain: ChainId, to_chain: ChainId) -> Result<(), ApiError> {
+    if from_chain == to_chain {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("to_chain: must differ from from_chain '{from_chain}'"),
+        )
+        .with_details(json!({ "field": "to_chain", "value": to_chain.to_string() })));
+    }
+    Ok(())
+}
+
+pub fn require_positive_amount(field: &str, amount: u64) -> Result<(), ApiError> {
+    if amount == 0 {
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            format!("{field} must be greater than zero"),
+        )
+        .with_details(json!({ "field": field })));
+    }
+    Ok(())
+}
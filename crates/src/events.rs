@@ -0,0 +1,163 @@
+use crate::blockchain::TxHash;
+use crate::models::{BridgeTransfer, Intent, IntentStatus, Leg, NettingSolution};
+use crate::state::StatusListener;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Events buffered per subscriber before the slowest ones start missing
+/// events.
+const CAPACITY: usize = 1024;
+
+/// A change pushed to `/events` subscribers.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum Event {
+    IntentCreated {
+        intent: Intent,
+    },
+    IntentStatusChanged {
+        intent_id: Uuid,
+        user_id: String,
+        status: IntentStatus,
+    },
+    SolutionComputed {
+        solution: NettingSolution,
+    },
+    /// A leg of solution `solution_id` was submitted as `tx_hash`. `leg`
+    /// indexes the solution's `txs` as the subscriber sees them.
+    LegSent {
+        solution_id: String,
+        intent_ids: Vec<Uuid>,
+        leg: usize,
+        tx: Leg,
+        tx_hash: TxHash,
+    },
+    LegConfirmed {
+        solution_id: String,
+        intent_ids: Vec<Uuid>,
+        leg: usize,
+        tx: Leg,
+        tx_hash: TxHash,
+    },
+    /// Residual volume of an intent was handed to a bridge.
+    BridgeSent {
+        transfer: BridgeTransfer,
+        tx_hash: TxHash,
+    },
+    SolutionFailed {
+        solution: NettingSolution,
+        reason: String,
+    },
+}
+
+impl Event {
+    /// The intents the event is about.
+    pub fn intent_ids(&self) -> Vec<Uuid> {
+        match self {
+            Event::IntentCreated { intent } => vec![intent.id],
+            Event::IntentStatusChanged { intent_id, .. } => vec![*intent_id],
+            Event::SolutionComputed { solution } | Event::SolutionFailed { solution, .. } => {
+                solution.involved_intent_ids.clone()
+            }
+            Event::LegSent { intent_ids, .. } | Event::LegConfirmed { intent_ids, .. } => {
+                intent_ids.clone()
+            }
+            Event::BridgeSent { transfer, .. } => vec![transfer.intent_id],
+        }
+    }
+
+    /// Name used as the SSE `event:` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::IntentCreated { .. } => "IntentCreated",
+            Event::IntentStatusChanged { .. } => "IntentStatusChanged",
+            Event::SolutionComputed { .. } => "SolutionComputed",
+            Event::LegSent { .. } => "LegSent",
+            Event::LegConfirmed { .. } => "LegConfirmed",
+            Event::BridgeSent { .. } => "BridgeSent",
+            Event::SolutionFailed { .. } => "SolutionFailed",
+        }
+    }
+}
+
+/// Restricts a subscription to one user and/or one intent. Solution and leg
+/// events match a user who sends or receives in them, and an intent they
+/// settle.
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventFilter {
+    pub user_id: Option<String>,
+    pub intent_id: Option<Uuid>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        self.matches_user(event) && self.matches_intent(event)
+    }
+
+    fn matches_user(&self, event: &Event) -> bool {
+        let Some(user) = &self.user_id else {
+            return true;
+        };
+        let is_user = |candidate: &str| candidate.eq_ignore_ascii_case(user);
+        let in_tx = |tx: &Leg| is_user(&tx.from_user) || is_user(&tx.to_user);
+        match event {
+            Event::IntentCreated { intent } => is_user(&intent.user_id),
+            Event::IntentStatusChanged { user_id, .. } => is_user(user_id),
+            Event::SolutionComputed { solution } | Event::SolutionFailed { solution, .. } => {
+                solution.txs.iter().any(in_tx)
+                    || solution
+                        .bridged
+                        .iter()
+                        .any(|transfer| is_user(&transfer.user))
+            }
+            Event::LegSent { tx, .. } | Event::LegConfirmed { tx, .. } => in_tx(tx),
+            Event::BridgeSent { transfer, .. } => is_user(&transfer.user),
+        }
+    }
+
+    fn matches_intent(&self, event: &Event) -> bool {
+        let Some(id) = &self.intent_id else {
+            return true;
+        };
+        event.intent_ids().contains(id)
+    }
+}
+
+/// Fan-out of [`Event`]s to any number of subscribers. Publishing never
+/// blocks; subscribers that fall more than the buffer behind skip ahead.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    pub fn publish(&self, event: Event) {
+        // An error only means nobody is listening.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+/// Publishes [`Event::IntentStatusChanged`] for every status change.
+impl StatusListener for EventBus {
+    fn status_changed(&self, intent: &Intent) {
+        self.publish(Event::IntentStatusChanged {
+            intent_id: intent.id,
+            user_id: intent.user_id.clone(),
+            status: intent.status.clone(),
+        });
+    }
+}
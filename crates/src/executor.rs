@@ -1,12 +1,45 @@
-use crate::blockchain::{BlockchainClient, BlockchainError};
-use crate::models::NettingSolution;
+use crate::blockchain::{BlockchainClient, BlockchainError, TxHash};
+use crate::invariants::InvariantReport;
+use crate::makers::LegDelegate;
+use crate::models::{Leg, NettingSolution};
+use std::collections::HashSet;
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
+use tracing::{Instrument, info_span, warn};
+
+/// Progress of a single leg, reported while a solution executes. A leg is
+/// `Sent` once its transfer has been submitted, so a leg that fails to send
+/// is only ever `Failed`.
+#[derive(Clone, Copy, Debug)]
+pub enum LegProgress<'a> {
+    Sent {
+        leg: usize,
+        tx: &'a Leg,
+        tx_hash: &'a TxHash,
+    },
+    Confirmed {
+        leg: usize,
+        tx: &'a Leg,
+        tx_hash: &'a TxHash,
+    },
+    Failed {
+        leg: usize,
+        tx: &'a Leg,
+    },
+}
 
 #[derive(Debug, Error)]
 pub enum ExecutionError {
     #[error("blockchain error: {0}")]
     Blockchain(#[from] BlockchainError),
+    #[error(transparent)]
+    Invariants(#[from] InvariantReport),
+    #[error("bridge {via} failed: {source}")]
+    Bridge {
+        via: String,
+        source: BlockchainError,
+    },
 }
 
 pub struct Executor<C>
@@ -14,6 +47,7 @@ where
     C: BlockchainClient,
 {
     client: C,
+    delegate: Option<Arc<dyn LegDelegate>>,
 }
 
 impl<C> Executor<C>
@@ -21,7 +55,16 @@ where
     C: BlockchainClient,
 {
     pub fn new(client: C) -> Self {
-        Self { client }
+        Self {
+            client,
+            delegate: None,
+        }
+    }
+
+    /// Sends the legs of makers through `delegate` instead of the client.
+    pub fn with_delegate(mut self, delegate: Arc<dyn LegDelegate>) -> Self {
+        self.delegate = Some(delegate);
+        self
     }
 
     pub fn client(&self) -> C {
@@ -29,10 +72,64 @@ where
     }
 
     pub async fn execute_solution(&self, solution: &NettingSolution) -> Result<(), ExecutionError> {
-        for tx in &solution.txs {
-            self.client
-                .send_local_transfer(tx.chain, &tx.from_user, &tx.to_user, tx.amount)
-                .await?;
+        self.execute_solution_with(solution, &HashSet::new(), |_| {})
+            .await
+    }
+
+    /// Like [`Self::execute_solution`], calling `on_leg` as each leg is sent
+    /// and confirmed. Legs paid by one of `makers` go to the delegate, if
+    /// there is one. Legs run in order and execution stops at the first
+    /// failure.
+    pub async fn execute_solution_with<F>(
+        &self,
+        solution: &NettingSolution,
+        makers: &HashSet<String>,
+        mut on_leg: F,
+    ) -> Result<(), ExecutionError>
+    where
+        F: FnMut(LegProgress<'_>),
+    {
+        for (leg, tx) in solution.txs.iter().enumerate() {
+            let delegate = self
+                .delegate
+                .as_ref()
+                .filter(|_| makers.contains(&tx.from_user));
+            let span = info_span!(
+                "leg",
+                leg,
+                chain = %tx.chain,
+                from = %tx.from_user,
+                to = %tx.to_user,
+                amount = tx.amount.get(),
+                delegated = delegate.is_some(),
+            );
+            let sent = match delegate {
+                Some(delegate) => delegate.send_leg(tx).instrument(span.clone()).await,
+                None => {
+                    self.client
+                        .send_local_transfer(tx.chain, &tx.from_user, &tx.to_user, tx.amount.get())
+                        .instrument(span.clone())
+                        .await
+                }
+            };
+            let tx_hash = match sent {
+                Ok(tx_hash) => tx_hash,
+                Err(err) => {
+                    span.in_scope(|| warn!(error = %err, "leg failed"));
+                    on_leg(LegProgress::Failed { leg, tx });
+                    return Err(err.into());
+                }
+            };
+            on_leg(LegProgress::Sent {
+                leg,
+                tx,
+                tx_hash: &tx_hash,
+            });
+            on_leg(LegProgress::Confirmed {
+                leg,
+                tx,
+                tx_hash: &tx_hash,
+            });
         }
         Ok(())
     }
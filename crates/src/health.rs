@@ -0,0 +1,137 @@
+use crate::app::AppContext;
+use crate::models::ChainId;
+use crate::scheduler::HEARTBEAT_TIMEOUT;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Upper bound on a single dependency probe, so a hung RPC node fails
+/// readiness instead of hanging it.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of one dependency probe.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Check {
+    /// `store`, `rpc`, `signer` or `scheduler`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainId>,
+    pub ok: bool,
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+/// Body of `/readyz`. `ready` is true only if every check passed.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<Check>,
+}
+
+/// Why a probe failed. Only `reason` is returned to callers: RPC errors can
+/// carry endpoint URLs with API keys in them, so `detail` is only logged.
+struct Failure {
+    reason: &'static str,
+    detail: String,
+}
+
+impl Failure {
+    fn new(reason: &'static str, detail: impl ToString) -> Self {
+        Self {
+            reason,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Runs every readiness check: the store, RPC reachability and chain id for
+/// each served chain, the signer for each served chain and, if a scheduler
+/// was started on `ctx`, its heartbeat.
+pub async fn readiness(ctx: &AppContext) -> Readiness {
+    let chains = ctx.chain_probe();
+    let mut checks = vec![
+        probe("store", None, async {
+            ctx.state
+                .check()
+                .map(|()| "ok".into())
+                .map_err(|err| Failure::new("store unavailable", err))
+        })
+        .await,
+    ];
+
+    for &chain in ctx.chains() {
+        checks.push(
+            probe("rpc", Some(chain), async {
+                let id = chains
+                    .chain_id(chain)
+                    .await
+                    .map_err(|err| Failure::new("rpc unreachable", err))?;
+                let expected = chain.as_u64();
+                if id != expected {
+                    return Err(Failure::new(
+                        "unexpected chain id",
+                        format!("chain id {id}, expected {expected}"),
+                    ));
+                }
+                Ok(format!("chain id {id}"))
+            })
+            .await,
+        );
+        checks.push(
+            probe("signer", Some(chain), async {
+                chains
+                    .signer_address(chain)
+                    .await
+                    .map_err(|err| Failure::new("no signer", err))
+            })
+            .await,
+        );
+    }
+
+    if let Some(age) = ctx.scheduler_heartbeat().age() {
+        let ok = age <= HEARTBEAT_TIMEOUT;
+        checks.push(Check {
+            name: "scheduler".into(),
+            chain: None,
+            ok,
+            message: format!("last heartbeat {}ms ago", age.as_millis()),
+            latency_ms: 0,
+        });
+    }
+
+    Readiness {
+        ready: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+async fn probe<F>(name: &str, chain: Option<ChainId>, check: F) -> Check
+where
+    F: Future<Output = Result<String, Failure>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Failure::new(
+                "timed out",
+                format!("no answer within {}s", CHECK_TIMEOUT.as_secs()),
+            ))
+        });
+    let (ok, message) = match result {
+        Ok(message) => (true, message),
+        Err(failure) => {
+            warn!(check = name, chain = ?chain, error = %failure.detail, "readiness check failed");
+            (false, failure.reason.to_string())
+        }
+    };
+    Check {
+        name: name.into(),
+        chain,
+        ok,
+        message,
+        latency_ms: started.elapsed().as_millis() as u64,
+    }
+}
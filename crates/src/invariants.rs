@@ -0,0 +1,232 @@
+use crate::models::{ChainId, Intent, NettingSolution};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One broken invariant of a solution.
+#[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Violation {
+    #[error("leg {leg} is empty or pays its own sender")]
+    InvalidLeg { leg: usize },
+    #[error("intent {intent_id} is not open")]
+    UnknownIntent { intent_id: Uuid },
+    #[error("intent {intent_id} is listed more than once")]
+    DuplicateIntent { intent_id: Uuid },
+    #[error("intent {intent_id} is settled but not listed as involved")]
+    UnlistedIntent { intent_id: Uuid },
+    #[error("intent {intent_id} is involved but nothing settles it")]
+    UnsettledIntent { intent_id: Uuid },
+    #[error("{user} moves funds on {chain} without an involved intent there")]
+    UninvolvedParty { chain: ChainId, user: String },
+    #[error("{user} sends {debited} on {chain} but their intents only cover {available}")]
+    Overspent {
+        chain: ChainId,
+        user: String,
+        debited: u64,
+        available: u64,
+    },
+    #[error("{user} receives {credited} on {chain} but their intents only ask for {expected}")]
+    Overpaid {
+        chain: ChainId,
+        user: String,
+        credited: u64,
+        expected: u64,
+    },
+    #[error(
+        "{user} sends {debited} and receives {credited}, expected to receive {debited} less {fees} in fees"
+    )]
+    Unbalanced {
+        user: String,
+        debited: u64,
+        credited: u64,
+        fees: u64,
+    },
+}
+
+/// Every invariant a solution breaks, reported before any of it is sent.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub struct InvariantReport {
+    pub solution_id: String,
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for InvariantReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "solution {} breaks {} invariants:",
+            self.solution_id,
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  - {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Position {
+    debited: u64,
+    credited: u64,
+}
+
+/// Checks `solution` against the open `intents` it settles. `solver` is
+/// the inventory account, whose legs carry its own funds rather than an
+/// intent's.
+///
+/// Legs are compressed, so they are checked per user: on each chain a user
+/// sends no more than their intents from it and receives no more than their
+/// intents to it, and across chains they receive what they send less the
+/// fees charged on their inventory fills. Bridged volume moves outside the
+/// legs and only counts against the intents' amounts.
+pub fn check_solution(
+    intents: &[Intent],
+    solution: &NettingSolution,
+    solver: Option<&str>,
+) -> Result<(), InvariantReport> {
+    let mut violations = Vec::new();
+    let open: HashMap<Uuid, &Intent> = intents.iter().map(|intent| (intent.id, intent)).collect();
+
+    let mut involved = Vec::new();
+    let mut listed = HashSet::new();
+    for &intent_id in &solution.involved_intent_ids {
+        if !listed.insert(intent_id) {
+            violations.push(Violation::DuplicateIntent { intent_id });
+            continue;
+        }
+        match open.get(&intent_id) {
+            Some(intent) => involved.push(*intent),
+            None => violations.push(Violation::UnknownIntent { intent_id }),
+        }
+    }
+
+    // Funds each user's involved intents may send from and receive on
+    // each chain.
+    let mut sendable: HashMap<(ChainId, &str), u64> = HashMap::new();
+    let mut receivable: HashMap<(ChainId, &str), u64> = HashMap::new();
+    for intent in &involved {
+        *sendable
+            .entry((intent.from_chain, &intent.user_id))
+            .or_default() += intent.amount.get();
+        *receivable
+            .entry((intent.to_chain, &intent.user_id))
+            .or_default() += intent.amount.get();
+    }
+
+    let mut positions: BTreeMap<(ChainId, &str), Position> = BTreeMap::new();
+    for (leg, tx) in solution.txs.iter().enumerate() {
+        if tx.amount == 0 || tx.from_user == tx.to_user {
+            violations.push(Violation::InvalidLeg { leg });
+            continue;
+        }
+        positions
+            .entry((tx.chain, &tx.from_user))
+            .or_default()
+            .debited += tx.amount.get();
+        positions
+            .entry((tx.chain, &tx.to_user))
+            .or_default()
+            .credited += tx.amount.get();
+    }
+
+    let mut settled = BTreeSet::new();
+    let mut fees: HashMap<&str, u64> = HashMap::new();
+    for fill in &solution.inventory_fills {
+        settled.insert(fill.intent_id);
+        *fees.entry(&fill.user).or_default() += fill.fee.get();
+    }
+    let mut bridged: HashMap<(ChainId, &str), u64> = HashMap::new();
+    for transfer in &solution.bridged {
+        settled.insert(transfer.intent_id);
+        *bridged
+            .entry((transfer.from_chain, &transfer.user))
+            .or_default() += transfer.amount.get();
+    }
+    for &intent_id in &settled {
+        if !listed.contains(&intent_id) {
+            violations.push(Violation::UnlistedIntent { intent_id });
+        }
+    }
+
+    let mut totals: BTreeMap<&str, Position> = BTreeMap::new();
+    for (&(chain, user), position) in &positions {
+        if Some(user) == solver {
+            continue;
+        }
+        let debited = position.debited.saturating_sub(position.credited);
+        let credited = position.credited.saturating_sub(position.debited);
+        let available = sendable.get(&(chain, user)).copied();
+        let expected = receivable.get(&(chain, user)).copied();
+        if (debited > 0 && available.is_none()) || (credited > 0 && expected.is_none()) {
+            violations.push(Violation::UninvolvedParty {
+                chain,
+                user: user.to_string(),
+            });
+            continue;
+        }
+
+        let sent = debited + bridged.get(&(chain, user)).copied().unwrap_or(0);
+        let available = available.unwrap_or(0);
+        if sent > available {
+            violations.push(Violation::Overspent {
+                chain,
+                user: user.to_string(),
+                debited: sent,
+                available,
+            });
+        }
+        let expected = expected.unwrap_or(0);
+        if credited > expected {
+            violations.push(Violation::Overpaid {
+                chain,
+                user: user.to_string(),
+                credited,
+                expected,
+            });
+        }
+        let total = totals.entry(user).or_default();
+        total.debited += debited;
+        total.credited += credited;
+    }
+
+    for (user, total) in totals {
+        let fees = fees.get(user).copied().unwrap_or(0);
+        if total.credited + fees != total.debited {
+            violations.push(Violation::Unbalanced {
+                user: user.to_string(),
+                debited: total.debited,
+                credited: total.credited,
+                fees,
+            });
+        }
+    }
+
+    // An involved intent's user must move funds somewhere in the solution.
+    for intent in &involved {
+        let moves = |chain| {
+            positions
+                .get(&(chain, intent.user_id.as_str()))
+                .is_some_and(|position| position.debited != position.credited)
+        };
+        let touched = moves(intent.from_chain) || moves(intent.to_chain);
+        if !touched && !settled.contains(&intent.id) {
+            violations.push(Violation::UnsettledIntent {
+                intent_id: intent.id,
+            });
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(InvariantReport {
+            solution_id: solution.id.clone(),
+            violations,
+        })
+    }
+}
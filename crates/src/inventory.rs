@@ -0,0 +1,194 @@
+use crate::blockchain::{BlockchainClient, BlockchainError};
+use crate::models::{Amount, ChainId, InventoryFill, Leg};
+use crate::solver::Residual;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Basis points in one whole.
+const BPS: u64 = 10_000;
+
+/// How much of the solver's inventory a chain should hold.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainTarget {
+    /// Share of the total inventory, between 0 and 1. Fills paying out of a
+    /// chain below it cost twice the spread.
+    pub target_ratio: f64,
+    /// At or below this balance the chain counts as depleted.
+    pub min_balance: u64,
+}
+
+/// The solver's own funds, held by `account` on each tracked chain.
+///
+/// Intents netting cannot settle are filled from it straight away: the user
+/// pays the solver on the source chain and the solver pays the user, less
+/// its spread, on the destination chain. Balances are read from the chain
+/// each round, so transfers made outside the solver are picked up too.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    account: String,
+    spread_bps: u64,
+    chains: BTreeMap<ChainId, ChainTarget>,
+}
+
+/// One chain's inventory, as served on `/admin/inventory`.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+pub struct ChainInventory {
+    pub chain: ChainId,
+    pub balance: Amount,
+    /// Share of the total inventory held on this chain.
+    pub ratio: f64,
+    pub target_ratio: f64,
+    pub min_balance: Amount,
+    pub depleted: bool,
+}
+
+impl Inventory {
+    /// Spreads of 10000 bps or more take the whole amount, which leaves
+    /// nothing to pay out, so no intent is filled.
+    pub fn new(account: impl Into<String>, spread_bps: u64) -> Self {
+        Self {
+            account: account.into(),
+            spread_bps,
+            chains: BTreeMap::new(),
+        }
+    }
+
+    /// Holds inventory on `chain`. Intents paying out on other chains are
+    /// never filled.
+    pub fn with_chain(mut self, chain: ChainId, target: ChainTarget) -> Self {
+        self.chains.insert(chain, target);
+        self
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Spread charged on `amount` paid out on `to_chain`, rounded up and at
+    /// most `amount`. Paying out of a chain that already holds less than its
+    /// target share of `balances` moves the inventory further from its
+    /// targets, so it costs twice the spread.
+    pub fn fee(
+        &self,
+        amount: Amount,
+        to_chain: ChainId,
+        balances: &BTreeMap<ChainId, Amount>,
+    ) -> Amount {
+        let below_target = self.share(to_chain, balances).is_some_and(|share| {
+            self.chains
+                .get(&to_chain)
+                .is_some_and(|target| share < target.target_ratio)
+        });
+        let spread_bps = if below_target {
+            self.spread_bps.saturating_mul(2)
+        } else {
+            self.spread_bps
+        };
+        let fee = (u128::from(amount.get()) * u128::from(spread_bps.min(BPS))).div_ceil(BPS.into());
+        Amount::from(fee as u64)
+    }
+
+    /// Share of the total inventory `balances` hold on `chain`, `None` while
+    /// the inventory is empty.
+    fn share(&self, chain: ChainId, balances: &BTreeMap<ChainId, Amount>) -> Option<f64> {
+        let total: Amount = balances.values().copied().sum();
+        if total == 0 {
+            return None;
+        }
+        let balance = balances.get(&chain).copied().unwrap_or_default();
+        Some(balance.get() as f64 / total.get() as f64)
+    }
+
+    /// Current balance of the solver account on every tracked chain.
+    pub async fn balances<C: BlockchainClient>(
+        &self,
+        client: &C,
+    ) -> Result<BTreeMap<ChainId, Amount>, BlockchainError> {
+        let mut balances = BTreeMap::new();
+        for &chain in self.chains.keys() {
+            let balance = client.balance_of(chain, &self.account).await?;
+            balances.insert(chain, Amount::from(balance));
+        }
+        Ok(balances)
+    }
+
+    /// Fills whichever `residuals` the inventory can pay out in full,
+    /// updating `balances` as it goes. Returns the fills and the residuals
+    /// left over, both in the order given.
+    pub fn fill(
+        &self,
+        residuals: Vec<Residual>,
+        balances: &mut BTreeMap<ChainId, Amount>,
+    ) -> (Vec<InventoryFill>, Vec<Residual>) {
+        let mut fills = Vec::new();
+        let mut unfilled = Vec::new();
+        for residual in residuals {
+            let intent = &residual.intent;
+            let fee = self.fee(residual.amount, intent.to_chain, balances);
+            let payout = residual.amount - fee;
+            let fillable = intent.user_id != self.account
+                && payout > 0
+                && balances
+                    .get(&intent.to_chain)
+                    .is_some_and(|balance| *balance >= payout);
+            if !fillable {
+                unfilled.push(residual);
+                continue;
+            }
+
+            if let Some(balance) = balances.get_mut(&intent.to_chain) {
+                *balance -= payout;
+            }
+            if let Some(balance) = balances.get_mut(&intent.from_chain) {
+                *balance += residual.amount;
+            }
+            fills.push(InventoryFill {
+                intent_id: intent.id,
+                user: intent.user_id.clone(),
+                from_chain: intent.from_chain,
+                to_chain: intent.to_chain,
+                amount: residual.amount,
+                fee,
+            });
+        }
+        (fills, unfilled)
+    }
+
+    /// The two legs of `fill`: the user paying the solver and the solver
+    /// paying the user.
+    pub fn legs(&self, fill: &InventoryFill) -> [Leg; 2] {
+        [
+            Leg {
+                chain: fill.from_chain,
+                from_user: fill.user.clone(),
+                to_user: self.account.clone(),
+                amount: fill.amount,
+            },
+            Leg {
+                chain: fill.to_chain,
+                from_user: self.account.clone(),
+                to_user: fill.user.clone(),
+                amount: fill.amount - fill.fee,
+            },
+        ]
+    }
+
+    /// Each tracked chain's balance against its target.
+    pub fn report(&self, balances: &BTreeMap<ChainId, Amount>) -> Vec<ChainInventory> {
+        self.chains
+            .iter()
+            .map(|(&chain, target)| {
+                let balance = balances.get(&chain).copied().unwrap_or_default();
+                ChainInventory {
+                    chain,
+                    balance,
+                    ratio: self.share(chain, balances).unwrap_or(0.0),
+                    target_ratio: target.target_ratio,
+                    min_balance: target.min_balance.into(),
+                    depleted: balance <= target.min_balance,
+                }
+            })
+            .collect()
+    }
+}
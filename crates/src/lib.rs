@@ -1,8 +1,23 @@
 pub mod api;
 pub mod app;
+pub mod auth;
 pub mod blockchain;
+pub mod bridge;
+pub mod config;
+pub mod error;
+pub mod events;
 pub mod executor;
+pub mod health;
+pub mod invariants;
+pub mod inventory;
+pub mod makers;
+pub mod metrics;
 pub mod models;
+pub mod quote;
 pub mod risk;
+pub mod scheduler;
+pub mod signing;
+pub mod simulation;
 pub mod solver;
 pub mod state;
+pub mod webhooks;
@@ -1,27 +1,152 @@
+use clap::Parser;
 use ocean_link_backend::api::router;
 use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::{Authenticator, RateLimit, Role};
 use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::config::Config;
 use ocean_link_backend::executor::Executor;
-use ocean_link_backend::risk::{AlwaysPassRiskEngine, RiskEngine};
+use ocean_link_backend::makers::FeederClient;
+use ocean_link_backend::scheduler::Scheduler;
 use ocean_link_backend::state::State;
+use ocean_link_backend::webhooks::{HttpTransport, RetryPolicy, WebhookDispatcher};
+use oceanlink_core::chain;
+use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+/// OceanLink netting backend.
+#[derive(Parser)]
+struct Cli {
+    /// TOML configuration file.
+    #[arg(long, env = "OCEANLINK_CONFIG")]
+    config: Option<PathBuf>,
+    /// Address to listen on; overrides `bind`.
+    #[arg(long)]
+    bind: Option<String>,
+    /// Overrides any setting, e.g. `--set scheduler.interval_ms=1000`.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+}
 
 #[tokio::main]
 async fn main() {
-    let state = Arc::new(Mutex::new(State::new()));
-    let risk_engine: Arc<dyn RiskEngine + Send + Sync> = Arc::new(AlwaysPassRiskEngine::default());
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let mut overrides = cli.overrides;
+    if let Some(bind) = cli.bind {
+        overrides.push(format!("bind={bind}"));
+    }
+    let config = match Config::load(cli.config.as_deref(), env::vars(), &overrides) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("{errors}");
+            process::exit(2);
+        }
+    };
+    chain::install(config.registry.clone());
+    for chain in &config.chains {
+        let info = chain
+            .chain
+            .info()
+            .expect("configured chains are registered");
+        info!(
+            chain = %info.name,
+            chain_id = info.id.as_u64(),
+            rpc = info.rpc_url.as_deref().unwrap_or_default(),
+            finality_depth = info.finality_depth,
+            "chain configured"
+        );
+    }
+    for signer in &config.signers {
+        info!(chain = %signer.chain, address = ?signer.address(), "signer configured");
+    }
+
+    let state = Arc::new(State::new());
     let blockchain = MockBlockchainClient::default();
-    let executor = Arc::new(Executor::new(blockchain));
-    let ctx = AppContext::new(state, risk_engine, executor);
+    let risk_engine = config.risk.engine(blockchain.clone());
+    let mut executor = Executor::new(blockchain);
+    if let Some(feeder) = &config.feeder {
+        info!(url = %feeder.url, "maker legs go to the feeder");
+        let client = FeederClient::new(&feeder.url, &feeder.api_key, Duration::from_secs(60));
+        executor = executor.with_delegate(Arc::new(client));
+    }
+    let executor = Arc::new(executor);
+    let webhooks = WebhookDispatcher::new(
+        HttpTransport::new(Duration::from_secs(10)),
+        RetryPolicy::default(),
+    );
+    let mut ctx = AppContext::new(state, risk_engine, executor)
+        .with_authenticator(authenticator(config.rate_limit))
+        .with_webhooks(webhooks)
+        .with_quoter(config.quotes.quoter(config.scheduler.interval));
+    if !config.chains.is_empty() {
+        ctx = ctx
+            .with_chains(config.chains.iter().map(|chain| chain.chain))
+            .with_chain_probe(config.chain_probe());
+    }
+    ctx = ctx.with_signing_domain(config.signing_domain());
+    if let Some(bridge) = &config.bridge {
+        info!(adapter = ?bridge.adapter, wait = ?bridge.wait, "residual volume is bridged");
+        ctx = ctx.with_rebalancer(bridge.rebalancer());
+    }
+    if let Some(inventory) = &config.inventory {
+        info!(account = %inventory.account, spread_bps = inventory.spread_bps, "solver inventory fills residual intents");
+        ctx = ctx.with_inventory(inventory.inventory());
+    }
+
+    let scheduler =
+        Scheduler::spawn(ctx.clone(), config.scheduler).expect("validated with the config");
 
     let app = router(ctx);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:8080")
+    let listener = tokio::net::TcpListener::bind(config.bind)
         .await
         .expect("failed to bind listener");
-    println!("Backend running on http://127.0.0.1:8080");
-    axum::serve(listener, app)
+    info!(storage = %config.storage, "backend running on http://{}", config.bind);
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .expect("server crashed unexpectedly");
+
+    scheduler.shutdown().await;
+}
+
+async fn shutdown_signal() {
+    tokio::signal::ctrl_c()
         .await
-        .expect("server crashed unexpectedly");
+        .expect("failed to install Ctrl+C handler");
+}
+
+/// Loads `ADMIN_API_KEY` and the comma-separated `API_KEYS` and
+/// `MAKER_API_KEYS` from the environment. With none set, every request is
+/// refused.
+fn authenticator(limit: RateLimit) -> Authenticator {
+    let auth = Authenticator::new(limit);
+    if let Ok(key) = env::var("ADMIN_API_KEY") {
+        auth.keys.insert(&key, Role::Admin);
+    }
+    if let Ok(keys) = env::var("API_KEYS") {
+        for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            auth.keys.insert(key, Role::User);
+        }
+    }
+    if let Ok(keys) = env::var("MAKER_API_KEYS") {
+        for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            auth.keys.insert(key, Role::Maker);
+        }
+    }
+    auth
 }
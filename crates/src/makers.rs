@@ -0,0 +1,75 @@
+use crate::auth::API_KEY_HEADER;
+use crate::blockchain::{BlockchainError, TxHash};
+use crate::models::Leg;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Sends legs paid by makers whose keys the backend does not hold, such as
+/// the feeder's. The [`crate::executor::Executor`] hands it every leg whose
+/// sender posted a maker intent in the round.
+#[async_trait]
+pub trait LegDelegate: Send + Sync {
+    /// Signs and sends `leg`, returning its transaction hash once accepted.
+    async fn send_leg(&self, leg: &Leg) -> Result<TxHash, BlockchainError>;
+}
+
+/// Delegates maker legs to a feeder by `POST`ing each one to its `/legs`
+/// route.
+#[derive(Clone)]
+pub struct FeederClient {
+    client: reqwest::Client,
+    url: String,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct LegReceipt {
+    tx_hash: TxHash,
+}
+
+impl FeederClient {
+    /// `url` is the feeder's base url; `api_key` one of its admin keys.
+    pub fn new(url: &str, api_key: &str, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("http client builds");
+        Self {
+            client,
+            url: format!("{}/legs", url.trim_end_matches('/')),
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl LegDelegate for FeederClient {
+    async fn send_leg(&self, leg: &Leg) -> Result<TxHash, BlockchainError> {
+        let body = serde_json::to_vec(leg).expect("legs serialize");
+        let response = self
+            .client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .header(API_KEY_HEADER, &self.api_key)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| BlockchainError::RpcUnavailable(format!("feeder: {err}")))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| BlockchainError::RpcUnavailable(format!("feeder: {err}")))?;
+        if !status.is_success() {
+            return Err(BlockchainError::TransferFailed(format!(
+                "feeder answered {status}: {}",
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        let receipt: LegReceipt = serde_json::from_slice(&body).map_err(|err| {
+            BlockchainError::TransferFailed(format!("unreadable feeder receipt: {err}"))
+        })?;
+        Ok(receipt.tx_hash)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::inventory::ChainInventory;
+use crate::models::ChainId;
+use crate::state::State;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::time::Duration;
+
+/// Prometheus metrics for the backend, rendered by `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    orderbook_intents: IntGaugeVec,
+    orderbook_notional: IntGaugeVec,
+    netted_volume: IntCounter,
+    match_duration: Histogram,
+    leg_failures: IntCounterVec,
+    inventory_balance: IntGaugeVec,
+    inventory_depleted: IntGaugeVec,
+    spread_earned: IntCounter,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let route = ["from_chain", "to_chain"];
+        let orderbook_intents = IntGaugeVec::new(
+            Opts::new("oceanlink_orderbook_intents", "Open intents per route"),
+            &route,
+        )
+        .expect("valid metric");
+        let orderbook_notional = IntGaugeVec::new(
+            Opts::new(
+                "oceanlink_orderbook_notional",
+                "Total amount of open intents per route",
+            ),
+            &route,
+        )
+        .expect("valid metric");
+        let netted_volume = IntCounter::new(
+            "oceanlink_netted_volume_total",
+            "Amount matched between opposite intents, counted once per netted pair",
+        )
+        .expect("valid metric");
+        let match_duration = Histogram::with_opts(HistogramOpts::new(
+            "oceanlink_match_round_duration_seconds",
+            "Time to compute and execute a match round that found a solution",
+        ))
+        .expect("valid metric");
+        let leg_failures = IntCounterVec::new(
+            Opts::new("oceanlink_leg_failures_total", "Failed solution legs"),
+            &["chain"],
+        )
+        .expect("valid metric");
+
+        let inventory_balance = IntGaugeVec::new(
+            Opts::new("oceanlink_inventory_balance", "Solver inventory per chain"),
+            &["chain"],
+        )
+        .expect("valid metric");
+        let inventory_depleted = IntGaugeVec::new(
+            Opts::new(
+                "oceanlink_inventory_depleted",
+                "1 while a chain's solver inventory is at or below its minimum",
+            ),
+            &["chain"],
+        )
+        .expect("valid metric");
+        let spread_earned = IntCounter::new(
+            "oceanlink_inventory_spread_total",
+            "Spread charged on intents filled from inventory",
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(orderbook_intents.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(orderbook_notional.clone()),
+            Box::new(netted_volume.clone()),
+            Box::new(match_duration.clone()),
+            Box::new(leg_failures.clone()),
+            Box::new(inventory_balance.clone()),
+            Box::new(inventory_depleted.clone()),
+            Box::new(spread_earned.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            orderbook_intents,
+            orderbook_notional,
+            netted_volume,
+            match_duration,
+            leg_failures,
+            inventory_balance,
+            inventory_depleted,
+            spread_earned,
+        }
+    }
+
+    pub fn observe_match_round(&self, elapsed: Duration) {
+        self.match_duration.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_netted_volume(&self, amount: u64) {
+        self.netted_volume.inc_by(amount);
+    }
+
+    pub fn record_leg_failure(&self, chain: ChainId) {
+        self.leg_failures
+            .with_label_values(&[&chain.to_string()])
+            .inc();
+    }
+
+    pub fn record_spread(&self, fee: u64) {
+        self.spread_earned.inc_by(fee);
+    }
+
+    pub fn record_inventory(&self, inventory: &[ChainInventory]) {
+        for chain in inventory {
+            let labels = [chain.chain.to_string()];
+            let labels = [labels[0].as_str()];
+            self.inventory_balance
+                .with_label_values(&labels)
+                .set(chain.balance.get().min(i64::MAX as u64) as i64);
+            self.inventory_depleted
+                .with_label_values(&labels)
+                .set(chain.depleted.into());
+        }
+    }
+
+    /// Refreshes the orderbook gauges from `state` and encodes every metric
+    /// in the Prometheus text format.
+    pub fn render(&self, state: &State) -> String {
+        self.orderbook_intents.reset();
+        self.orderbook_notional.reset();
+        for (route, intents, notional) in state.orderbook_depth_by_route() {
+            let labels = [route.from_chain.to_string(), route.to_chain.to_string()];
+            let labels = [labels[0].as_str(), labels[1].as_str()];
+            self.orderbook_intents
+                .with_label_values(&labels)
+                .set(intents as i64);
+            self.orderbook_notional
+                .with_label_values(&labels)
+                .set(notional.min(i64::MAX as u64) as i64);
+        }
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding cannot fail");
+        String::from_utf8(buffer).expect("metrics are utf-8")
+    }
+}
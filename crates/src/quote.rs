@@ -0,0 +1,213 @@
+use crate::bridge::Rebalancer;
+use crate::inventory::Inventory;
+use crate::models::{Amount, ChainId, Intent, Route};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What an intent would get if submitted now, signed by the backend and
+/// valid until `expires_at`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Quote {
+    pub id: Uuid,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: Amount,
+    /// Volume the opposite route nets right away, after the intents queued
+    /// ahead on the same route.
+    pub nettable: Amount,
+    /// Volume the solver's inventory fills straight away.
+    pub inventory_fill: Amount,
+    /// Volume bridged once it has waited for a counterparty.
+    pub bridged: Amount,
+    pub uses_bridge: bool,
+    /// The inventory's spread; netting and bridging charge nothing.
+    pub fee: Amount,
+    /// What arrives on `to_chain` from the nettable, filled and bridged
+    /// volume, less the fee. Anything else waits for a counterparty.
+    pub receive: Amount,
+    /// Until the last of `receive` is sent, or `None` when part of the
+    /// amount waits for a counterparty with no bridge to fall back on.
+    pub expected_wait_ms: Option<u64>,
+    /// Unix seconds.
+    pub issued_at: u64,
+    /// Unix seconds after which the quote is void.
+    pub expires_at: u64,
+    /// Hex HMAC-SHA256 of the quote's other fields, keyed with the backend's
+    /// quote secret.
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum QuoteError {
+    #[error("quote expired at {expires_at}")]
+    Expired { expires_at: u64 },
+    #[error("quote signature does not match")]
+    BadSignature,
+    #[error("quote was issued for another route or amount")]
+    WrongIntent,
+}
+
+/// Default validity of a quote.
+pub const DEFAULT_QUOTE_TTL: Duration = Duration::from_secs(30);
+
+/// Prices intents against the live orderbook and signs the result.
+#[derive(Clone)]
+pub struct Quoter {
+    secret: String,
+    ttl: Duration,
+    round_interval: Option<Duration>,
+}
+
+impl fmt::Debug for Quoter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Quoter")
+            .field("ttl", &self.ttl)
+            .field("round_interval", &self.round_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Quoter {
+    pub fn new(secret: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            secret: secret.into(),
+            ttl,
+            round_interval: None,
+        }
+    }
+
+    /// Counts the wait for the next scheduled round into the expected wait.
+    pub fn with_round_interval(mut self, interval: Option<Duration>) -> Self {
+        self.round_interval = interval;
+        self
+    }
+
+    /// Quotes `amount` on `route` as of `now` (unix seconds). `inventory`
+    /// comes with the solver's current balances.
+    ///
+    /// The intent would join the end of the orderbook, so the opposite
+    /// route's depth nets the intents ahead of it first. What is left goes
+    /// to the inventory if it can pay all of it, and otherwise over the
+    /// bridge: in the same round when part of the intent nets, or once the
+    /// rebalancer's wait runs out when none of it does.
+    pub fn quote(
+        &self,
+        route: Route,
+        amount: Amount,
+        orderbook: &[Intent],
+        inventory: Option<(&Inventory, &BTreeMap<ChainId, Amount>)>,
+        rebalancer: Option<&Rebalancer>,
+        now: u64,
+    ) -> Quote {
+        let Route {
+            from_chain,
+            to_chain,
+        } = route;
+        let depth = |from, to| -> Amount {
+            orderbook
+                .iter()
+                .filter(|intent| intent.from_chain == from && intent.to_chain == to)
+                .map(|intent| intent.amount)
+                .sum()
+        };
+        let available = depth(to_chain, from_chain).saturating_sub(depth(from_chain, to_chain));
+        let nettable = amount.min(available);
+        let residual = amount - nettable;
+
+        let mut inventory_fill = Amount::default();
+        let mut fee = Amount::default();
+        if let Some((inventory, balances)) = inventory.filter(|_| residual > 0) {
+            let spread = inventory.fee(residual, to_chain, balances);
+            let payout = residual - spread;
+            if payout > 0 && balances.get(&to_chain).is_some_and(|b| *b >= payout) {
+                inventory_fill = residual;
+                fee = spread;
+            }
+        }
+        let bridged = match rebalancer {
+            Some(_) => residual - inventory_fill,
+            None => Amount::default(),
+        };
+
+        // The bridge takes what is left of a partly netted intent in the
+        // same round; an intent that nets nothing waits for it first.
+        let round = self.round_interval.unwrap_or_default();
+        let expected_wait = if nettable + inventory_fill + bridged < amount {
+            None
+        } else if bridged > 0 && nettable == 0 {
+            rebalancer.map(|rebalancer| rebalancer.wait() + round)
+        } else {
+            Some(round)
+        };
+
+        let mut quote = Quote {
+            id: Uuid::new_v4(),
+            from_chain,
+            to_chain,
+            amount,
+            nettable,
+            inventory_fill,
+            bridged,
+            uses_bridge: bridged > 0,
+            fee,
+            receive: nettable + inventory_fill + bridged - fee,
+            expected_wait_ms: expected_wait.map(|wait| wait.as_millis() as u64),
+            issued_at: now,
+            expires_at: now + self.ttl.as_secs(),
+            signature: String::new(),
+        };
+        quote.signature = self.sign(&quote);
+        quote
+    }
+
+    /// Checks that `quote` was issued by this backend for `amount` on
+    /// `route`, unchanged, and is still valid at `now`.
+    pub fn verify(
+        &self,
+        quote: &Quote,
+        route: Route,
+        amount: Amount,
+        now: u64,
+    ) -> Result<(), QuoteError> {
+        let signature = hex::decode(&quote.signature).map_err(|_| QuoteError::BadSignature)?;
+        self.mac(quote)
+            .verify_slice(&signature)
+            .map_err(|_| QuoteError::BadSignature)?;
+        if quote.expires_at < now {
+            return Err(QuoteError::Expired {
+                expires_at: quote.expires_at,
+            });
+        }
+        if quote.from_chain != route.from_chain
+            || quote.to_chain != route.to_chain
+            || quote.amount != amount
+        {
+            return Err(QuoteError::WrongIntent);
+        }
+        Ok(())
+    }
+
+    fn sign(&self, quote: &Quote) -> String {
+        hex::encode(self.mac(quote).finalize().into_bytes())
+    }
+
+    /// MAC over the quote's JSON with an empty signature.
+    fn mac(&self, quote: &Quote) -> Hmac<Sha256> {
+        let unsigned = Quote {
+            signature: String::new(),
+            ..quote.clone()
+        };
+        let body = serde_json::to_vec(&unsigned).expect("quotes serialize");
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("hmac accepts any key length");
+        mac.update(&body);
+        mac
+    }
+}
@@ -1,14 +1,460 @@
-use crate::models::Intent;
+use crate::blockchain::BlockchainClient;
+use crate::models::{ChainId, Intent, Route};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use uuid::Uuid;
 
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RiskRejection {
+    #[error("amount {amount} exceeds the per-intent limit of {max}")]
+    MaxIntentSize { amount: u64, max: u64 },
+    #[error("user {user} would exceed the daily notional limit of {limit} ({used} already used)")]
+    DailyNotionalExceeded { user: String, used: u64, limit: u64 },
+    #[error("route {from_chain} -> {to_chain} is not allowed")]
+    RouteNotAllowed {
+        from_chain: ChainId,
+        to_chain: ChainId,
+    },
+    #[error("address {address} is on the sanctions list")]
+    SanctionedAddress { address: String },
+    #[error("user {user} has {available} available on {chain} but the intent needs {requested}")]
+    InsufficientBalance {
+        chain: ChainId,
+        user: String,
+        available: u64,
+        requested: u64,
+    },
+    #[error("could not read balance on {chain}: {reason}")]
+    BalanceUnavailable { chain: ChainId, reason: String },
+}
+
+/// Outcome of a risk check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RiskDecision {
+    Approve,
+    /// Hold the intent until an operator approves or rejects it.
+    Review {
+        reason: String,
+    },
+    Reject(RiskRejection),
+}
+
+#[async_trait]
 pub trait RiskEngine: Send + Sync {
-    fn check_intent(&self, intent: &Intent) -> bool;
+    async fn check_intent(&self, intent: &Intent) -> RiskDecision;
+
+    /// Gives back whatever [`Self::check_intent`] reserved for an intent
+    /// that passed it but was later turned away: rejected in review, or
+    /// failed in execution.
+    fn release(&self, _intent: &Intent) {}
+
+    /// Tells the engine that `intent.amount` of an intent it approved has
+    /// executed. Partly filled intents settle once per fill.
+    fn settle(&self, _intent: &Intent) {}
 }
 
 #[derive(Default)]
 pub struct AlwaysPassRiskEngine;
 
+#[async_trait]
 impl RiskEngine for AlwaysPassRiskEngine {
-    fn check_intent(&self, _intent: &Intent) -> bool {
-        true
+    async fn check_intent(&self, _intent: &Intent) -> RiskDecision {
+        RiskDecision::Approve
+    }
+}
+
+/// A single check applied by [`RuleBasedRiskEngine`]. Rules are async so they
+/// can consult external services such as address screening.
+#[async_trait]
+pub trait RiskRule: Send + Sync {
+    /// Stateful rules reserve the intent's share in the same step when they
+    /// don't reject it, so concurrent checks cannot pass on the same headroom.
+    async fn check(&self, intent: &Intent) -> RiskDecision;
+
+    /// Gives back what [`Self::check`] reserved for `intent`.
+    fn release(&self, _intent: &Intent) {}
+
+    /// Ends holds that only last until `intent` executes. Usage limits stay
+    /// counted.
+    fn settle(&self, _intent: &Intent) {}
+}
+
+/// Runs its rules in order. The first rejection wins and releases what the
+/// rules before it reserved; otherwise the intent goes to review if any rule
+/// asked for it.
+#[derive(Default)]
+pub struct RuleBasedRiskEngine {
+    rules: Vec<Box<dyn RiskRule>>,
+}
+
+impl RuleBasedRiskEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule<R>(mut self, rule: R) -> Self
+    where
+        R: RiskRule + 'static,
+    {
+        self.rules.push(Box::new(rule));
+        self
+    }
+}
+
+#[async_trait]
+impl RiskEngine for RuleBasedRiskEngine {
+    async fn check_intent(&self, intent: &Intent) -> RiskDecision {
+        let mut decision = RiskDecision::Approve;
+        for (checked, rule) in self.rules.iter().enumerate() {
+            match rule.check(intent).await {
+                RiskDecision::Approve => {}
+                RiskDecision::Reject(rejection) => {
+                    for rule in &self.rules[..checked] {
+                        rule.release(intent);
+                    }
+                    return RiskDecision::Reject(rejection);
+                }
+                review @ RiskDecision::Review { .. } => {
+                    if decision == RiskDecision::Approve {
+                        decision = review;
+                    }
+                }
+            }
+        }
+        decision
+    }
+
+    fn release(&self, intent: &Intent) {
+        for rule in &self.rules {
+            rule.release(intent);
+        }
+    }
+
+    fn settle(&self, intent: &Intent) {
+        for rule in &self.rules {
+            rule.settle(intent);
+        }
+    }
+}
+
+pub struct MaxIntentSizeRule {
+    max: u64,
+}
+
+impl MaxIntentSizeRule {
+    pub fn new(max: u64) -> Self {
+        Self { max }
+    }
+}
+
+#[async_trait]
+impl RiskRule for MaxIntentSizeRule {
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        if intent.amount > self.max {
+            return RiskDecision::Reject(RiskRejection::MaxIntentSize {
+                amount: intent.amount.get(),
+                max: self.max,
+            });
+        }
+        RiskDecision::Approve
+    }
+}
+
+/// Caps the notional each user may submit per UTC day.
+pub struct DailyNotionalRule {
+    limit: u64,
+    usage: Mutex<DailyUsage>,
+}
+
+/// Notional counted so far on `day`, and the intents it was counted for.
+#[derive(Default)]
+struct DailyUsage {
+    day: u64,
+    by_user: HashMap<String, u64>,
+    counted: HashSet<Uuid>,
+}
+
+impl DailyUsage {
+    /// Starts over when `today` is a new day.
+    fn roll(&mut self, today: u64) {
+        if self.day != today {
+            *self = Self {
+                day: today,
+                ..Self::default()
+            };
+        }
+    }
+}
+
+impl DailyNotionalRule {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            usage: Mutex::new(DailyUsage::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskRule for DailyNotionalRule {
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        let mut usage = self.usage.lock().expect("usage lock poisoned");
+        usage.roll(current_day());
+        let used = usage.by_user.get(&intent.user_id).copied().unwrap_or(0);
+        let total = used.saturating_add(intent.amount.get());
+        if total > self.limit {
+            return RiskDecision::Reject(RiskRejection::DailyNotionalExceeded {
+                user: intent.user_id.clone(),
+                used,
+                limit: self.limit,
+            });
+        }
+        usage.by_user.insert(intent.user_id.clone(), total);
+        usage.counted.insert(intent.id);
+        RiskDecision::Approve
+    }
+
+    /// Intents counted on an earlier day were already forgotten.
+    fn release(&self, intent: &Intent) {
+        let mut usage = self.usage.lock().expect("usage lock poisoned");
+        usage.roll(current_day());
+        if usage.counted.remove(&intent.id)
+            && let Some(used) = usage.by_user.get_mut(&intent.user_id)
+        {
+            *used = used.saturating_sub(intent.amount.get());
+        }
+    }
+}
+
+fn current_day() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+        .unwrap_or(0)
+}
+
+/// Sends intents at or above `min_amount` to manual review.
+pub struct ManualReviewRule {
+    min_amount: u64,
+}
+
+impl ManualReviewRule {
+    pub fn new(min_amount: u64) -> Self {
+        Self { min_amount }
+    }
+}
+
+#[async_trait]
+impl RiskRule for ManualReviewRule {
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        if intent.amount >= self.min_amount {
+            return RiskDecision::Review {
+                reason: format!(
+                    "amount {} is at or above the review threshold of {}",
+                    intent.amount, self.min_amount
+                ),
+            };
+        }
+        RiskDecision::Approve
+    }
+}
+
+pub struct AllowedRoutesRule {
+    routes: HashSet<Route>,
+}
+
+impl AllowedRoutesRule {
+    pub fn new(routes: impl IntoIterator<Item = Route>) -> Self {
+        Self {
+            routes: routes.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl RiskRule for AllowedRoutesRule {
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        if !self.routes.contains(&intent.route()) {
+            return RiskDecision::Reject(RiskRejection::RouteNotAllowed {
+                from_chain: intent.from_chain,
+                to_chain: intent.to_chain,
+            });
+        }
+        RiskDecision::Approve
+    }
+}
+
+/// Rejects intents from listed addresses. Matching is case-insensitive so
+/// checksummed and lowercase forms of the same address are treated alike.
+pub struct SanctionsListRule {
+    addresses: HashSet<String>,
+}
+
+impl SanctionsListRule {
+    pub fn new<I, S>(addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            addresses: addresses
+                .into_iter()
+                .map(|address| address.as_ref().trim().to_lowercase())
+                .filter(|address| !address.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Loads one address per line; see [`read_address_list`].
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(read_address_list(path)?))
+    }
+}
+
+/// Reads one address per line, skipping blank lines and `#` comments.
+pub fn read_address_list(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[async_trait]
+impl RiskRule for SanctionsListRule {
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        if self.addresses.contains(&intent.user_id.to_lowercase()) {
+            return RiskDecision::Reject(RiskRejection::SanctionedAddress {
+                address: intent.user_id.clone(),
+            });
+        }
+        RiskDecision::Approve
+    }
+}
+
+/// Checks that a user can fund an intent on its `from_chain` and holds that
+/// amount back until the intent settles, so several open intents cannot
+/// spend the same balance. Clones share their holds.
+#[derive(Clone)]
+pub struct BalanceCheckRule<C>
+where
+    C: BlockchainClient,
+{
+    client: C,
+    reserved: Arc<Mutex<Reserved>>,
+}
+
+/// Amount held per chain and user, and how much of it each intent holds.
+#[derive(Default)]
+struct Reserved {
+    by_user: HashMap<(ChainId, String), u64>,
+    held_for: HashMap<Uuid, u64>,
+}
+
+impl Reserved {
+    /// Frees up to `amount` of what `intent` holds.
+    fn free(&mut self, intent: &Intent, amount: u64) {
+        let Some(held_for) = self.held_for.get_mut(&intent.id) else {
+            return;
+        };
+        let freed = amount.min(*held_for);
+        *held_for -= freed;
+        if *held_for == 0 {
+            self.held_for.remove(&intent.id);
+        }
+        let key = (intent.from_chain, intent.user_id.clone());
+        if let Some(held) = self.by_user.get_mut(&key) {
+            *held = held.saturating_sub(freed);
+            if *held == 0 {
+                self.by_user.remove(&key);
+            }
+        }
+    }
+}
+
+impl<C> BalanceCheckRule<C>
+where
+    C: BlockchainClient,
+{
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            reserved: Arc::new(Mutex::new(Reserved::default())),
+        }
+    }
+
+    pub fn reserved(&self, chain: ChainId, user: &str) -> u64 {
+        let reserved = self.reserved.lock().expect("reservations lock poisoned");
+        *reserved
+            .by_user
+            .get(&(chain, user.to_string()))
+            .unwrap_or(&0)
+    }
+}
+
+#[async_trait]
+impl<C> RiskRule for BalanceCheckRule<C>
+where
+    C: BlockchainClient,
+{
+    async fn check(&self, intent: &Intent) -> RiskDecision {
+        let balance = match self
+            .client
+            .balance_of(intent.from_chain, &intent.user_id)
+            .await
+        {
+            Ok(balance) => balance,
+            Err(err) => {
+                return RiskDecision::Reject(RiskRejection::BalanceUnavailable {
+                    chain: intent.from_chain,
+                    reason: err.to_string(),
+                });
+            }
+        };
+
+        let mut reserved = self.reserved.lock().expect("reservations lock poisoned");
+        if reserved.held_for.contains_key(&intent.id) {
+            return RiskDecision::Approve;
+        }
+        let held = reserved
+            .by_user
+            .entry((intent.from_chain, intent.user_id.clone()))
+            .or_insert(0);
+        let available = balance.saturating_sub(*held);
+        if intent.amount > available {
+            return RiskDecision::Reject(RiskRejection::InsufficientBalance {
+                chain: intent.from_chain,
+                user: intent.user_id.clone(),
+                available,
+                requested: intent.amount.get(),
+            });
+        }
+        *held += intent.amount.get();
+        reserved.held_for.insert(intent.id, intent.amount.get());
+        RiskDecision::Approve
+    }
+
+    /// Frees all the intent still holds. Does nothing for intents that hold
+    /// no reservation, so releasing twice cannot free another intent's share.
+    fn release(&self, intent: &Intent) {
+        let mut reserved = self.reserved.lock().expect("reservations lock poisoned");
+        reserved.free(intent, u64::MAX);
+    }
+
+    /// The executed transfer already took the amount off the balance.
+    fn settle(&self, intent: &Intent) {
+        let mut reserved = self.reserved.lock().expect("reservations lock poisoned");
+        reserved.free(intent, intent.amount.get());
     }
 }
@@ -0,0 +1,155 @@
+use crate::app::AppContext;
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant, MissedTickBehavior};
+
+/// Controls when the background batch auction runs a match round.
+///
+/// A round is triggered by whichever configured condition fires first: the
+/// fixed `interval` elapsing, or the orderbook reaching `min_intents` open
+/// intents or `min_notional` total volume.
+#[derive(Clone, Debug)]
+pub struct SchedulerConfig {
+    pub interval: Option<Duration>,
+    pub min_intents: Option<usize>,
+    pub min_notional: Option<u64>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            interval: Some(Duration::from_secs(5)),
+            min_intents: None,
+            min_notional: None,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SchedulerError {
+    #[error("scheduler needs an interval or an orderbook threshold")]
+    NoTrigger,
+    #[error("scheduler interval must be greater than zero")]
+    ZeroInterval,
+}
+
+impl SchedulerConfig {
+    pub fn validate(&self) -> Result<(), SchedulerError> {
+        if self.interval.is_none() && !self.has_threshold() {
+            return Err(SchedulerError::NoTrigger);
+        }
+        if self.interval == Some(Duration::ZERO) {
+            return Err(SchedulerError::ZeroInterval);
+        }
+        Ok(())
+    }
+
+    fn has_threshold(&self) -> bool {
+        self.min_intents.is_some() || self.min_notional.is_some()
+    }
+
+    fn threshold_reached(&self, intents: usize, notional: u64) -> bool {
+        self.min_intents.is_some_and(|min| intents >= min)
+            || self.min_notional.is_some_and(|min| notional >= min)
+    }
+}
+
+/// How often an idle scheduler loop reports that it is alive.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// Readiness fails once the loop has been silent for this long, which also
+/// covers a round stuck on the chain.
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// When the scheduler loop last went round, for readiness checks.
+#[derive(Default)]
+pub struct Heartbeat {
+    last: Mutex<Option<Instant>>,
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        *self.last.lock().expect("heartbeat lock poisoned") = Some(Instant::now());
+    }
+
+    /// Time since the last beat, or `None` if no scheduler ever ran.
+    pub fn age(&self) -> Option<Duration> {
+        self.last
+            .lock()
+            .expect("heartbeat lock poisoned")
+            .map(|last| last.elapsed())
+    }
+}
+
+pub struct Scheduler;
+
+impl Scheduler {
+    /// Spawns the matching loop on the current runtime.
+    ///
+    /// Rounds go through `AppContext::match_and_execute`, which holds the
+    /// context's round lock, so a scheduled round never overlaps one started
+    /// through the API.
+    pub fn spawn(
+        ctx: AppContext,
+        config: SchedulerConfig,
+    ) -> Result<SchedulerHandle, SchedulerError> {
+        config.validate()?;
+        ctx.scheduler_heartbeat().beat();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let task = tokio::spawn(run(ctx, config, shutdown_rx));
+        Ok(SchedulerHandle {
+            shutdown: shutdown_tx,
+            task,
+        })
+    }
+}
+
+pub struct SchedulerHandle {
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl SchedulerHandle {
+    /// Stops the loop, waiting for an in-flight round to finish first.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        if let Err(err) = self.task.await {
+            tracing::error!(error = ?err, "scheduler task ended abnormally");
+        }
+    }
+}
+
+async fn run(ctx: AppContext, config: SchedulerConfig, mut shutdown: watch::Receiver<bool>) {
+    // A never-firing ticker stands in when only thresholds are configured so
+    // the select below keeps a single shape.
+    let period = config.interval.unwrap_or(Duration::from_secs(3600));
+    let mut ticker = time::interval_at(Instant::now() + period, period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let orderbook_changed = ctx.orderbook_changed();
+    let mut heartbeat = time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ctx.scheduler_heartbeat().beat();
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            _ = heartbeat.tick() => continue,
+            _ = ticker.tick(), if config.interval.is_some() => {}
+            _ = orderbook_changed.notified(), if config.has_threshold() => {
+                let (intents, notional) = ctx.orderbook_depth().await;
+                if !config.threshold_reached(intents, notional) {
+                    continue;
+                }
+            }
+        }
+
+        if let Err(err) = ctx.match_and_execute().await {
+            tracing::error!(error = %err, "scheduled match round failed");
+        }
+        if config.interval.is_some() {
+            ticker.reset();
+        }
+    }
+}
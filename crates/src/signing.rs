@@ -0,0 +1,91 @@
+use crate::app::CreateIntentRequest;
+use crate::models::{ChainId, IntentKind};
+use crate::quote::Quote;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub use oceanlink_core::signing::{
+    DOMAIN_NAME, DOMAIN_VERSION, INTENT_TYPE, IntentMessage, MAX_DEADLINE_WINDOW, NonceRegistry,
+    SignatureError, SigningDomain, parse_address, recover_signer, unix_now, verify_signature,
+};
+
+/// An intent as submitted over the API: the order fields plus the user's
+/// EIP-712 signature over them. Chain names are validated separately so
+/// malformed ones get a precise error.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignedIntentRequest {
+    /// Address of the signer, hex encoded.
+    pub user_id: String,
+    /// Registry name, alias or EVM id of the chain; the signature covers its
+    /// EVM id as `fromChainId`.
+    #[schema(example = "Base")]
+    pub from_chain: String,
+    /// Signed as `toChainId`, like `from_chain`.
+    #[schema(example = "Arbitrum")]
+    pub to_chain: String,
+    pub amount: u64,
+    pub nonce: u64,
+    /// Unix timestamp in seconds after which the signature is void, at most
+    /// an hour ahead.
+    pub deadline: u64,
+    pub signature: String,
+    /// `maker` posts inventory for the solver and needs a maker API key.
+    /// Not part of the signed message.
+    #[serde(default)]
+    pub kind: IntentKind,
+    /// A quote from `GET /quote` for this route and amount. The intent is
+    /// refused if the quote has expired or was altered. Not part of the
+    /// signed message.
+    #[serde(default)]
+    pub quote: Option<Quote>,
+}
+
+/// A [`SignedIntentRequest`] whose chains have been parsed.
+#[derive(Clone, Debug)]
+pub struct SignedIntent {
+    pub user_id: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: u64,
+    pub nonce: u64,
+    pub deadline: u64,
+    pub signature: String,
+    pub kind: IntentKind,
+}
+
+impl SignedIntent {
+    /// The message the user signed, in the domain of its `from_chain`.
+    pub fn message(&self, domain: &SigningDomain) -> Result<IntentMessage, SignatureError> {
+        Ok(IntentMessage {
+            user: parse_address(&self.user_id)?,
+            from_chain: self.from_chain,
+            to_chain: self.to_chain,
+            amount: self.amount,
+            nonce: self.nonce,
+            deadline: self.deadline,
+            verifying_contract: domain.verifying_contract(self.from_chain),
+        })
+    }
+}
+
+/// Checks the signature, deadline and nonce of `req`, consuming the nonce on
+/// success. The returned request carries the recovered address in lowercase
+/// hex as its `user_id`.
+pub fn verify(
+    nonces: &NonceRegistry,
+    domain: &SigningDomain,
+    req: SignedIntent,
+) -> Result<CreateIntentRequest, SignatureError> {
+    let message = req.message(domain)?;
+    let now = unix_now();
+    let signer = verify_signature(&message, &req.signature, now)?;
+    nonces.consume(signer, req.nonce, req.deadline, now)?;
+
+    Ok(CreateIntentRequest {
+        user_id: format!("{signer:#x}"),
+        from_chain: req.from_chain,
+        to_chain: req.to_chain,
+        amount: req.amount,
+        kind: req.kind,
+    })
+}
@@ -0,0 +1,126 @@
+use crate::blockchain::{BlockchainClient, BlockchainError};
+use crate::models::{Amount, ChainId, Leg, NettingSolution};
+use crate::solver::{SolverSnapshot, solve, unsettled};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a match round would do with an orderbook, computed without sending
+/// anything or changing any state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Simulation {
+    /// `None` when nothing nets.
+    pub solution: Option<NettingSolution>,
+    /// Each leg of the solution in order, and whether its sender could pay.
+    pub legs: Vec<SimulatedLeg>,
+    /// Balances of every user a leg touches, before and after the legs that
+    /// can be paid.
+    pub balance_deltas: Vec<BalanceDelta>,
+    /// Volume the round would leave waiting in the orderbook: not netted,
+    /// not filled from inventory and not bridged.
+    pub residuals: Vec<ResidualView>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SimulatedLeg {
+    #[serde(flatten)]
+    pub leg: Leg,
+    /// The sender's balance is short of the amount when the leg comes up.
+    pub would_fail: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct BalanceDelta {
+    pub chain: ChainId,
+    pub user: String,
+    pub before: Amount,
+    pub after: Amount,
+    pub delta: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ResidualView {
+    pub intent_id: Uuid,
+    pub user_id: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: Amount,
+}
+
+impl Simulation {
+    pub fn failing_legs(&self) -> impl Iterator<Item = &SimulatedLeg> {
+        self.legs.iter().filter(|leg| leg.would_fail)
+    }
+}
+
+/// Solves `snapshot` as a round would and plays the legs against a copy of
+/// the balances `client` reports. A leg whose sender is short is marked and
+/// skipped, and the rest still play, so every failing leg shows up at once.
+pub async fn simulate<C: BlockchainClient>(
+    client: &C,
+    snapshot: &SolverSnapshot,
+) -> Result<Simulation, BlockchainError> {
+    let solution = solve(snapshot);
+    let residuals = unsettled(&snapshot.intents, solution.as_ref())
+        .into_iter()
+        .map(|residual| ResidualView {
+            intent_id: residual.intent.id,
+            user_id: residual.intent.user_id,
+            from_chain: residual.intent.from_chain,
+            to_chain: residual.intent.to_chain,
+            amount: residual.amount,
+        })
+        .collect();
+    let txs = solution
+        .as_ref()
+        .map(|s| s.txs.as_slice())
+        .unwrap_or_default();
+
+    let mut before: BTreeMap<(ChainId, &str), u64> = BTreeMap::new();
+    for leg in txs {
+        for user in [&leg.from_user, &leg.to_user] {
+            if let Entry::Vacant(entry) = before.entry((leg.chain, user.as_str())) {
+                entry.insert(client.balance_of(leg.chain, user).await?);
+            }
+        }
+    }
+
+    let mut after = before.clone();
+    let mut legs = Vec::with_capacity(txs.len());
+    for leg in txs {
+        let amount = leg.amount.get();
+        let sender = after.entry((leg.chain, &leg.from_user)).or_default();
+        let would_fail = *sender < amount;
+        if !would_fail {
+            *sender -= amount;
+            *after.entry((leg.chain, &leg.to_user)).or_default() += amount;
+        }
+        legs.push(SimulatedLeg {
+            leg: leg.clone(),
+            would_fail,
+        });
+    }
+
+    let balance_deltas = before
+        .iter()
+        .map(|(&(chain, user), &before)| {
+            let after = after[&(chain, user)];
+            BalanceDelta {
+                chain,
+                user: user.to_string(),
+                before: before.into(),
+                after: after.into(),
+                delta: (i128::from(after) - i128::from(before)) as i64,
+            }
+        })
+        .collect();
+
+    Ok(Simulation {
+        solution,
+        legs,
+        balance_deltas,
+        residuals,
+    })
+}
@@ -1,91 +1,338 @@
-use crate::models::{ChainId, Intent, LocalTx, NettingSolution};
-use std::collections::HashSet;
+use crate::bridge::BridgePolicy;
+use crate::inventory::Inventory;
+use crate::models::{Amount, ChainId, Intent, Leg, NettingSolution};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Clone)]
 struct WorkingIntent {
     id: Uuid,
     user: String,
-    from_chain: ChainId,
-    to_chain: ChainId,
-    remaining: u64,
+    remaining: Amount,
 }
 
+/// Volume of an intent left over after netting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Residual {
+    pub intent: Intent,
+    pub amount: Amount,
+}
+
+impl Residual {
+    /// Whether some of the intent was netted.
+    pub fn is_partial(&self) -> bool {
+        self.amount < self.intent.amount
+    }
+}
+
+/// Everything a round's solution is computed from, kept with the solution
+/// so that the round can be replayed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolverSnapshot {
+    /// Open intents in orderbook order.
+    pub intents: Vec<Intent>,
+    /// The solver inventory's account, spread and chain targets, if it
+    /// keeps one.
+    pub inventory_config: Option<Inventory>,
+    /// Balances of the solver inventory, if it keeps one and they could be
+    /// read.
+    pub inventory: Option<BTreeMap<ChainId, Amount>>,
+    /// How residual volume is bridged, if a bridge is configured.
+    pub bridge: Option<BridgePolicy>,
+    /// Intents that had waited long enough to be bridged.
+    pub bridge_due: BTreeSet<Uuid>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("no snapshot of solution {0}")]
+    NotFound(String),
+    #[error(
+        "replaying solution {expected} yields {}",
+        actual.as_deref().unwrap_or("no solution")
+    )]
+    Mismatch {
+        expected: String,
+        actual: Option<String>,
+    },
+}
+
+/// Nets intents against those on the opposite route. Each pair of chains is
+/// matched on its own, lower chain id first, and intents on a route are
+/// filled in the order given.
 pub fn compute_solution(intents: &[Intent]) -> Option<NettingSolution> {
-    let mut base_to_arbitrum: Vec<WorkingIntent> = intents
+    let snapshot = SolverSnapshot {
+        intents: intents.to_vec(),
+        ..SolverSnapshot::default()
+    };
+    solve(&snapshot)
+}
+
+/// Computes the solution for `snapshot`: intents are netted, then the
+/// inventory fills what it can of the rest and the bridge takes what is
+/// due. `None` if nothing settles.
+pub fn solve(snapshot: &SolverSnapshot) -> Option<NettingSolution> {
+    let (netted, residuals) = compute_netting(&snapshot.intents);
+    let mut solution = netted.unwrap_or_default();
+
+    let residuals = match (&snapshot.inventory_config, &snapshot.inventory) {
+        (Some(inventory), Some(balances)) => {
+            let (fills, unfilled) = inventory.fill(residuals, &mut balances.clone());
+            for fill in &fills {
+                solution.txs.extend(inventory.legs(fill));
+            }
+            solution.inventory_fills = fills;
+            unfilled
+        }
+        _ => residuals,
+    };
+    if let Some(bridge) = &snapshot.bridge {
+        solution.bridged = bridge.plan(&residuals, &snapshot.bridge_due);
+    }
+    solution.txs = compress_legs(&solution.txs);
+    if solution.txs.is_empty() && solution.bridged.is_empty() {
+        return None;
+    }
+
+    // Intents settled without netting are part of the solution too.
+    let involved = &mut solution.involved_intent_ids;
+    involved.extend(solution.inventory_fills.iter().map(|fill| fill.intent_id));
+    involved.extend(solution.bridged.iter().map(|transfer| transfer.intent_id));
+    involved.sort();
+    involved.dedup();
+    solution.id = solution_id(snapshot, &solution);
+    Some(solution)
+}
+
+/// What is left of each intent once `solution` has netted, filled and
+/// bridged what it can, in the order given.
+pub fn unsettled(intents: &[Intent], solution: Option<&NettingSolution>) -> Vec<Residual> {
+    let (_, residuals) = compute_netting(intents);
+    residuals
+        .into_iter()
+        .filter_map(|residual| {
+            let id = residual.intent.id;
+            let settled: Amount = solution
+                .into_iter()
+                .flat_map(|solution| {
+                    let filled = solution
+                        .inventory_fills
+                        .iter()
+                        .filter(move |fill| fill.intent_id == id)
+                        .map(|fill| fill.amount);
+                    let bridged = solution
+                        .bridged
+                        .iter()
+                        .filter(move |transfer| transfer.intent_id == id)
+                        .map(|transfer| transfer.amount);
+                    filled.chain(bridged)
+                })
+                .sum();
+            (settled < residual.amount).then(|| Residual {
+                amount: residual.amount - settled,
+                intent: residual.intent,
+            })
+        })
+        .collect()
+}
+
+/// Rewrites `legs` as few transfers as it can find on each chain, keeping
+/// every user's net position there. Legs between the same users are merged,
+/// opposite ones cancel, and what each user owes on a chain is paid to
+/// those owed, exact matches first, then largest amounts first. Chains come
+/// out in order.
+pub fn compress_legs(legs: &[Leg]) -> Vec<Leg> {
+    let mut nets: BTreeMap<ChainId, BTreeMap<&str, i128>> = BTreeMap::new();
+    for leg in legs {
+        let chain = nets.entry(leg.chain).or_default();
+        let amount = i128::from(leg.amount.get());
+        *chain.entry(&leg.from_user).or_default() -= amount;
+        *chain.entry(&leg.to_user).or_default() += amount;
+    }
+
+    let mut compressed = Vec::new();
+    for (chain, net) in nets {
+        let mut owing: Vec<(&str, u64)> = Vec::new();
+        let mut owed: Vec<(&str, u64)> = Vec::new();
+        for (user, amount) in net {
+            match amount.cmp(&0) {
+                std::cmp::Ordering::Less => owing.push((user, (-amount) as u64)),
+                std::cmp::Ordering::Greater => owed.push((user, amount as u64)),
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+
+        let mut pay = |from: &str, to: &str, amount: u64| {
+            compressed.push(Leg {
+                chain,
+                from_user: from.to_string(),
+                to_user: to.to_string(),
+                amount: amount.into(),
+            });
+        };
+
+        // An exact match settles two users with one transfer.
+        owing.retain(
+            |&(from, amount)| match owed.iter().position(|&(_, owed)| owed == amount) {
+                Some(i) => {
+                    pay(from, owed.remove(i).0, amount);
+                    false
+                }
+                None => true,
+            },
+        );
+
+        let largest_first = |a: &(&str, u64), b: &(&str, u64)| b.1.cmp(&a.1).then(a.0.cmp(b.0));
+        owing.sort_by(largest_first);
+        owed.sort_by(largest_first);
+        let (mut i, mut j) = (0, 0);
+        while i < owing.len() && j < owed.len() {
+            let amount = owing[i].1.min(owed[j].1);
+            pay(owing[i].0, owed[j].0, amount);
+            owing[i].1 -= amount;
+            owed[j].1 -= amount;
+            if owing[i].1 == 0 {
+                i += 1;
+            }
+            if owed[j].1 == 0 {
+                j += 1;
+            }
+        }
+    }
+    compressed
+}
+
+/// Keccak-256 of the canonical JSON encoding of `snapshot` and `solution`,
+/// leaving out the solution's own id.
+pub fn solution_id(snapshot: &SolverSnapshot, solution: &NettingSolution) -> String {
+    let solution = NettingSolution {
+        id: String::new(),
+        ..solution.clone()
+    };
+    let encoded = serde_json::to_vec(&(snapshot, &solution)).expect("solutions serialize");
+    format!("0x{}", hex::encode(keccak256(encoded)))
+}
+
+/// Re-runs the solver on the `snapshot` that `recorded` was computed from
+/// and checks that it yields the same solution.
+pub fn replay(
+    snapshot: &SolverSnapshot,
+    recorded: &NettingSolution,
+) -> Result<NettingSolution, ReplayError> {
+    match solve(snapshot) {
+        Some(solution) if solution == *recorded => Ok(solution),
+        other => Err(ReplayError::Mismatch {
+            expected: recorded.id.clone(),
+            actual: other.map(|solution| solution.id),
+        }),
+    }
+}
+
+/// Nets `intents` like [`compute_solution`], without giving the solution an
+/// id, and also returns what is left of each intent that was not filled
+/// completely, in the order given.
+pub fn compute_netting(intents: &[Intent]) -> (Option<NettingSolution>, Vec<Residual>) {
+    let pairs: BTreeSet<(ChainId, ChainId)> = intents
         .iter()
-        .filter(|intent| intent.from_chain == ChainId::Base && intent.to_chain == ChainId::Arbitrum)
-        .map(|intent| WorkingIntent {
-            id: intent.id,
-            user: intent.user_id.clone(),
-            from_chain: intent.from_chain,
-            to_chain: intent.to_chain,
-            remaining: intent.amount,
+        .filter(|intent| intent.from_chain < intent.to_chain)
+        .map(|intent| (intent.from_chain, intent.to_chain))
+        .collect();
+
+    let mut txs = Vec::new();
+    let mut filled: HashMap<Uuid, Amount> = HashMap::new();
+    for (low, high) in pairs {
+        net_pair(intents, low, high, &mut txs, &mut filled);
+    }
+
+    let residuals = intents
+        .iter()
+        .filter_map(|intent| {
+            let filled = filled.get(&intent.id).copied().unwrap_or_default();
+            (filled < intent.amount).then(|| Residual {
+                intent: intent.clone(),
+                amount: intent.amount - filled,
+            })
         })
         .collect();
 
-    let mut arbitrum_to_base: Vec<WorkingIntent> = intents
+    if txs.is_empty() {
+        return (None, residuals);
+    }
+
+    let solution = NettingSolution {
+        txs,
+        involved_intent_ids: filled
+            .into_keys()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        ..NettingSolution::default()
+    };
+    (Some(solution), residuals)
+}
+
+fn working(intents: &[Intent], from_chain: ChainId, to_chain: ChainId) -> Vec<WorkingIntent> {
+    intents
         .iter()
-        .filter(|intent| intent.from_chain == ChainId::Arbitrum && intent.to_chain == ChainId::Base)
+        .filter(|intent| intent.from_chain == from_chain && intent.to_chain == to_chain)
         .map(|intent| WorkingIntent {
             id: intent.id,
             user: intent.user_id.clone(),
-            from_chain: intent.from_chain,
-            to_chain: intent.to_chain,
             remaining: intent.amount,
         })
-        .collect();
+        .collect()
+}
 
-    if base_to_arbitrum.is_empty() || arbitrum_to_base.is_empty() {
-        return None;
-    }
+/// Matches `low -> high` intents against `high -> low` ones, adding a leg on
+/// each chain per matched amount.
+fn net_pair(
+    intents: &[Intent],
+    low: ChainId,
+    high: ChainId,
+    txs: &mut Vec<Leg>,
+    filled: &mut HashMap<Uuid, Amount>,
+) {
+    let mut from_low = working(intents, low, high);
+    let mut from_high = working(intents, high, low);
 
     let mut i = 0;
     let mut j = 0;
-    let mut txs = Vec::new();
-    let mut involved: HashSet<Uuid> = HashSet::new();
-
-    while i < base_to_arbitrum.len() && j < arbitrum_to_base.len() {
-        let from_base = &mut base_to_arbitrum[i];
-        let from_arbitrum = &mut arbitrum_to_base[j];
-        let amount = from_base.remaining.min(from_arbitrum.remaining);
+    while i < from_low.len() && j < from_high.len() {
+        let low_side = &mut from_low[i];
+        let high_side = &mut from_high[j];
+        let amount = low_side.remaining.min(high_side.remaining);
 
         if amount == 0 {
             break;
         }
 
-        txs.push(LocalTx {
-            chain: ChainId::Base,
-            from_user: from_base.user.clone(),
-            to_user: from_arbitrum.user.clone(),
+        txs.push(Leg {
+            chain: low,
+            from_user: low_side.user.clone(),
+            to_user: high_side.user.clone(),
             amount,
         });
-        txs.push(LocalTx {
-            chain: ChainId::Arbitrum,
-            from_user: from_arbitrum.user.clone(),
-            to_user: from_base.user.clone(),
+        txs.push(Leg {
+            chain: high,
+            from_user: high_side.user.clone(),
+            to_user: low_side.user.clone(),
             amount,
         });
 
-        from_base.remaining -= amount;
-        from_arbitrum.remaining -= amount;
-        involved.insert(from_base.id);
-        involved.insert(from_arbitrum.id);
+        low_side.remaining -= amount;
+        high_side.remaining -= amount;
+        for id in [low_side.id, high_side.id] {
+            *filled.entry(id).or_default() += amount;
+        }
 
-        if from_base.remaining == 0 {
+        if low_side.remaining == 0 {
             i += 1;
         }
-        if from_arbitrum.remaining == 0 {
+        if high_side.remaining == 0 {
             j += 1;
         }
     }
-
-    if txs.is_empty() {
-        return None;
-    }
-
-    Some(NettingSolution {
-        txs,
-        involved_intent_ids: involved.into_iter().collect(),
-    })
 }
@@ -1,68 +1,330 @@
-use crate::models::{Intent, IntentStatus, NettingSolution};
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use crate::models::{Amount, Intent, IntentStatus, NettingSolution, Route};
+use crate::solver::SolverSnapshot;
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::Instant;
 use uuid::Uuid;
 
+/// Service state, split so that intent intake, matching and solution reads
+/// each take their own short-lived locks instead of one global mutex.
+///
+/// None of the components hand out guards, so no lock can be held across
+/// an `.await`.
 #[derive(Default)]
 pub struct State {
-    intents: HashMap<Uuid, Intent>,
-    orderbook: Vec<Uuid>,
-    last_solution: Option<NettingSolution>,
+    intents: IntentStore,
+    orderbook: Orderbook,
+    solutions: SolutionLog,
+    /// API key id that submitted each intent, for intents that came in
+    /// through the API.
+    submitters: DashMap<Uuid, String>,
+    listeners: RwLock<Vec<Arc<dyn StatusListener>>>,
 }
 
-pub type SharedState = Arc<Mutex<State>>;
+/// Told about every intent whose status changes, whether through
+/// [`State::mark_status`] or [`State::update_intent`]. Called synchronously,
+/// so implementations should hand off slow work.
+pub trait StatusListener: Send + Sync {
+    fn status_changed(&self, intent: &Intent);
+}
+
+pub type SharedState = Arc<State>;
 
 impl State {
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub fn insert_intent(&mut self, intent: Intent) {
-        self.intents.insert(intent.id, intent);
+    pub fn orderbook(&self) -> &Orderbook {
+        &self.orderbook
     }
 
-    pub fn intents(&self) -> Vec<Intent> {
-        self.intents.values().cloned().collect()
+    pub fn solutions(&self) -> &SolutionLog {
+        &self.solutions
     }
 
-    pub fn get(&self, id: &Uuid) -> Option<&Intent> {
-        self.intents.get(id)
+    pub fn insert_intent(&self, intent: Intent) {
+        self.intents.insert(intent);
+    }
+
+    pub fn set_submitter(&self, id: Uuid, key_id: &str) {
+        self.submitters.insert(id, key_id.to_string());
+    }
+
+    pub fn submitted_by(&self, id: &Uuid, key_id: &str) -> bool {
+        self.submitters
+            .get(id)
+            .is_some_and(|submitter| submitter.value() == key_id)
     }
 
-    pub fn get_mut(&mut self, id: &Uuid) -> Option<&mut Intent> {
-        self.intents.get_mut(id)
+    pub fn intents(&self) -> Vec<Intent> {
+        self.intents.all()
     }
 
-    pub fn add_to_orderbook(&mut self, intent_id: Uuid) {
-        self.orderbook.push(intent_id);
+    pub fn get(&self, id: &Uuid) -> Option<Intent> {
+        self.intents.get(id)
     }
 
-    pub fn remove_from_orderbook(&mut self, ids: &HashSet<Uuid>) {
-        self.orderbook.retain(|id| !ids.contains(id));
+    pub fn add_to_orderbook(&self, intent: &Intent) {
+        self.orderbook.push(intent.route(), intent.id);
     }
 
+    pub fn remove_from_orderbook(&self, ids: &HashSet<Uuid>) {
+        self.orderbook.remove(ids);
+    }
+
+    /// Open intents in orderbook order, each with the amount it has left.
     pub fn orderbook_snapshot(&self) -> Vec<Intent> {
         self.orderbook
+            .ids()
             .iter()
-            .filter_map(|id| self.intents.get(id))
-            .cloned()
+            .filter_map(|id| self.open_intent(id))
+            .collect()
+    }
+
+    /// The stored intent with `amount` cut down to what is left of it.
+    fn open_intent(&self, id: &Uuid) -> Option<Intent> {
+        let mut intent = self.intents.get(id)?;
+        if let Some(left) = self.orderbook.remaining(id) {
+            intent.amount = left;
+        }
+        Some(intent)
+    }
+
+    pub fn orderbook_depth(&self) -> (usize, u64) {
+        self.depth(&self.orderbook.ids())
+    }
+
+    pub fn add_status_listener(&self, listener: Arc<dyn StatusListener>) {
+        self.listeners
+            .write()
+            .expect("listeners lock poisoned")
+            .push(listener);
+    }
+
+    /// Open intent count and notional for each route that has been used.
+    pub fn orderbook_depth_by_route(&self) -> Vec<(Route, usize, u64)> {
+        self.orderbook
+            .by_route()
+            .into_iter()
+            .map(|(route, ids)| {
+                let (count, notional) = self.depth(&ids);
+                (route, count, notional)
+            })
             .collect()
     }
 
-    pub fn mark_status(&mut self, ids: &HashSet<Uuid>, status: IntentStatus) {
+    /// Count and notional of `ids`, saturating rather than wrapping on a
+    /// notional past `u64::MAX`.
+    fn depth(&self, ids: &[Uuid]) -> (usize, u64) {
+        ids.iter()
+            .filter_map(|id| self.open_intent(id))
+            .fold((0, 0), |(count, notional), intent| {
+                (count + 1, notional.saturating_add(intent.amount.get()))
+            })
+    }
+
+    pub fn mark_status(&self, ids: &HashSet<Uuid>, status: IntentStatus) {
         for id in ids {
-            if let Some(intent) = self.intents.get_mut(id) {
-                intent.status = status;
+            self.update_intent(id, |intent| intent.status = status.clone());
+        }
+    }
+
+    /// Applies `change` to the stored intent and returns the updated copy.
+    /// Status changes must go through here (or [`Self::mark_status`]) so
+    /// that every [`StatusListener`] hears about them.
+    pub fn update_intent<F>(&self, id: &Uuid, change: F) -> Option<Intent>
+    where
+        F: FnOnce(&mut Intent),
+    {
+        let mut before = None;
+        let updated = self.intents.update(id, |intent| {
+            before = Some(intent.status.clone());
+            change(intent);
+        })?;
+        if before.as_ref() != Some(&updated.status) {
+            let listeners = self.listeners.read().expect("listeners lock poisoned");
+            for listener in listeners.iter() {
+                listener.status_changed(&updated);
             }
         }
+        Some(updated)
     }
 
-    pub fn set_last_solution(&mut self, solution: NettingSolution) {
-        self.last_solution = Some(solution);
+    /// Logs `solution` with the snapshot it was computed from.
+    pub fn record_solution(&self, snapshot: SolverSnapshot, solution: NettingSolution) {
+        self.solutions.push(snapshot, solution);
     }
 
     pub fn last_solution(&self) -> Option<NettingSolution> {
-        self.last_solution.clone()
+        self.solutions.last()
+    }
+
+    /// Fails once a panic has poisoned one of the store's locks, after which
+    /// every request touching it would panic too.
+    pub fn check(&self) -> Result<(), String> {
+        if self.listeners.is_poisoned() {
+            return Err("listeners lock poisoned".into());
+        }
+        if self.solutions.solutions.is_poisoned() {
+            return Err("solution log lock poisoned".into());
+        }
+        Ok(())
+    }
+}
+
+/// Intents keyed by id, sharded so concurrent inserts rarely contend.
+#[derive(Default)]
+pub struct IntentStore {
+    intents: DashMap<Uuid, Intent>,
+}
+
+impl IntentStore {
+    pub fn insert(&self, intent: Intent) {
+        self.intents.insert(intent.id, intent);
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<Intent> {
+        self.intents.get(id).map(|entry| entry.value().clone())
+    }
+
+    /// Applies `f` to the stored intent and returns the updated copy.
+    pub fn update<F>(&self, id: &Uuid, f: F) -> Option<Intent>
+    where
+        F: FnOnce(&mut Intent),
+    {
+        let mut entry = self.intents.get_mut(id)?;
+        f(entry.value_mut());
+        Some(entry.value().clone())
+    }
+
+    pub fn all(&self) -> Vec<Intent> {
+        self.intents
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+}
+
+/// Open intent ids grouped by route, each route kept in arrival order.
+#[derive(Default)]
+pub struct Orderbook {
+    routes: DashMap<Route, Vec<Uuid>>,
+    queued_at: DashMap<Uuid, Instant>,
+    /// What is left of partly filled intents.
+    remaining: DashMap<Uuid, Amount>,
+}
+
+impl Orderbook {
+    pub fn push(&self, route: Route, id: Uuid) {
+        self.queued_at.insert(id, Instant::now());
+        self.routes.entry(route).or_default().push(id);
+    }
+
+    pub fn remove(&self, ids: &HashSet<Uuid>) {
+        for mut entry in self.routes.iter_mut() {
+            entry.value_mut().retain(|id| !ids.contains(id));
+        }
+        for id in ids {
+            self.queued_at.remove(id);
+            self.remaining.remove(id);
+        }
+    }
+
+    /// Keeps `id` in the book, in its place, with only `left` of it open.
+    pub fn fill_partially(&self, id: Uuid, left: Amount) {
+        self.remaining.insert(id, left);
+    }
+
+    /// What is left of `id` if it was partly filled.
+    pub fn remaining(&self, id: &Uuid) -> Option<Amount> {
+        self.remaining.get(id).map(|left| *left.value())
+    }
+
+    /// How long `id` has been waiting in the orderbook, if it is there.
+    pub fn waited(&self, id: &Uuid) -> Option<Duration> {
+        self.queued_at.get(id).map(|queued| queued.elapsed())
+    }
+
+    /// Ids on a single route, oldest first.
+    pub fn route(&self, route: &Route) -> Vec<Uuid> {
+        self.routes
+            .get(route)
+            .map(|entry| entry.value().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn by_route(&self) -> Vec<(Route, Vec<Uuid>)> {
+        self.routes
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Every open id, routes in order and each route oldest first.
+    pub fn ids(&self) -> Vec<Uuid> {
+        let mut routes = self.by_route();
+        routes.sort_by_key(|(route, _)| *route);
+        routes.into_iter().flat_map(|(_, ids)| ids).collect()
+    }
+}
+
+/// Append-only record of computed solutions and, by solution id, the
+/// snapshots they were computed from.
+#[derive(Default)]
+pub struct SolutionLog {
+    solutions: RwLock<Vec<NettingSolution>>,
+    snapshots: DashMap<String, SolverSnapshot>,
+}
+
+impl SolutionLog {
+    pub fn push(&self, snapshot: SolverSnapshot, solution: NettingSolution) {
+        self.snapshots.insert(solution.id.clone(), snapshot);
+        self.solutions
+            .write()
+            .expect("solution log lock poisoned")
+            .push(solution);
+    }
+
+    /// The solution with `id` and its snapshot.
+    pub fn get(&self, id: &str) -> Option<(SolverSnapshot, NettingSolution)> {
+        let snapshot = self.snapshots.get(id)?.value().clone();
+        let solution = self
+            .solutions
+            .read()
+            .expect("solution log lock poisoned")
+            .iter()
+            .rev()
+            .find(|solution| solution.id == id)
+            .cloned()?;
+        Some((snapshot, solution))
+    }
+
+    /// The solution with `id`, without its snapshot.
+    pub fn solution(&self, id: &str) -> Option<NettingSolution> {
+        self.solutions
+            .read()
+            .expect("solution log lock poisoned")
+            .iter()
+            .rev()
+            .find(|solution| solution.id == id)
+            .cloned()
+    }
+
+    pub fn last(&self) -> Option<NettingSolution> {
+        self.solutions
+            .read()
+            .expect("solution log lock poisoned")
+            .last()
+            .cloned()
+    }
+
+    pub fn all(&self) -> Vec<NettingSolution> {
+        self.solutions
+            .read()
+            .expect("solution log lock poisoned")
+            .clone()
     }
 }
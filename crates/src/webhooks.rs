@@ -0,0 +1,457 @@
+use crate::models::Intent;
+use crate::signing::unix_now;
+use crate::state::StatusListener;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Notify;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// `sha256=<hex HMAC-SHA256 of the body>`, keyed with the webhook's secret.
+pub const SIGNATURE_HEADER: &str = "x-oceanlink-signature";
+pub const DELIVERY_HEADER: &str = "x-oceanlink-delivery";
+/// Dead letters kept for inspection; older ones are dropped first.
+pub const DEFAULT_DEAD_LETTER_LIMIT: usize = 1_000;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WebhookError {
+    #[error("invalid webhook url '{0}': must be an absolute http(s) url")]
+    InvalidUrl(String),
+    #[error("webhook url '{0}' points at a loopback, link-local or private address")]
+    PrivateHost(String),
+    #[error("webhook {0} not found")]
+    NotFound(Uuid),
+}
+
+/// A registered endpoint. Each belongs to the API key that created it and
+/// only receives updates for intents submitted with that key.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    #[serde(skip)]
+    owner: String,
+    #[serde(skip)]
+    secret: String,
+}
+
+/// Returned once on registration; the secret is not shown again.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookCreated {
+    pub id: Uuid,
+    pub url: String,
+    /// Key for verifying the `x-oceanlink-signature` header.
+    pub secret: String,
+}
+
+/// Body POSTed to a webhook when one of its intents changes status.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookPayload {
+    /// Stable across retries, so receivers can drop duplicates.
+    pub delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    /// Counts the intent's status changes from 1. Deliveries run
+    /// concurrently and retry on their own, so they can arrive out of order;
+    /// receivers keep the highest sequence they have seen.
+    pub sequence: u64,
+    pub intent: Intent,
+    pub timestamp: u64,
+}
+
+/// A delivery that failed every attempt.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct DeadLetter {
+    pub url: String,
+    pub payload: WebhookPayload,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based), doubling each time.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[async_trait]
+pub trait WebhookTransport: Send + Sync {
+    /// POSTs `body`; any non-2xx answer is an error.
+    async fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<(), String>;
+}
+
+/// Delivers over HTTP. Host names only resolve to public addresses and
+/// redirects are not followed, so a webhook cannot reach internal services
+/// however its DNS is set up.
+#[derive(Clone)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+impl HttpTransport {
+    pub fn new(timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .expect("http client builds");
+        Self { client }
+    }
+}
+
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(WebhookError::PrivateHost(host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is reachable on the public internet, as opposed to
+/// loopback, link-local, private, shared or otherwise reserved space.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8 and the 100.64.0.0/10 carrier-grade NAT range.
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local fc00::/7 and link-local fe80::/10.
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+/// Checks a webhook url: absolute http(s), and not naming a local host or
+/// a non-public address outright. Names are checked again on every
+/// delivery, when they resolve.
+fn validate_url(url: &str) -> Result<(), WebhookError> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| WebhookError::InvalidUrl(url.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(WebhookError::InvalidUrl(url.to_string()));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| WebhookError::InvalidUrl(url.to_string()))?;
+    let private = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) => !is_public(ip),
+        Err(_) => {
+            let domain = host.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+    };
+    if private {
+        return Err(WebhookError::PrivateHost(url.to_string()));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl WebhookTransport for HttpTransport {
+    async fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(url)
+            .header("content-type", "application/json")
+            .body(body.to_vec());
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let response = request.send().await.map_err(|err| err.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("endpoint answered {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`, as sent in [`SIGNATURE_HEADER`].
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Keeps webhook registrations and delivers intent status changes to them.
+/// Register it on [`crate::state::State`] as a [`StatusListener`].
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    inner: Arc<DispatcherInner>,
+}
+
+struct DispatcherInner {
+    transport: Arc<dyn WebhookTransport>,
+    policy: RetryPolicy,
+    hooks: RwLock<HashMap<Uuid, Webhook>>,
+    intents: DashMap<Uuid, Tracked>,
+    dead_letters: Mutex<VecDeque<DeadLetter>>,
+    dead_letter_limit: usize,
+    in_flight: AtomicUsize,
+    drained: Notify,
+}
+
+/// Who submitted an intent and how many of its status changes were sent.
+struct Tracked {
+    owner: String,
+    sequence: u64,
+}
+
+impl WebhookDispatcher {
+    pub fn new<T>(transport: T, policy: RetryPolicy) -> Self
+    where
+        T: WebhookTransport + 'static,
+    {
+        Self {
+            inner: Arc::new(DispatcherInner {
+                transport: Arc::new(transport),
+                policy,
+                hooks: RwLock::new(HashMap::new()),
+                intents: DashMap::new(),
+                dead_letters: Mutex::new(VecDeque::new()),
+                dead_letter_limit: DEFAULT_DEAD_LETTER_LIMIT,
+                in_flight: AtomicUsize::new(0),
+                drained: Notify::new(),
+            }),
+        }
+    }
+
+    /// Keeps at most `limit` dead letters instead of
+    /// [`DEFAULT_DEAD_LETTER_LIMIT`]. Call before the dispatcher is shared.
+    pub fn with_dead_letter_limit(mut self, limit: usize) -> Self {
+        Arc::get_mut(&mut self.inner)
+            .expect("dispatcher is not shared yet")
+            .dead_letter_limit = limit;
+        self
+    }
+
+    pub fn register(&self, owner: &str, url: &str) -> Result<WebhookCreated, WebhookError> {
+        validate_url(url)?;
+        let hook = Webhook {
+            id: Uuid::new_v4(),
+            url: url.to_string(),
+            owner: owner.to_string(),
+            secret: format!("whsec_{}", Uuid::new_v4().simple()),
+        };
+        let created = WebhookCreated {
+            id: hook.id,
+            url: hook.url.clone(),
+            secret: hook.secret.clone(),
+        };
+        self.inner
+            .hooks
+            .write()
+            .expect("webhooks lock poisoned")
+            .insert(hook.id, hook);
+        Ok(created)
+    }
+
+    pub fn list(&self, owner: &str) -> Vec<Webhook> {
+        self.inner
+            .hooks
+            .read()
+            .expect("webhooks lock poisoned")
+            .values()
+            .filter(|hook| hook.owner == owner)
+            .cloned()
+            .collect()
+    }
+
+    pub fn remove(&self, owner: &str, id: Uuid) -> Result<(), WebhookError> {
+        let mut hooks = self.inner.hooks.write().expect("webhooks lock poisoned");
+        match hooks.get(&id) {
+            Some(hook) if hook.owner == owner => {
+                hooks.remove(&id);
+                Ok(())
+            }
+            _ => Err(WebhookError::NotFound(id)),
+        }
+    }
+
+    /// Records which API key submitted an intent, so its webhooks hear about
+    /// it until it reaches a terminal status.
+    pub fn track(&self, intent_id: Uuid, owner: &str) {
+        self.inner.intents.insert(
+            intent_id,
+            Tracked {
+                owner: owner.to_string(),
+                sequence: 0,
+            },
+        );
+    }
+
+    /// Intents whose status changes are still being delivered.
+    pub fn tracked(&self) -> usize {
+        self.inner.intents.len()
+    }
+
+    /// The most recent exhausted deliveries, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.inner
+            .dead_letters
+            .lock()
+            .expect("dead letters lock poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Waits until no deliveries, including pending retries, are in flight.
+    pub async fn idle(&self) {
+        loop {
+            let drained = self.inner.drained.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            if self.inner.in_flight.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            drained.await;
+        }
+    }
+
+    fn dispatch(&self, hook: Webhook, sequence: u64, intent: Intent) {
+        let payload = WebhookPayload {
+            delivery_id: Uuid::new_v4(),
+            webhook_id: hook.id,
+            sequence,
+            intent,
+            timestamp: unix_now(),
+        };
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            inner.deliver(hook, payload).await;
+            if inner.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+                inner.drained.notify_waiters();
+            }
+        });
+    }
+}
+
+impl DispatcherInner {
+    fn bury(&self, letter: DeadLetter) {
+        let mut dead = self
+            .dead_letters
+            .lock()
+            .expect("dead letters lock poisoned");
+        if self.dead_letter_limit == 0 {
+            return;
+        }
+        while dead.len() >= self.dead_letter_limit {
+            dead.pop_front();
+        }
+        dead.push_back(letter);
+    }
+
+    async fn deliver(&self, hook: Webhook, payload: WebhookPayload) {
+        let body = serde_json::to_vec(&payload).expect("payload serializes");
+        let headers = [
+            (SIGNATURE_HEADER, sign(&hook.secret, &body)),
+            (DELIVERY_HEADER, payload.delivery_id.to_string()),
+        ];
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let last_error = match self.transport.post(&hook.url, &headers, &body).await {
+                Ok(()) => return,
+                Err(err) => err,
+            };
+            if attempts >= self.policy.max_attempts {
+                self.bury(DeadLetter {
+                    url: hook.url,
+                    payload,
+                    attempts,
+                    last_error,
+                });
+                return;
+            }
+            tokio::time::sleep(self.policy.backoff(attempts)).await;
+        }
+    }
+}
+
+impl StatusListener for WebhookDispatcher {
+    fn status_changed(&self, intent: &Intent) {
+        let Some((owner, sequence)) = self.inner.intents.get_mut(&intent.id).map(|mut tracked| {
+            tracked.sequence += 1;
+            (tracked.owner.clone(), tracked.sequence)
+        }) else {
+            return;
+        };
+        for hook in self.list(&owner) {
+            self.dispatch(hook, sequence, intent.clone());
+        }
+        if intent.status.is_terminal() {
+            self.inner.intents.remove(&intent.id);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::context::context;
+use common::create::create;
+use common::keys::{ADMIN_KEY, USER_KEY, authenticator};
+use common::request::request as intent_request;
+use ocean_link_backend::api::router;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::models::ChainId;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+fn app(limit: RateLimit) -> axum::Router {
+    router(context(MockBlockchainClient::default()).with_authenticator(authenticator(limit)))
+}
+
+fn request(method: &str, uri: &str, key: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(key) = key {
+        builder = builder.header(API_KEY_HEADER, key);
+    }
+    builder.body(Body::empty()).expect("valid request")
+}
+
+async fn status(app: &axum::Router, req: Request<Body>) -> StatusCode {
+    app.clone().oneshot(req).await.expect("infallible").status()
+}
+
+#[tokio::test]
+async fn requests_without_a_valid_key_are_refused() {
+    let app = app(RateLimit::default());
+
+    assert_eq!(
+        status(&app, request("GET", "/intents", None)).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some("wrong"))).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some(USER_KEY))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn bearer_tokens_are_accepted() {
+    let app = app(RateLimit::default());
+    let req = Request::builder()
+        .uri("/solutions")
+        .header(header::AUTHORIZATION, format!("Bearer {USER_KEY}"))
+        .body(Body::empty())
+        .expect("valid request");
+
+    assert_eq!(status(&app, req).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn admin_routes_need_admin_role() {
+    let app = app(RateLimit::default());
+
+    assert_eq!(
+        status(&app, request("POST", "/match-and-execute", Some(USER_KEY))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(&app, request("GET", "/admin/reviews", Some(USER_KEY))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status(&app, request("POST", "/match-and-execute", Some(ADMIN_KEY))).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some(ADMIN_KEY))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn exceeding_the_rate_limit_returns_429() {
+    let app = app(RateLimit {
+        per_second: 0.001,
+        burst: 2,
+    });
+
+    for _ in 0..2 {
+        assert_eq!(
+            status(&app, request("GET", "/intents", Some(USER_KEY))).await,
+            StatusCode::OK
+        );
+    }
+    let limited = app
+        .clone()
+        .oneshot(request("GET", "/intents", Some(USER_KEY)))
+        .await
+        .expect("infallible");
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(limited.headers().contains_key(header::RETRY_AFTER));
+
+    // Limits are tracked per key, so the admin key is unaffected.
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some(ADMIN_KEY))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn unauthenticated_callers_are_rate_limited_by_ip() {
+    let app = app(RateLimit {
+        per_second: 0.001,
+        burst: 1,
+    });
+
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some("guess-1"))).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(&app, request("GET", "/intents", Some("guess-2"))).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+async fn get_json(app: &axum::Router, uri: &str, key: &str) -> Value {
+    let response = app
+        .clone()
+        .oneshot(request("GET", uri, Some(key)))
+        .await
+        .expect("infallible");
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    serde_json::from_slice(&bytes).expect("body is JSON")
+}
+
+#[tokio::test]
+async fn user_keys_only_read_their_own_intents_and_solutions() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 60);
+    blockchain.set_balance(ChainId::ARBITRUM, "C", 40);
+    let ctx = context(blockchain).with_authenticator(authenticator(RateLimit::default()));
+    let key = ctx.auth.keys.authenticate(USER_KEY).expect("key exists");
+    create(
+        &ctx,
+        intent_request("A", ChainId::BASE, ChainId::ARBITRUM, 100),
+    )
+    .await;
+    let own = ctx
+        .submit_intent(
+            &key,
+            intent_request("B", ChainId::ARBITRUM, ChainId::BASE, 60),
+        )
+        .await;
+    ctx.wait_for_risk(own.id).await.expect("intent exists");
+    create(
+        &ctx,
+        intent_request("C", ChainId::ARBITRUM, ChainId::BASE, 40),
+    )
+    .await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("intents net");
+    let app = router(ctx);
+
+    let intents = get_json(&app, "/intents", USER_KEY).await;
+    assert_eq!(intents.as_array().map(Vec::len), Some(1));
+    assert_eq!(intents[0]["id"], json!(own.id));
+    let all = get_json(&app, "/intents", ADMIN_KEY).await;
+    assert_eq!(all.as_array().map(Vec::len), Some(3));
+
+    let solution = get_json(&app, "/solutions", USER_KEY).await;
+    assert_eq!(solution["involved_intent_ids"], json!([own.id]));
+    let legs = solution["txs"].as_array().expect("legs");
+    assert!(!legs.is_empty());
+    assert!(
+        legs.iter()
+            .all(|leg| leg["from_user"] == "B" || leg["to_user"] == "B")
+    );
+    let full = get_json(&app, "/solutions", ADMIN_KEY).await;
+    assert_eq!(
+        full["involved_intent_ids"].as_array().map(Vec::len),
+        Some(3)
+    );
+}
@@ -0,0 +1,187 @@
+mod common {
+    pub mod context;
+    pub mod keys;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::context::context;
+use common::keys::{ADMIN_KEY, USER_KEY, authenticator};
+use ocean_link_backend::api::router;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+fn app() -> axum::Router {
+    router(
+        context(MockBlockchainClient::default())
+            .with_authenticator(authenticator(RateLimit::default())),
+    )
+}
+
+fn intent_body(from_chain: &str, amount: u64) -> Value {
+    json!({
+        "user_id": "0x0000000000000000000000000000000000000001",
+        "from_chain": from_chain,
+        "to_chain": "arbitrum",
+        "amount": amount,
+        "nonce": 1,
+        "deadline": u64::MAX,
+        "signature": "00",
+    })
+}
+
+async fn send(
+    method: &str,
+    uri: &str,
+    key: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(key) = key {
+        builder = builder.header(API_KEY_HEADER, key);
+    }
+    let body = match body {
+        Some(body) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = app()
+        .oneshot(builder.body(body).expect("valid request"))
+        .await
+        .expect("infallible");
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("error body is JSON"),
+    )
+}
+
+#[tokio::test]
+async fn malformed_chain_names_are_reported_per_field() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(intent_body("solana", 1_000)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_chain");
+    assert_eq!(body["details"]["field"], "from_chain");
+    assert_eq!(body["details"]["value"], "solana");
+}
+
+#[tokio::test]
+async fn registry_chains_the_backend_does_not_serve_are_rejected() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(intent_body("ethereum", 1_000)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_chain");
+    assert_eq!(
+        body["message"],
+        "from_chain: chain 'Ethereum' is not served by this backend"
+    );
+}
+
+#[tokio::test]
+async fn intents_must_change_chain() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(intent_body("arbitrum", 1_000)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+    assert_eq!(body["details"]["field"], "to_chain");
+}
+
+#[tokio::test]
+async fn zero_amounts_are_rejected() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(intent_body("base", 0)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_amount");
+    assert_eq!(body["details"]["field"], "amount");
+}
+
+#[tokio::test]
+async fn bad_signatures_have_their_own_code() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(intent_body("base", 1_000)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "invalid_signature");
+}
+
+#[tokio::test]
+async fn unparseable_bodies_are_invalid_requests() {
+    let (status, body) = send(
+        "POST",
+        "/intents",
+        Some(USER_KEY),
+        Some(json!({ "amount": "lots" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+    assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+}
+
+#[tokio::test]
+async fn auth_failures_use_the_error_body() {
+    let (status, body) = send("GET", "/intents", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "unauthorized");
+
+    let (status, body) = send("GET", "/admin/reviews", Some(USER_KEY), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "forbidden");
+}
+
+#[tokio::test]
+async fn unknown_review_ids_are_not_found() {
+    let uri = format!("/admin/intents/{}/approve", uuid::Uuid::new_v4());
+    let (status, body) = send("POST", &uri, Some(ADMIN_KEY), None).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "not_found");
+
+    let (status, body) = send(
+        "POST",
+        "/admin/intents/not-a-uuid/approve",
+        Some(ADMIN_KEY),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+}
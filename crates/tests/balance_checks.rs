@@ -0,0 +1,152 @@
+mod common {
+    pub mod context_with;
+    pub mod create;
+    pub mod request;
+}
+
+use common::create::create;
+use common::request::request;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::models::{ChainId, IntentStatus};
+use ocean_link_backend::risk::{
+    BalanceCheckRule, MaxIntentSizeRule, RiskRejection, RiskRule, RuleBasedRiskEngine,
+};
+use std::sync::Arc;
+
+type Reservations = BalanceCheckRule<MockBlockchainClient>;
+
+/// Runs `engine` with balance checks as its last rule.
+fn context(engine: RuleBasedRiskEngine) -> (AppContext, MockBlockchainClient, Reservations) {
+    let blockchain = MockBlockchainClient::default();
+    let reservations = BalanceCheckRule::new(blockchain.clone());
+    let engine = engine.with_rule(reservations.clone());
+    let ctx =
+        common::context_with::context_with(Arc::new(engine), Executor::new(blockchain.clone()));
+    (ctx, blockchain, reservations)
+}
+
+#[tokio::test]
+async fn unfunded_intent_is_rejected_at_creation() {
+    let (ctx, blockchain, _) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::ARBITRUM, "A", 1_000);
+
+    let intent = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+
+    assert_eq!(
+        intent.status,
+        IntentStatus::Rejected {
+            reason: RiskRejection::InsufficientBalance {
+                chain: ChainId::BASE,
+                user: "A".into(),
+                available: 0,
+                requested: 1_000,
+            }
+            .to_string()
+        }
+    );
+    assert_eq!(ctx.orderbook_depth().await, (0, 0));
+}
+
+#[tokio::test]
+async fn open_intents_cannot_oversubscribe_a_balance() {
+    let (ctx, blockchain, reservations) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+
+    let first = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 700)).await;
+    let second = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 700)).await;
+    let remainder = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 300)).await;
+
+    assert_eq!(first.status, IntentStatus::InOrderbook);
+    assert!(matches!(second.status, IntentStatus::Rejected { .. }));
+    assert_eq!(remainder.status, IntentStatus::InOrderbook);
+
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 1_000);
+}
+
+#[tokio::test]
+async fn releasing_frees_only_what_the_intent_holds() {
+    let (ctx, blockchain, reservations) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+
+    let held = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 700)).await;
+    let rejected = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 700)).await;
+
+    reservations.release(&rejected);
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 700);
+
+    reservations.release(&held);
+    reservations.release(&held);
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 0);
+}
+
+#[tokio::test]
+async fn reservation_is_released_when_other_rules_reject() {
+    let engine = RuleBasedRiskEngine::new().with_rule(MaxIntentSizeRule::new(500));
+    let (ctx, blockchain, reservations) = context(engine);
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+
+    let rejected = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 800)).await;
+
+    assert!(matches!(rejected.status, IntentStatus::Rejected { .. }));
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 0);
+}
+
+#[tokio::test]
+async fn execution_releases_reservations() {
+    let (ctx, blockchain, reservations) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 1_000)).await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("solution expected");
+
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 0);
+    assert_eq!(reservations.reserved(ChainId::ARBITRUM, "B"), 0);
+
+    // A's Base balance moved to B, so a new Base intent from A is unfunded.
+    let again = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1)).await;
+    assert!(matches!(again.status, IntentStatus::Rejected { .. }));
+}
+
+#[tokio::test]
+async fn partial_fills_release_only_what_settled() {
+    let (ctx, blockchain, reservations) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 600);
+
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 600)).await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("solution expected");
+
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 400);
+    assert_eq!(reservations.reserved(ChainId::ARBITRUM, "B"), 0);
+}
+
+#[tokio::test]
+async fn failed_execution_releases_reservations() {
+    let (ctx, blockchain, reservations) = context(RuleBasedRiskEngine::new());
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+
+    let a = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 1_000)).await;
+    // B spends the balance elsewhere before the round.
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 0);
+    ctx.match_and_execute().await.expect_err("B cannot pay");
+
+    assert_eq!(reservations.reserved(ChainId::BASE, "A"), 0);
+    assert_eq!(reservations.reserved(ChainId::ARBITRUM, "B"), 0);
+    let a = ctx.state.get(&a.id).expect("intent exists");
+    assert!(matches!(a.status, IntentStatus::Failed { .. }));
+    assert!(a.status.is_terminal());
+    assert_eq!(ctx.orderbook_depth().await, (0, 0));
+}
@@ -0,0 +1,97 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod request;
+}
+
+use common::create::create;
+use common::request::request;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::bridge::{MockBridge, Rebalancer};
+use ocean_link_backend::models::{BridgeTransfer, ChainId, IntentStatus, Leg};
+use std::sync::Arc;
+use std::time::Duration;
+
+const WAIT: Duration = Duration::from_secs(60);
+
+fn context(bridge: &MockBridge) -> AppContext {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+    common::context::context(blockchain)
+        .with_rebalancer(Rebalancer::new(Arc::new(bridge.clone()), WAIT))
+}
+
+#[tokio::test]
+async fn the_rest_of_a_partial_fill_is_bridged_with_the_netted_legs() {
+    let bridge = MockBridge::default();
+    let ctx = context(&bridge);
+    let a = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    let b = create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 600)).await;
+
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("intents net");
+
+    assert_eq!(
+        solution.txs,
+        [
+            Leg {
+                chain: ChainId::BASE,
+                from_user: "A".into(),
+                to_user: "B".into(),
+                amount: 600.into(),
+            },
+            Leg {
+                chain: ChainId::ARBITRUM,
+                from_user: "B".into(),
+                to_user: "A".into(),
+                amount: 600.into(),
+            },
+        ]
+    );
+    let expected = BridgeTransfer {
+        intent_id: a.id,
+        user: "A".into(),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 400.into(),
+        via: "mock".into(),
+    };
+    assert_eq!(solution.bridged, [expected]);
+    assert_eq!(bridge.recorded(), solution.bridged);
+    for id in [a.id, b.id] {
+        let intent = ctx.state.get(&id).expect("intent exists");
+        assert_eq!(intent.status, IntentStatus::Executed);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn unmatched_intents_are_bridged_after_the_wait() {
+    let bridge = MockBridge::default();
+    let ctx = context(&bridge);
+    let lone = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+
+    let early = ctx.match_and_execute().await.expect("round succeeds");
+    assert!(early.is_none(), "the intent may still find a counterparty");
+    assert!(bridge.recorded().is_empty());
+
+    tokio::time::advance(WAIT).await;
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("the intent is bridged");
+
+    assert!(solution.txs.is_empty());
+    assert_eq!(solution.involved_intent_ids, [lone.id]);
+    assert_eq!(solution.bridged.len(), 1);
+    assert_eq!(solution.bridged[0].amount, 1_000);
+    assert_eq!(bridge.recorded(), solution.bridged);
+    let intent = ctx.state.get(&lone.id).expect("intent exists");
+    assert_eq!(intent.status, IntentStatus::Executed);
+    assert_eq!(ctx.orderbook_depth().await, (0, 0));
+}
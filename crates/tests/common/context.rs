@@ -0,0 +1,18 @@
+//! A context that lets every intent through.
+
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::risk::AlwaysPassRiskEngine;
+use ocean_link_backend::state::State;
+use std::sync::Arc;
+
+/// A context settling on `blockchain` that lets every intent through.
+pub fn context(blockchain: MockBlockchainClient) -> AppContext {
+    let executor = Arc::new(Executor::new(blockchain));
+    AppContext::new(
+        Arc::new(State::new()),
+        Arc::new(AlwaysPassRiskEngine),
+        executor,
+    )
+}
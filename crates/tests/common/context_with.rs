@@ -0,0 +1,16 @@
+//! A context built from a caller-chosen risk engine and executor.
+
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::risk::RiskEngine;
+use ocean_link_backend::state::State;
+use std::sync::Arc;
+
+/// A context whose intents `risk_engine` checks and `executor` settles.
+pub fn context_with(
+    risk_engine: Arc<dyn RiskEngine + Send + Sync>,
+    executor: Executor<MockBlockchainClient>,
+) -> AppContext {
+    AppContext::new(Arc::new(State::new()), risk_engine, Arc::new(executor))
+}
@@ -0,0 +1,12 @@
+//! Submits intents and waits out their risk check.
+
+use ocean_link_backend::app::{AppContext, CreateIntentRequest};
+use ocean_link_backend::models::Intent;
+
+/// Submits `req` and returns the intent once its risk check has finished.
+pub async fn create(ctx: &AppContext, req: CreateIntentRequest) -> Intent {
+    let intent = ctx.create_intent(req).await;
+    ctx.wait_for_risk(intent.id)
+        .await
+        .expect("intent should exist")
+}
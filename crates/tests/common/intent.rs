@@ -0,0 +1,17 @@
+//! Orderbook intents for driving the solver directly.
+
+use ocean_link_backend::models::{ChainId, Intent, IntentKind, IntentStatus};
+use uuid::Uuid;
+
+/// A taker intent already on the orderbook.
+pub fn intent(user_id: &str, from_chain: ChainId, to_chain: ChainId, amount: u64) -> Intent {
+    Intent {
+        id: Uuid::new_v4(),
+        user_id: user_id.into(),
+        from_chain,
+        to_chain,
+        amount: amount.into(),
+        kind: IntentKind::Taker,
+        status: IntentStatus::InOrderbook,
+    }
+}
@@ -0,0 +1,13 @@
+//! API keys for the tests that go through the router.
+
+use ocean_link_backend::auth::{Authenticator, RateLimit, Role};
+
+pub const USER_KEY: &str = "user-secret";
+pub const ADMIN_KEY: &str = "admin-secret";
+
+/// Knows [`USER_KEY`] as a user and [`ADMIN_KEY`] as an admin.
+pub fn authenticator(limit: RateLimit) -> Authenticator {
+    Authenticator::new(limit)
+        .with_key(USER_KEY, Role::User)
+        .with_key(ADMIN_KEY, Role::Admin)
+}
@@ -0,0 +1,20 @@
+//! Intent requests as a client would submit them.
+
+use ocean_link_backend::app::CreateIntentRequest;
+use ocean_link_backend::models::{ChainId, IntentKind};
+
+/// A taker intent from `user`.
+pub fn request(
+    user: &str,
+    from_chain: ChainId,
+    to_chain: ChainId,
+    amount: u64,
+) -> CreateIntentRequest {
+    CreateIntentRequest {
+        user_id: user.into(),
+        from_chain,
+        to_chain,
+        amount,
+        kind: IntentKind::Taker,
+    }
+}
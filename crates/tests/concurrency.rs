@@ -0,0 +1,94 @@
+mod common {
+    pub mod context;
+    pub mod request;
+}
+
+use common::context::context;
+use common::request::request;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::models::{ChainId, IntentStatus};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const WRITERS: usize = 8;
+const INTENTS_PER_WRITER: usize = 50;
+
+fn user(writer: usize, n: usize) -> String {
+    format!("writer-{writer}-{n}")
+}
+
+/// Every other intent flows the opposite way, so the whole book nets.
+fn route(n: usize) -> (ChainId, ChainId) {
+    if n.is_multiple_of(2) {
+        (ChainId::BASE, ChainId::ARBITRUM)
+    } else {
+        (ChainId::ARBITRUM, ChainId::BASE)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_creates_and_rounds_lose_no_intents() {
+    let blockchain = MockBlockchainClient::default();
+    for writer in 0..WRITERS {
+        for n in 0..INTENTS_PER_WRITER {
+            blockchain.set_balance(route(n).0, &user(writer, n), 1_000);
+        }
+    }
+    let ctx = context(blockchain);
+
+    let writing = Arc::new(AtomicBool::new(true));
+    let matcher = {
+        let ctx = ctx.clone();
+        let writing = writing.clone();
+        tokio::spawn(async move {
+            while writing.load(Ordering::Acquire) {
+                ctx.match_and_execute().await.expect("round succeeds");
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+    let writers: Vec<_> = (0..WRITERS)
+        .map(|writer| {
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                let mut ids = Vec::with_capacity(INTENTS_PER_WRITER);
+                for n in 0..INTENTS_PER_WRITER {
+                    let (from_chain, to_chain) = route(n);
+                    let intent = ctx
+                        .create_intent(request(&user(writer, n), from_chain, to_chain, 1_000))
+                        .await;
+                    ids.push(intent.id);
+                }
+                ids
+            })
+        })
+        .collect();
+
+    let mut created = HashSet::new();
+    for writer in writers {
+        created.extend(writer.await.expect("writer task panicked"));
+    }
+    writing.store(false, Ordering::Release);
+    matcher.await.expect("matcher task panicked");
+    for id in &created {
+        ctx.wait_for_risk(*id).await.expect("intent exists");
+    }
+    ctx.match_and_execute().await.expect("round succeeds");
+
+    let intents = ctx.list_intents().await;
+    assert_eq!(created.len(), WRITERS * INTENTS_PER_WRITER);
+    assert_eq!(intents.len(), created.len());
+    assert!(intents.iter().all(|intent| created.contains(&intent.id)));
+    assert!(
+        intents
+            .iter()
+            .all(|intent| intent.status == IntentStatus::Executed),
+        "unsettled: {:?}",
+        intents
+            .iter()
+            .filter(|intent| intent.status != IntentStatus::Executed)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(ctx.orderbook_depth().await, (0, 0));
+}
@@ -0,0 +1,379 @@
+use ocean_link_backend::auth::RateLimit;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::config::{BridgeAdapterConfig, Config, StorageConfig};
+use ocean_link_backend::inventory::ChainTarget;
+use ocean_link_backend::models::{ChainId, Intent, IntentKind, IntentStatus, Route};
+use ocean_link_backend::risk::RiskDecision;
+use std::time::Duration;
+use uuid::Uuid;
+
+const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const SIGNER_ADDRESS: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+const USDC: &str = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913";
+
+fn full_config() -> String {
+    format!(
+        r#"
+bind = "0.0.0.0:9000"
+
+[chains.base]
+rpc_url = "https://mainnet.base.org"
+vault = "0x1111111111111111111111111111111111111111"
+tokens = {{ usdc = "{USDC}" }}
+
+[chains.arbitrum]
+rpc_url = "wss://arb1.example.com"
+aliases = ["arb"]
+finality_depth = 40
+
+[chains.op]
+chain_id = 10
+name = "Optimism"
+rpc_url = "https://mainnet.optimism.io"
+explorer_url = "https://optimistic.etherscan.io"
+
+[signers.base]
+private_key = "{PRIVATE_KEY}"
+
+[risk]
+max_intent_size = 1000
+manual_review_above = 500
+allowed_routes = ["Base->Arbitrum", "optimism->arb"]
+balance_checks = true
+
+[scheduler]
+interval_ms = 0
+min_intents = 4
+
+[storage]
+backend = "memory"
+
+[feeder]
+url = "http://127.0.0.1:8081"
+api_key = "feeder-admin-key"
+
+[bridge]
+adapter = "mock"
+wait_ms = 30000
+
+[inventory]
+account = "solver"
+spread_bps = 25
+
+[inventory.chains.base]
+target_ratio = 0.6
+min_balance = 1000
+
+[inventory.chains.arb]
+target_ratio = 0.4
+
+[quotes]
+secret = "quote-secret-quote-secret-quote-secret"
+ttl_secs = 10
+
+[rate_limit]
+per_second = 2
+burst = 5
+"#
+    )
+}
+
+fn intent(amount: u64, from_chain: ChainId, to_chain: ChainId) -> Intent {
+    Intent {
+        id: Uuid::new_v4(),
+        user_id: "user".into(),
+        from_chain,
+        to_chain,
+        amount: amount.into(),
+        kind: IntentKind::Taker,
+        status: IntentStatus::PendingRisk,
+    }
+}
+
+#[test]
+fn defaults_keep_the_previous_behaviour() {
+    let config = Config::load(None, Vec::new(), &[]).expect("defaults are valid");
+    assert_eq!(config.bind.to_string(), "127.0.0.1:8080");
+    assert!(config.chains.is_empty());
+    assert!(config.signers.is_empty());
+    assert!(!config.risk.balance_checks);
+    assert_eq!(config.scheduler.interval, Some(Duration::from_secs(5)));
+    assert_eq!(config.storage, StorageConfig::Memory);
+    assert!(config.feeder.is_none());
+    assert!(config.bridge.is_none());
+    assert!(config.inventory.is_none());
+    assert_eq!(config.quotes.secret, None);
+    assert_eq!(config.quotes.ttl, Duration::from_secs(30));
+    assert_eq!(config.rate_limit, RateLimit::default());
+}
+
+#[test]
+fn a_full_file_is_typed() {
+    let config = Config::from_toml(&full_config()).expect("valid config");
+    assert_eq!(config.bind.to_string(), "0.0.0.0:9000");
+
+    let chains: Vec<ChainId> = config.chains.iter().map(|chain| chain.chain).collect();
+    let optimism = ChainId::new(10);
+    assert_eq!(chains, [ChainId::ARBITRUM, ChainId::BASE, optimism]);
+    let base = &config.chains[1];
+    assert_eq!(format!("{:?}", base.tokens["usdc"]), USDC);
+    assert!(base.vault.is_some());
+
+    let registry = &config.registry;
+    let arbitrum = registry.get(ChainId::ARBITRUM).expect("built in");
+    assert_eq!(arbitrum.rpc_url.as_deref(), Some("wss://arb1.example.com"));
+    assert_eq!(arbitrum.finality_depth, 40);
+    assert_eq!(registry.resolve("ARB"), Some(ChainId::ARBITRUM));
+    let added = registry.get(optimism).expect("defined by the file");
+    assert_eq!(added.name, "Optimism");
+    assert_eq!(
+        registry.resolve("op"),
+        Some(optimism),
+        "the key stays an alias"
+    );
+    assert_eq!(
+        added.tx_url("0xabc").as_deref(),
+        Some("https://optimistic.etherscan.io/tx/0xabc")
+    );
+
+    assert_eq!(config.signers.len(), 1);
+    assert_eq!(format!("{:?}", config.signers[0].address()), SIGNER_ADDRESS);
+
+    assert_eq!(
+        config.risk.allowed_routes,
+        Some(vec![
+            Route {
+                from_chain: ChainId::BASE,
+                to_chain: ChainId::ARBITRUM,
+            },
+            Route {
+                from_chain: optimism,
+                to_chain: ChainId::ARBITRUM,
+            },
+        ])
+    );
+    assert!(config.risk.balance_checks);
+    assert_eq!(config.scheduler.interval, None);
+    assert_eq!(config.scheduler.min_intents, Some(4));
+
+    let feeder = config.feeder.expect("feeder configured");
+    assert_eq!(feeder.url, "http://127.0.0.1:8081");
+    assert_eq!(feeder.api_key, "feeder-admin-key");
+    assert!(!format!("{feeder:?}").contains("feeder-admin-key"));
+
+    let bridge = config.bridge.expect("bridge configured");
+    assert_eq!(bridge.adapter, BridgeAdapterConfig::Mock);
+    assert_eq!(bridge.wait, Duration::from_secs(30));
+
+    let inventory = config.inventory.expect("inventory configured");
+    assert_eq!(inventory.account, "solver");
+    assert_eq!(inventory.spread_bps, 25);
+    assert_eq!(
+        inventory.chains[&ChainId::BASE],
+        ChainTarget {
+            target_ratio: 0.6,
+            min_balance: 1000,
+        }
+    );
+    assert_eq!(inventory.chains[&ChainId::ARBITRUM].min_balance, 0);
+
+    assert_eq!(config.quotes.ttl, Duration::from_secs(10));
+    assert!(!format!("{:?}", config.quotes).contains("quote-secret"));
+    assert_eq!(
+        config.rate_limit,
+        RateLimit {
+            per_second: 2.0,
+            burst: 5,
+        }
+    );
+}
+
+#[test]
+fn env_overrides_the_file_and_flags_override_env() {
+    let path = std::env::temp_dir().join(format!("oceanlink-{}.toml", Uuid::new_v4()));
+    std::fs::write(&path, full_config()).expect("write config file");
+
+    let env = vec![
+        ("OCEANLINK__BIND".to_string(), "127.0.0.1:7000".to_string()),
+        (
+            "OCEANLINK__RISK__MAX_INTENT_SIZE".to_string(),
+            "2000".to_string(),
+        ),
+        (
+            "OCEANLINK__SCHEDULER__INTERVAL_MS".to_string(),
+            "250".to_string(),
+        ),
+        ("UNRELATED".to_string(), "ignored".to_string()),
+    ];
+    let overrides = vec!["bind=127.0.0.1:7001".to_string()];
+    let config = Config::load(Some(&path), env, &overrides);
+    std::fs::remove_file(&path).ok();
+    let config = config.expect("valid config");
+
+    assert_eq!(config.bind.to_string(), "127.0.0.1:7001");
+    assert_eq!(config.risk.max_intent_size, Some(2000));
+    assert_eq!(
+        config.risk.manual_review_above,
+        Some(500),
+        "kept from the file"
+    );
+    assert_eq!(config.scheduler.interval, Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn every_error_is_reported_at_once() {
+    let source = r#"
+bind = "localhost"
+colour = "blue"
+
+[chains.solana]
+rpc_url = "https://solana.example.com"
+
+[chains.base]
+rpc_url = "ftp://base.example.com"
+chain_id = -1
+vault = "0x1234"
+
+[chains.arbitrum]
+chain_id = 8453
+rpc_url = "https://arb1.example.com"
+
+[chains.arb-nova]
+chain_id = 42170
+aliases = ["base"]
+rpc_url = "https://nova.example.com"
+
+[signers.base]
+private_key = "0xnot-a-key"
+
+[risk]
+max_intent_size = 0
+allowed_routes = ["Base->Base", "Base"]
+sanctions_file = "/does/not/exist"
+
+[scheduler]
+interval_ms = 0
+
+[storage]
+backend = "postgres"
+
+[feeder]
+url = "ftp://feeder.example.com"
+
+[bridge]
+adapter = "wormhole"
+wait_ms = -5
+
+[inventory]
+spread_bps = 20000
+
+[inventory.chains.solana]
+target_ratio = 2
+
+[quotes]
+secret = "short"
+ttl_secs = 0
+
+[rate_limit]
+per_second = 0
+burst = 5000000000
+"#;
+    let errors = Config::from_toml(source).expect_err("config is invalid");
+    let mut keys = errors.keys();
+    keys.sort();
+    assert_eq!(
+        keys,
+        [
+            "bind",
+            "bridge.adapter",
+            "bridge.wait_ms",
+            "chains.arb-nova",
+            "chains.arbitrum.chain_id",
+            "chains.base.chain_id",
+            "chains.base.rpc_url",
+            "chains.base.vault",
+            "chains.solana",
+            "colour",
+            "feeder.api_key",
+            "feeder.url",
+            "inventory.account",
+            "inventory.chains.solana",
+            "inventory.chains.solana.target_ratio",
+            "inventory.spread_bps",
+            "quotes.secret",
+            "quotes.ttl_secs",
+            "rate_limit.burst",
+            "rate_limit.per_second",
+            "risk.allowed_routes[0]",
+            "risk.allowed_routes[1]",
+            "risk.max_intent_size",
+            "risk.sanctions_file",
+            "scheduler",
+            "signers.base.private_key",
+            "storage.backend",
+        ]
+    );
+
+    let report = errors.to_string();
+    assert!(report.starts_with("invalid configuration (27 errors):"));
+    assert!(!report.contains("not-a-key"), "secrets are never echoed");
+}
+
+#[test]
+fn missing_and_mistyped_settings_are_named() {
+    let errors = Config::load(
+        None,
+        vec![(
+            "OCEANLINK__RISK__BALANCE_CHECKS".to_string(),
+            "maybe".to_string(),
+        )],
+        &[
+            "chains.base.chain_id=8453".to_string(),
+            "no-equals-sign".to_string(),
+        ],
+    )
+    .expect_err("config is invalid");
+    let messages: Vec<String> = errors.0.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "no-equals-sign: expected key=value",
+            "chains.base.rpc_url: required",
+            "risk.balance_checks: expected true or false, got 'maybe'",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn risk_rules_come_from_the_config() {
+    let config = Config::from_toml(&full_config()).expect("valid config");
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "user", 10_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "user", 10_000);
+    let engine = config.risk.engine(blockchain);
+
+    let small = intent(100, ChainId::BASE, ChainId::ARBITRUM);
+    assert_eq!(engine.check_intent(&small).await, RiskDecision::Approve);
+    let large = intent(600, ChainId::BASE, ChainId::ARBITRUM);
+    assert!(matches!(
+        engine.check_intent(&large).await,
+        RiskDecision::Review { .. }
+    ));
+    let oversize = intent(5000, ChainId::BASE, ChainId::ARBITRUM);
+    assert!(matches!(
+        engine.check_intent(&oversize).await,
+        RiskDecision::Reject(_)
+    ));
+    let wrong_way = intent(100, ChainId::ARBITRUM, ChainId::BASE);
+    assert!(matches!(
+        engine.check_intent(&wrong_way).await,
+        RiskDecision::Reject(_)
+    ));
+}
+
+#[test]
+fn the_example_config_is_valid() {
+    let source = include_str!("../backend.example.toml");
+    let config = Config::from_toml(source).unwrap_or_else(|errors| panic!("{errors}"));
+    assert_eq!(config.chains.len(), 2);
+}
@@ -0,0 +1,311 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use common::create::create;
+use common::keys::{ADMIN_KEY, USER_KEY, authenticator};
+use common::request::request;
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit, Role};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::events::{Event, EventFilter};
+use ocean_link_backend::models::{ChainId, Intent, IntentStatus};
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+fn context(blockchain: MockBlockchainClient) -> AppContext {
+    common::context::context(blockchain).with_authenticator(authenticator(RateLimit::default()))
+}
+
+/// Opens A (Base -> Arbitrum) against B (Arbitrum -> Base) for one amount.
+async fn open_pair(ctx: &AppContext) -> (Intent, Intent) {
+    let a = create(ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    let b = create(ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+    (a, b)
+}
+
+fn drain(events: &mut Receiver<Event>) -> Vec<Event> {
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+    received
+}
+
+fn names(events: &[Event]) -> Vec<&'static str> {
+    events.iter().map(Event::name).collect()
+}
+
+#[tokio::test]
+async fn a_settled_round_publishes_the_full_lifecycle() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 100);
+    let ctx = context(blockchain);
+    let mut events = ctx.events().subscribe();
+
+    let (a, _) = open_pair(&ctx).await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("intents net");
+
+    let received = drain(&mut events);
+    assert_eq!(
+        names(&received),
+        [
+            "IntentCreated",
+            "IntentStatusChanged",
+            "IntentCreated",
+            "IntentStatusChanged",
+            "IntentStatusChanged",
+            "IntentStatusChanged",
+            "SolutionComputed",
+            "LegSent",
+            "LegConfirmed",
+            "LegSent",
+            "LegConfirmed",
+            "IntentStatusChanged",
+            "IntentStatusChanged",
+        ]
+    );
+
+    let a_statuses: Vec<IntentStatus> = received
+        .iter()
+        .filter_map(|event| match event {
+            Event::IntentStatusChanged {
+                intent_id, status, ..
+            } if *intent_id == a.id => Some(status.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        a_statuses,
+        [
+            IntentStatus::InOrderbook,
+            IntentStatus::Matched,
+            IntentStatus::Executed
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failed_legs_publish_solution_failed() {
+    // B has no Arbitrum balance, so its leg fails.
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    let ctx = context(blockchain);
+    open_pair(&ctx).await;
+    let mut events = ctx.events().subscribe();
+
+    ctx.match_and_execute().await.expect_err("B cannot pay");
+
+    let received = drain(&mut events);
+    let last = received.last().expect("events were published");
+    assert!(matches!(last, Event::SolutionFailed { reason, .. } if reason.contains("B")));
+    let count = |name| names(&received).iter().filter(|n| **n == name).count();
+    // The failed leg was never submitted, so it is not reported as sent.
+    assert_eq!(count("LegSent"), count("LegConfirmed"));
+    assert!(received.iter().all(|e| match e {
+        Event::LegSent { tx_hash, .. } => !tx_hash.is_empty(),
+        _ => true,
+    }));
+}
+
+#[tokio::test]
+async fn filters_select_by_user_and_intent() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 100);
+    let ctx = context(blockchain);
+    let mut events = ctx.events().subscribe();
+
+    let (a, b) = open_pair(&ctx).await;
+    let c = create(&ctx, request("C", ChainId::BASE, ChainId::ARBITRUM, 5)).await;
+    ctx.match_and_execute().await.expect("execution succeeds");
+    let received = drain(&mut events);
+
+    let for_c = EventFilter {
+        user_id: Some("c".into()),
+        intent_id: None,
+    };
+    // C is created and enters the orderbook but takes no part in the round.
+    assert_eq!(received.iter().filter(|e| for_c.matches(e)).count(), 2);
+
+    let for_b = EventFilter {
+        user_id: None,
+        intent_id: Some(b.id),
+    };
+    let matched: Vec<&Event> = received.iter().filter(|e| for_b.matches(e)).collect();
+    assert!(
+        matched
+            .iter()
+            .any(|e| matches!(e, Event::LegConfirmed { .. }))
+    );
+    assert!(matched.iter().all(|e| match e {
+        Event::IntentCreated { intent } => intent.id == b.id,
+        Event::IntentStatusChanged { intent_id, .. } => *intent_id == b.id,
+        _ => true,
+    }));
+
+    let both = EventFilter {
+        user_id: Some("A".into()),
+        intent_id: Some(c.id),
+    };
+    assert!(received.iter().all(|e| !both.matches(e)));
+    assert!(received.iter().any(|e| {
+        EventFilter {
+            user_id: Some("A".into()),
+            intent_id: Some(a.id),
+        }
+        .matches(e)
+    }));
+}
+
+async fn subscribe(ctx: &AppContext, uri: &str, key: &str) -> Body {
+    let req = Request::builder()
+        .uri(uri)
+        .header(API_KEY_HEADER, key)
+        .body(Body::empty())
+        .expect("valid request");
+    let response = router(ctx.clone()).oneshot(req).await.expect("infallible");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    response.into_body()
+}
+
+async fn next_frame(body: Body) -> String {
+    let frame = tokio::time::timeout(Duration::from_secs(5), body.into_data_stream().next())
+        .await
+        .expect("an event arrives")
+        .expect("stream is open")
+        .expect("frame is readable");
+    String::from_utf8(frame.to_vec()).expect("utf-8")
+}
+
+#[tokio::test]
+async fn events_are_streamed_over_sse() {
+    let ctx = context(MockBlockchainClient::default());
+    let body = subscribe(&ctx, "/events?user_id=A", ADMIN_KEY).await;
+
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+
+    // B's events are filtered out, so A's creation comes first.
+    let text = next_frame(body).await;
+    assert!(text.starts_with("event: IntentCreated\n"), "{text}");
+    assert!(text.contains("\"user_id\":\"A\""), "{text}");
+}
+
+#[tokio::test]
+async fn user_keys_only_stream_their_own_intents() {
+    let ctx = context(MockBlockchainClient::default());
+    let key = ctx.auth.keys.authenticate(USER_KEY).expect("key exists");
+    let body = subscribe(&ctx, "/events", USER_KEY).await;
+
+    // Submitted with another key, or none, so not the caller's to see.
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+    let own = ctx
+        .submit_intent(&key, request("A", ChainId::BASE, ChainId::ARBITRUM, 100))
+        .await;
+
+    let text = next_frame(body).await;
+    assert!(text.starts_with("event: IntentCreated\n"), "{text}");
+    assert!(text.contains(&own.id.to_string()), "{text}");
+
+    let admin = ctx.auth.keys.authenticate(ADMIN_KEY).expect("key exists");
+    let created = Event::IntentCreated { intent: own };
+    assert!(ctx.visible_to(&key, &created).is_some());
+    assert!(ctx.visible_to(&admin, &created).is_some());
+    let other = ctx.auth.keys.insert("other-secret", Role::User);
+    assert!(ctx.visible_to(&other, &created).is_none());
+}
+
+#[tokio::test]
+async fn user_keys_only_see_their_part_of_a_solution() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 60);
+    blockchain.set_balance(ChainId::ARBITRUM, "C", 40);
+    let ctx = context(blockchain);
+    let key = ctx.auth.keys.authenticate(USER_KEY).expect("key exists");
+    let admin = ctx.auth.keys.authenticate(ADMIN_KEY).expect("key exists");
+    let mut events = ctx.events().subscribe();
+
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    let own = ctx
+        .submit_intent(&key, request("B", ChainId::ARBITRUM, ChainId::BASE, 60))
+        .await;
+    ctx.wait_for_risk(own.id).await.expect("intent exists");
+    create(&ctx, request("C", ChainId::ARBITRUM, ChainId::BASE, 40)).await;
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("intents net");
+
+    let received = drain(&mut events);
+    let computed = received
+        .iter()
+        .find(|e| matches!(e, Event::SolutionComputed { .. }))
+        .expect("solution computed");
+    assert!(matches!(
+        ctx.visible_to(&admin, computed),
+        Some(Event::SolutionComputed { solution: full }) if full == solution
+    ));
+    let Some(Event::SolutionComputed { solution: seen }) = ctx.visible_to(&key, computed) else {
+        panic!("the caller's intent is in the solution");
+    };
+    assert_eq!(seen.involved_intent_ids, vec![own.id]);
+    assert!(!seen.txs.is_empty());
+    assert!(seen.txs.len() < solution.txs.len());
+    assert!(
+        seen.txs
+            .iter()
+            .all(|leg| leg.from_user == "B" || leg.to_user == "B")
+    );
+
+    // Leg indexes count only the legs the caller sees.
+    let legs: Vec<Event> = received
+        .iter()
+        .filter(|e| matches!(e, Event::LegSent { .. }))
+        .filter_map(|e| ctx.visible_to(&key, e))
+        .collect();
+    assert_eq!(legs.len(), seen.txs.len());
+    for event in legs {
+        let Event::LegSent {
+            intent_ids,
+            leg,
+            tx,
+            ..
+        } = event
+        else {
+            unreachable!();
+        };
+        assert_eq!(intent_ids, vec![own.id]);
+        assert_eq!(seen.txs[leg], tx);
+    }
+}
+
+#[tokio::test]
+async fn malformed_filters_are_rejected() {
+    let ctx = context(MockBlockchainClient::default());
+    let req = Request::builder()
+        .uri("/events?intent_id=nope")
+        .header(API_KEY_HEADER, USER_KEY)
+        .body(Body::empty())
+        .expect("valid request");
+    let response = router(ctx).oneshot(req).await.expect("infallible");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,164 @@
+mod common {
+    pub mod context;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use common::context::context;
+use ethers::types::Address;
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::{MockBlockchainClient, RpcProbe};
+use ocean_link_backend::models::ChainId;
+use ocean_link_backend::scheduler::{HEARTBEAT_TIMEOUT, Scheduler, SchedulerConfig};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tower::ServiceExt;
+
+/// Calls `uri` without an API key.
+async fn get(ctx: &AppContext, uri: &str) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .expect("valid request");
+    let response = router(ctx.clone()).oneshot(req).await.expect("infallible");
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn check<'a>(body: &'a Value, name: &str, chain: Option<&str>) -> &'a Value {
+    body["checks"]
+        .as_array()
+        .expect("checks array")
+        .iter()
+        .find(|check| check["name"] == name && check.get("chain").and_then(Value::as_str) == chain)
+        .unwrap_or_else(|| panic!("no {name} check for {chain:?} in {body}"))
+}
+
+#[tokio::test]
+async fn healthy_dependencies_are_ready_without_a_key() {
+    let ctx = context(MockBlockchainClient::default());
+
+    let (status, _) = get(&ctx, "/healthz").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = get(&ctx, "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ready"], true);
+    assert_eq!(check(&body, "store", None)["ok"], true);
+    for chain in ["Base", "Arbitrum"] {
+        assert_eq!(check(&body, "rpc", Some(chain))["ok"], true);
+        assert_eq!(check(&body, "signer", Some(chain))["ok"], true);
+    }
+    // No scheduler was started on this context.
+    assert!(
+        body["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|check| check["name"] != "scheduler")
+    );
+}
+
+#[tokio::test]
+async fn each_failing_dependency_is_reported() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_chain_id(ChainId::BASE, 1);
+    blockchain.set_unreachable(ChainId::ARBITRUM);
+    blockchain.remove_signer(ChainId::BASE);
+    let ctx = context(blockchain);
+
+    let (status, body) = get(&ctx, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["ready"], false);
+
+    let base_rpc = check(&body, "rpc", Some("Base"));
+    assert_eq!(base_rpc["ok"], false);
+    assert_eq!(base_rpc["message"], json!("unexpected chain id"));
+    let arbitrum_rpc = check(&body, "rpc", Some("Arbitrum"));
+    assert_eq!(arbitrum_rpc["ok"], false);
+    assert_eq!(arbitrum_rpc["message"], json!("rpc unreachable"));
+    let base_signer = check(&body, "signer", Some("Base"));
+    assert_eq!(base_signer["ok"], false);
+    assert_eq!(base_signer["message"], json!("no signer"));
+    assert_eq!(check(&body, "signer", Some("Arbitrum"))["ok"], true);
+    assert_eq!(check(&body, "store", None)["ok"], true);
+}
+
+#[tokio::test]
+async fn configured_endpoints_are_probed_without_leaking_their_urls() {
+    // A node that answers as Base.
+    let rpc = Router::new().route(
+        "/",
+        post(|Json(req): Json<Value>| async move {
+            Json(json!({ "jsonrpc": "2.0", "id": req["id"], "result": "0x2105" }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("port is free");
+    let base = listener.local_addr().expect("bound");
+    tokio::spawn(async move { axum::serve(listener, rpc).await });
+    // Nothing listens on Arbitrum's endpoint any more.
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("port is free")
+        .local_addr()
+        .expect("bound");
+
+    let ctx = context(MockBlockchainClient::default()).with_chain_probe(
+        RpcProbe::new()
+            .with_rpc_url(ChainId::BASE, format!("http://{base}"))
+            .with_rpc_url(ChainId::ARBITRUM, format!("http://{closed}/v2/secret-key"))
+            .with_signer(ChainId::BASE, Address::repeat_byte(0x11)),
+    );
+
+    let (status, body) = get(&ctx, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        check(&body, "rpc", Some("Base"))["message"],
+        json!("chain id 8453")
+    );
+    assert_eq!(
+        check(&body, "signer", Some("Base"))["message"],
+        json!(format!("{:#x}", Address::repeat_byte(0x11)))
+    );
+    let arbitrum_rpc = check(&body, "rpc", Some("Arbitrum"));
+    assert_eq!(arbitrum_rpc["ok"], false);
+    assert_eq!(arbitrum_rpc["message"], json!("rpc unreachable"));
+    assert_eq!(check(&body, "signer", Some("Arbitrum"))["ok"], false);
+    assert!(!body.to_string().contains("secret-key"), "{body}");
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_silent_scheduler_fails_readiness() {
+    let ctx = context(MockBlockchainClient::default());
+    let scheduler = Scheduler::spawn(
+        ctx.clone(),
+        SchedulerConfig {
+            interval: Some(Duration::from_secs(5)),
+            min_intents: None,
+            min_notional: None,
+        },
+    )
+    .expect("valid config");
+
+    tokio::time::sleep(HEARTBEAT_TIMEOUT * 2).await;
+    let (status, body) = get(&ctx, "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(check(&body, "scheduler", None)["ok"], true);
+
+    scheduler.shutdown().await;
+    tokio::time::sleep(HEARTBEAT_TIMEOUT + Duration::from_secs(1)).await;
+    let (status, body) = get(&ctx, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(check(&body, "scheduler", None)["ok"], false);
+}
@@ -0,0 +1,131 @@
+mod common {
+    pub mod intent;
+}
+
+use common::intent::intent;
+use ocean_link_backend::invariants::{Violation, check_solution};
+use ocean_link_backend::models::{BridgeTransfer, ChainId, Intent, InventoryFill, Leg};
+use ocean_link_backend::solver::compute_solution;
+use uuid::Uuid;
+
+fn leg(chain: ChainId, from: &str, to: &str, amount: u64) -> Leg {
+    Leg {
+        chain,
+        from_user: from.into(),
+        to_user: to.into(),
+        amount: amount.into(),
+    }
+}
+
+fn intents() -> Vec<Intent> {
+    vec![
+        intent("A", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+        intent("B", ChainId::ARBITRUM, ChainId::BASE, 600),
+        intent("C", ChainId::ARBITRUM, ChainId::BASE, 300),
+    ]
+}
+
+#[test]
+fn solver_output_passes() {
+    let intents = intents();
+    let solution = compute_solution(&intents).expect("intents net");
+    assert_eq!(check_solution(&intents, &solution, None), Ok(()));
+}
+
+#[test]
+fn inventory_fills_and_bridged_volume_pass() {
+    let intents = intents();
+    let mut solution = compute_solution(&intents).expect("intents net");
+    // A's last 100 goes half through the inventory, half over a bridge.
+    solution.txs.extend([
+        leg(ChainId::BASE, "A", "solver", 50),
+        leg(ChainId::ARBITRUM, "solver", "A", 49),
+    ]);
+    solution.inventory_fills.push(InventoryFill {
+        intent_id: intents[0].id,
+        user: "A".into(),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 50.into(),
+        fee: 1.into(),
+    });
+    solution.bridged.push(BridgeTransfer {
+        intent_id: intents[0].id,
+        user: "A".into(),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 50.into(),
+        via: "mock".into(),
+    });
+    assert_eq!(check_solution(&intents, &solution, Some("solver")), Ok(()));
+
+    // Without the solver exemption its legs belong to no intent.
+    let report = check_solution(&intents, &solution, None).expect_err("solver is unknown");
+    assert!(report.violations.contains(&Violation::UninvolvedParty {
+        chain: ChainId::BASE,
+        user: "solver".into(),
+    }));
+}
+
+#[test]
+fn every_violation_is_reported() {
+    let intents = intents();
+    let mut solution = compute_solution(&intents).expect("intents net");
+    let stranger = Uuid::new_v4();
+    solution.involved_intent_ids.push(stranger);
+    solution.involved_intent_ids.push(intents[1].id);
+    // B is paid twice on Base, more than asked and more than they send.
+    solution.txs.push(leg(ChainId::BASE, "A", "B", 600));
+    solution.txs.push(leg(ChainId::BASE, "A", "A", 5));
+    solution.bridged.push(BridgeTransfer {
+        intent_id: Uuid::nil(),
+        user: "A".into(),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 100.into(),
+        via: "mock".into(),
+    });
+
+    let report = check_solution(&intents, &solution, None).expect_err("solution is broken");
+    assert_eq!(report.solution_id, solution.id);
+    for violation in [
+        Violation::UnknownIntent {
+            intent_id: stranger,
+        },
+        Violation::DuplicateIntent {
+            intent_id: intents[1].id,
+        },
+        Violation::InvalidLeg { leg: 5 },
+        Violation::UnlistedIntent {
+            intent_id: Uuid::nil(),
+        },
+        Violation::Overspent {
+            chain: ChainId::BASE,
+            user: "A".into(),
+            debited: 1_600,
+            available: 1_000,
+        },
+        Violation::Overpaid {
+            chain: ChainId::BASE,
+            user: "B".into(),
+            credited: 1_200,
+            expected: 600,
+        },
+        Violation::Unbalanced {
+            user: "B".into(),
+            debited: 600,
+            credited: 1_200,
+            fees: 0,
+        },
+    ] {
+        assert!(
+            report.violations.contains(&violation),
+            "missing {violation:?} in {report}"
+        );
+    }
+    assert!(report.to_string().starts_with(&format!(
+        "solution {} breaks {} invariants:",
+        solution.id,
+        report.violations.len()
+    )));
+}
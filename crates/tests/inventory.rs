@@ -0,0 +1,180 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod intent;
+    pub mod request;
+}
+
+use common::create::create;
+use common::intent::intent;
+use common::request::request;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::{BlockchainClient, MockBlockchainClient};
+use ocean_link_backend::inventory::{ChainTarget, Inventory};
+use ocean_link_backend::models::{Amount, ChainId, IntentStatus, InventoryFill, Leg};
+use ocean_link_backend::solver::Residual;
+use std::collections::BTreeMap;
+
+const SOLVER: &str = "solver";
+
+fn context(blockchain: &MockBlockchainClient) -> AppContext {
+    let target = |min_balance| ChainTarget {
+        target_ratio: 0.5,
+        min_balance,
+    };
+    let inventory = Inventory::new(SOLVER, 50)
+        .with_chain(ChainId::BASE, target(0))
+        .with_chain(ChainId::ARBITRUM, target(1_000));
+    common::context::context(blockchain.clone()).with_inventory(inventory)
+}
+
+#[tokio::test]
+async fn unmatched_intents_are_filled_from_inventory_less_the_spread() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, SOLVER, 10_000);
+    let ctx = context(&blockchain);
+    let intent = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("inventory fills the intent");
+
+    assert_eq!(
+        solution.inventory_fills,
+        [InventoryFill {
+            intent_id: intent.id,
+            user: "A".into(),
+            from_chain: ChainId::BASE,
+            to_chain: ChainId::ARBITRUM,
+            amount: 1_000.into(),
+            fee: 5.into(),
+        }]
+    );
+    assert_eq!(
+        solution.txs,
+        [
+            Leg {
+                chain: ChainId::BASE,
+                from_user: "A".into(),
+                to_user: SOLVER.into(),
+                amount: 1_000.into(),
+            },
+            Leg {
+                chain: ChainId::ARBITRUM,
+                from_user: SOLVER.into(),
+                to_user: "A".into(),
+                amount: 995.into(),
+            },
+        ]
+    );
+    assert_eq!(solution.involved_intent_ids, [intent.id]);
+    let intent = ctx.state.get(&intent.id).expect("intent exists");
+    assert_eq!(intent.status, IntentStatus::Executed);
+    let received = blockchain
+        .balance_of(ChainId::ARBITRUM, "A")
+        .await
+        .expect("balance");
+    assert_eq!(received, 995);
+
+    let report = ctx
+        .inventory()
+        .await
+        .expect("balances readable")
+        .expect("inventory mode is on");
+    let balances: Vec<(ChainId, u64)> = report
+        .iter()
+        .map(|chain| (chain.chain, chain.balance.get()))
+        .collect();
+    assert_eq!(
+        balances,
+        [(ChainId::BASE, 1_000), (ChainId::ARBITRUM, 9_005)]
+    );
+    assert!(report.iter().all(|chain| !chain.depleted));
+}
+
+#[tokio::test]
+async fn intents_the_inventory_cannot_cover_keep_waiting() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 5_000);
+    blockchain.set_balance(ChainId::BASE, "B", 5_000);
+    blockchain.set_balance(ChainId::ARBITRUM, SOLVER, 2_000);
+    let ctx = context(&blockchain);
+    let first = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    let second = create(&ctx, request("B", ChainId::BASE, ChainId::ARBITRUM, 2_000)).await;
+
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("inventory fills the first intent");
+
+    assert_eq!(solution.involved_intent_ids, [first.id]);
+    let second = ctx.state.get(&second.id).expect("intent exists");
+    assert_eq!(second.status, IntentStatus::InOrderbook);
+
+    // 1_005 is left on Arbitrum, just above its minimum of 1_000.
+    let report = ctx
+        .inventory()
+        .await
+        .expect("balances readable")
+        .expect("inventory mode is on");
+    let arbitrum = report
+        .iter()
+        .find(|chain| chain.chain == ChainId::ARBITRUM)
+        .expect("arbitrum is tracked");
+    assert_eq!(arbitrum.balance, 1_005);
+    assert!(!arbitrum.depleted);
+
+    blockchain.set_balance(ChainId::ARBITRUM, SOLVER, 1_000);
+    let report = ctx
+        .inventory()
+        .await
+        .expect("balances readable")
+        .expect("inventory mode is on");
+    assert!(report.iter().any(|chain| chain.depleted));
+}
+
+#[test]
+fn paying_out_of_a_chain_below_its_target_costs_double() {
+    let target = ChainTarget {
+        target_ratio: 0.5,
+        min_balance: 0,
+    };
+    let inventory = Inventory::new(SOLVER, 50)
+        .with_chain(ChainId::BASE, target)
+        .with_chain(ChainId::ARBITRUM, target);
+    let balances = BTreeMap::from([
+        (ChainId::BASE, Amount::from(9_000)),
+        (ChainId::ARBITRUM, Amount::from(1_000)),
+    ]);
+
+    assert_eq!(inventory.fee(1_000.into(), ChainId::BASE, &balances), 5);
+    assert_eq!(
+        inventory.fee(1_000.into(), ChainId::ARBITRUM, &balances),
+        10
+    );
+}
+
+#[test]
+fn spreads_of_the_whole_amount_fill_nothing() {
+    let inventory = Inventory::new(SOLVER, 20_000).with_chain(
+        ChainId::ARBITRUM,
+        ChainTarget {
+            target_ratio: 1.0,
+            min_balance: 0,
+        },
+    );
+    let mut balances = BTreeMap::from([(ChainId::ARBITRUM, Amount::from(10_000))]);
+    let residual = Residual {
+        intent: intent("A", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+        amount: 1_000.into(),
+    };
+
+    let (fills, unfilled) = inventory.fill(vec![residual.clone()], &mut balances);
+
+    assert!(fills.is_empty());
+    assert_eq!(unfilled, [residual]);
+}
@@ -0,0 +1,86 @@
+use ocean_link_backend::models::{ChainId, Leg};
+use ocean_link_backend::solver::compress_legs;
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+const USERS: [&str; 5] = ["A", "B", "C", "D", "E"];
+const CHAINS: [ChainId; 2] = [ChainId::BASE, ChainId::ARBITRUM];
+
+fn leg(chain: ChainId, from: &str, to: &str, amount: u64) -> Leg {
+    Leg {
+        chain,
+        from_user: from.into(),
+        to_user: to.into(),
+        amount: amount.into(),
+    }
+}
+
+/// What each user receives minus what they send, per chain.
+fn net_positions(legs: &[Leg]) -> BTreeMap<(ChainId, String), i128> {
+    let mut nets = BTreeMap::new();
+    for leg in legs {
+        let amount = i128::from(leg.amount.get());
+        *nets.entry((leg.chain, leg.from_user.clone())).or_default() -= amount;
+        *nets.entry((leg.chain, leg.to_user.clone())).or_default() += amount;
+    }
+    nets.retain(|_, net| *net != 0);
+    nets
+}
+
+fn legs_on(legs: &[Leg], chain: ChainId) -> usize {
+    legs.iter().filter(|leg| leg.chain == chain).count()
+}
+
+fn arb_leg() -> impl Strategy<Value = Leg> {
+    (0..CHAINS.len(), 0..USERS.len(), 0..USERS.len(), 1..1_000u64)
+        .prop_map(|(chain, from, to, amount)| leg(CHAINS[chain], USERS[from], USERS[to], amount))
+}
+
+#[test]
+fn merges_and_cancels_legs_between_the_same_users() {
+    let legs = [
+        leg(ChainId::BASE, "A", "B", 300),
+        leg(ChainId::BASE, "A", "B", 200),
+        leg(ChainId::BASE, "B", "A", 100),
+        leg(ChainId::ARBITRUM, "C", "D", 50),
+        leg(ChainId::ARBITRUM, "D", "C", 50),
+    ];
+    assert_eq!(compress_legs(&legs), [leg(ChainId::BASE, "A", "B", 400)]);
+}
+
+#[test]
+fn keeps_the_readme_example_at_one_leg_per_counterparty() {
+    let legs = [
+        leg(ChainId::BASE, "A", "B", 500_000),
+        leg(ChainId::ARBITRUM, "B", "A", 500_000),
+        leg(ChainId::BASE, "A", "C", 300_000),
+        leg(ChainId::ARBITRUM, "C", "A", 300_000),
+        leg(ChainId::BASE, "A", "D", 200_000),
+        leg(ChainId::ARBITRUM, "D", "A", 200_000),
+    ];
+    let compressed = compress_legs(&legs);
+    assert_eq!(compressed.len(), 6);
+    assert_eq!(net_positions(&compressed), net_positions(&legs));
+}
+
+proptest! {
+    #[test]
+    fn compression_keeps_every_net_position(legs in prop::collection::vec(arb_leg(), 0..40)) {
+        let compressed = compress_legs(&legs);
+
+        prop_assert_eq!(net_positions(&compressed), net_positions(&legs));
+        for leg in &compressed {
+            prop_assert!(leg.amount > 0);
+            prop_assert_ne!(&leg.from_user, &leg.to_user);
+        }
+        for chain in CHAINS {
+            // Each transfer settles at least one user completely.
+            let unsettled = net_positions(&legs)
+                .keys()
+                .filter(|(on, _)| *on == chain)
+                .count();
+            prop_assert!(legs_on(&compressed, chain) <= unsettled.saturating_sub(1));
+            prop_assert!(legs_on(&compressed, chain) <= legs_on(&legs, chain));
+        }
+    }
+}
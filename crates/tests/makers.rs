@@ -0,0 +1,171 @@
+mod common {
+    pub mod context;
+    pub mod context_with;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use async_trait::async_trait;
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::context::context;
+use common::context_with::context_with;
+use common::create::create;
+use common::keys::{USER_KEY, authenticator};
+use common::request::request;
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::CreateIntentRequest;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit, Role};
+use ocean_link_backend::blockchain::{BlockchainError, MockBlockchainClient, TxHash};
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::makers::LegDelegate;
+use ocean_link_backend::models::{ChainId, IntentKind, IntentStatus, Leg};
+use ocean_link_backend::risk::AlwaysPassRiskEngine;
+use ocean_link_backend::signing::{SignedIntent, SigningDomain, unix_now};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+const MAKER_KEY: &str = "maker-secret";
+
+/// Stands in for the feeder, recording the legs it is asked to send.
+#[derive(Default)]
+struct RecordingDelegate {
+    legs: Mutex<Vec<Leg>>,
+}
+
+#[async_trait]
+impl LegDelegate for RecordingDelegate {
+    async fn send_leg(&self, leg: &Leg) -> Result<TxHash, BlockchainError> {
+        let mut legs = self.legs.lock().expect("legs lock poisoned");
+        legs.push(leg.clone());
+        Ok(format!("feeder-{}", legs.len()))
+    }
+}
+
+async fn signed_body(wallet: &LocalWallet, kind: IntentKind) -> Value {
+    let mut req = SignedIntent {
+        user_id: format!("{:#x}", wallet.address()),
+        from_chain: ChainId::ARBITRUM,
+        to_chain: ChainId::BASE,
+        amount: 1_000,
+        nonce: 1,
+        deadline: unix_now() + 60,
+        signature: String::new(),
+        kind,
+    };
+    let message = req.message(&SigningDomain::new()).expect("valid address");
+    let signature = wallet
+        .sign_typed_data(&message)
+        .await
+        .expect("signing succeeds");
+    req.signature = signature.to_string();
+    json!({
+        "user_id": req.user_id,
+        "from_chain": "arbitrum",
+        "to_chain": "base",
+        "amount": req.amount,
+        "nonce": req.nonce,
+        "deadline": req.deadline,
+        "signature": req.signature,
+        "kind": kind,
+    })
+}
+
+async fn post_intent(app: &axum::Router, key: &str, body: &Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/intents")
+        .header(API_KEY_HEADER, key)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid request");
+    let response = app.clone().oneshot(req).await.expect("infallible");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (status, serde_json::from_slice(&body).expect("JSON body"))
+}
+
+#[tokio::test]
+async fn maker_intents_need_a_maker_key() {
+    let auth = authenticator(RateLimit::default()).with_key(MAKER_KEY, Role::Maker);
+    let app = router(context(MockBlockchainClient::default()).with_authenticator(auth));
+    let wallet = LocalWallet::new(&mut thread_rng());
+    let body = signed_body(&wallet, IntentKind::Maker).await;
+
+    let (status, error) = post_intent(&app, USER_KEY, &body).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(error["code"], "forbidden");
+
+    let (status, intent) = post_intent(&app, MAKER_KEY, &body).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(intent["kind"], "maker");
+
+    // Maker keys still submit ordinary intents.
+    let other = LocalWallet::new(&mut thread_rng());
+    let (status, intent) = post_intent(
+        &app,
+        MAKER_KEY,
+        &signed_body(&other, IntentKind::Taker).await,
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(intent["kind"], "taker");
+}
+
+#[tokio::test]
+async fn maker_legs_are_sent_by_the_delegate() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "taker", 1_000);
+    let delegate = Arc::new(RecordingDelegate::default());
+    let ctx = context_with(
+        Arc::new(AlwaysPassRiskEngine),
+        Executor::new(blockchain.clone()).with_delegate(delegate.clone()),
+    );
+
+    let taker = create(
+        &ctx,
+        request("taker", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+    )
+    .await;
+    let maker = create(
+        &ctx,
+        CreateIntentRequest {
+            kind: IntentKind::Maker,
+            ..request("maker", ChainId::ARBITRUM, ChainId::BASE, 1_000)
+        },
+    )
+    .await;
+    assert_eq!(maker.kind, IntentKind::Maker);
+
+    ctx.match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("intents net");
+
+    let delegated = delegate.legs.lock().expect("legs lock poisoned").clone();
+    assert_eq!(
+        delegated,
+        [Leg {
+            chain: ChainId::ARBITRUM,
+            from_user: "maker".into(),
+            to_user: "taker".into(),
+            amount: 1_000.into(),
+        }]
+    );
+    let sent: Vec<(ChainId, String)> = blockchain
+        .recorded_txs()
+        .into_iter()
+        .map(|tx| (tx.chain, tx.from_user))
+        .collect();
+    assert_eq!(sent, [(ChainId::BASE, "taker".to_string())]);
+    for id in [taker.id, maker.id] {
+        let intent = ctx.state.get(&id).expect("intent exists");
+        assert_eq!(intent.status, IntentStatus::Executed);
+    }
+}
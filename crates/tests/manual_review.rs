@@ -0,0 +1,140 @@
+mod common {
+    pub mod context_with;
+    pub mod create;
+    pub mod request;
+}
+
+use async_trait::async_trait;
+use common::create::create;
+use common::request::request;
+use ocean_link_backend::app::{AppContext, ReviewError};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::models::{ChainId, Intent, IntentStatus};
+use ocean_link_backend::risk::{
+    ManualReviewRule, MaxIntentSizeRule, RiskDecision, RiskRule, RuleBasedRiskEngine,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+fn context(engine: RuleBasedRiskEngine) -> (AppContext, MockBlockchainClient) {
+    let blockchain = MockBlockchainClient::default();
+    let ctx =
+        common::context_with::context_with(Arc::new(engine), Executor::new(blockchain.clone()));
+    (ctx, blockchain)
+}
+
+/// Holds every check until the test releases a permit, standing in for a
+/// slow screening service.
+struct GatedRule {
+    gate: Arc<Semaphore>,
+}
+
+#[async_trait]
+impl RiskRule for GatedRule {
+    async fn check(&self, _intent: &Intent) -> RiskDecision {
+        let _permit = self.gate.acquire().await.expect("gate open");
+        RiskDecision::Approve
+    }
+}
+
+#[tokio::test]
+async fn create_intent_returns_before_risk_completes() {
+    let gate = Arc::new(Semaphore::new(0));
+    let (ctx, _) = context(RuleBasedRiskEngine::new().with_rule(GatedRule { gate: gate.clone() }));
+
+    let intent = ctx
+        .create_intent(request("A", ChainId::BASE, ChainId::ARBITRUM, 100))
+        .await;
+    assert_eq!(intent.status, IntentStatus::PendingRisk);
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(
+        ctx.state.get(&intent.id).expect("stored").status,
+        IntentStatus::PendingRisk
+    );
+
+    gate.add_permits(1);
+    let settled = ctx.wait_for_risk(intent.id).await.expect("stored");
+    assert_eq!(settled.status, IntentStatus::InOrderbook);
+}
+
+#[tokio::test]
+async fn large_intents_wait_for_review() {
+    let (ctx, _) = context(RuleBasedRiskEngine::new().with_rule(ManualReviewRule::new(1_000)));
+
+    let small = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 999)).await;
+    let large = create(&ctx, request("B", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+
+    assert_eq!(small.status, IntentStatus::InOrderbook);
+    assert!(matches!(large.status, IntentStatus::PendingReview { .. }));
+    assert_eq!(ctx.orderbook_depth().await, (1, 999));
+
+    let queued = ctx.pending_reviews().await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].id, large.id);
+}
+
+#[tokio::test]
+async fn rejection_takes_precedence_over_review() {
+    let (ctx, _) = context(
+        RuleBasedRiskEngine::new()
+            .with_rule(ManualReviewRule::new(1_000))
+            .with_rule(MaxIntentSizeRule::new(5_000)),
+    );
+
+    let intent = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 6_000)).await;
+
+    assert!(matches!(intent.status, IntentStatus::Rejected { .. }));
+}
+
+#[tokio::test]
+async fn approved_intents_are_matched() {
+    let (ctx, blockchain) =
+        context(RuleBasedRiskEngine::new().with_rule(ManualReviewRule::new(1_000)));
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+
+    let a = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    let b = create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 1_000)).await;
+    assert!(ctx.match_and_execute().await.expect("round runs").is_none());
+
+    ctx.approve_intent(a.id).await.expect("approve A");
+    let approved_b = ctx.approve_intent(b.id).await.expect("approve B");
+    assert_eq!(approved_b.status, IntentStatus::InOrderbook);
+
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round runs")
+        .expect("solution expected");
+    assert_eq!(solution.involved_intent_ids.len(), 2);
+    assert!(ctx.pending_reviews().await.is_empty());
+}
+
+#[tokio::test]
+async fn rejected_review_records_reason() {
+    let (ctx, _) = context(RuleBasedRiskEngine::new().with_rule(ManualReviewRule::new(1)));
+
+    let intent = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 10)).await;
+    let rejected = ctx
+        .reject_intent(intent.id, "source of funds unclear".into())
+        .await
+        .expect("reject");
+
+    assert_eq!(
+        rejected.status,
+        IntentStatus::Rejected {
+            reason: "source of funds unclear".into()
+        }
+    );
+    assert!(matches!(
+        ctx.approve_intent(intent.id).await,
+        Err(ReviewError::NotUnderReview { .. })
+    ));
+    assert!(matches!(
+        ctx.approve_intent(uuid::Uuid::new_v4()).await,
+        Err(ReviewError::NotFound(_))
+    ));
+}
@@ -0,0 +1,123 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::create::create;
+use common::keys::{ADMIN_KEY, USER_KEY, authenticator};
+use common::request::request;
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::RateLimit;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::models::ChainId;
+use tower::ServiceExt;
+
+fn context(blockchain: MockBlockchainClient) -> AppContext {
+    common::context::context(blockchain).with_authenticator(authenticator(RateLimit::default()))
+}
+
+async fn scrape(ctx: &AppContext, key: &str) -> (StatusCode, String) {
+    let req = Request::builder()
+        .uri("/metrics")
+        .header(header::AUTHORIZATION, format!("Bearer {key}"))
+        .body(Body::empty())
+        .expect("valid request");
+    let response = router(ctx.clone()).oneshot(req).await.expect("infallible");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (status, String::from_utf8(body.to_vec()).expect("utf-8"))
+}
+
+#[tokio::test]
+async fn metrics_need_an_admin_key() {
+    let ctx = context(MockBlockchainClient::default());
+    let (status, _) = scrape(&ctx, USER_KEY).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn netted_rounds_update_volume_latency_and_depth() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 100);
+    let ctx = context(blockchain);
+    // Rounds without a solution are not timed.
+    assert!(
+        ctx.match_and_execute()
+            .await
+            .expect("empty round")
+            .is_none()
+    );
+
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+    let (_, before) = scrape(&ctx, ADMIN_KEY).await;
+    assert!(
+        before.contains(r#"oceanlink_orderbook_intents{from_chain="Base",to_chain="Arbitrum"} 1"#),
+        "{before}"
+    );
+    assert!(
+        before
+            .contains(r#"oceanlink_orderbook_notional{from_chain="Arbitrum",to_chain="Base"} 100"#)
+    );
+
+    ctx.match_and_execute().await.expect("execution succeeds");
+    // A leftover intent stays on the book.
+    create(&ctx, request("C", ChainId::BASE, ChainId::ARBITRUM, 7)).await;
+
+    let (status, after) = scrape(&ctx, ADMIN_KEY).await;
+    assert_eq!(status, StatusCode::OK);
+    // The 100 matched between A and B is counted once, not once per side.
+    assert!(
+        after.contains("oceanlink_netted_volume_total 100"),
+        "{after}"
+    );
+    assert!(after.contains("oceanlink_match_round_duration_seconds_count 1"));
+    assert!(
+        after.contains(r#"oceanlink_orderbook_notional{from_chain="Base",to_chain="Arbitrum"} 7"#)
+    );
+    assert!(
+        after.contains(r#"oceanlink_orderbook_intents{from_chain="Arbitrum",to_chain="Base"} 0"#)
+    );
+}
+
+#[tokio::test]
+async fn only_the_netted_part_of_an_intent_counts_as_netted() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 60);
+    let ctx = context(blockchain);
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 60)).await;
+
+    ctx.match_and_execute().await.expect("execution succeeds");
+
+    let (_, body) = scrape(&ctx, ADMIN_KEY).await;
+    assert!(body.contains("oceanlink_netted_volume_total 60"), "{body}");
+}
+
+#[tokio::test]
+async fn failed_legs_are_counted_by_chain() {
+    // B holds nothing on Arbitrum, so the Arbitrum leg fails.
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    let ctx = context(blockchain);
+    create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+
+    ctx.match_and_execute().await.expect_err("B cannot pay");
+
+    let (_, body) = scrape(&ctx, ADMIN_KEY).await;
+    assert!(
+        body.contains(r#"oceanlink_leg_failures_total{chain="Arbitrum"} 1"#),
+        "{body}"
+    );
+    assert!(body.contains("oceanlink_netted_volume_total 0"));
+}
@@ -1,74 +1,56 @@
-use ocean_link_backend::app::{AppContext, CreateIntentRequest};
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod intent;
+    pub mod request;
+}
+
+use common::create::create;
+use common::intent::intent;
+use common::request::request;
 use ocean_link_backend::blockchain::MockBlockchainClient;
-use ocean_link_backend::executor::Executor;
-use ocean_link_backend::models::{ChainId, IntentStatus, LocalTx};
-use ocean_link_backend::risk::{AlwaysPassRiskEngine, RiskEngine};
-use ocean_link_backend::state::State;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
-fn sort_key(tx: &LocalTx) -> (u8, &str, &str, u64) {
-    (
-        match tx.chain {
-            ChainId::Base => 0,
-            ChainId::Arbitrum => 1,
-        },
-        &tx.from_user,
-        &tx.to_user,
-        tx.amount,
-    )
+use ocean_link_backend::models::{Amount, ChainId, IntentStatus, Leg};
+use ocean_link_backend::solver::compute_solution;
+
+fn sort_key(tx: &Leg) -> (ChainId, &str, &str, Amount) {
+    (tx.chain, &tx.from_user, &tx.to_user, tx.amount)
 }
 
 #[tokio::test]
 async fn nets_four_intents_end_to_end() {
-    let state = Arc::new(Mutex::new(State::new()));
-    let risk_engine: Arc<dyn RiskEngine + Send + Sync> = Arc::new(AlwaysPassRiskEngine::default());
     let blockchain = MockBlockchainClient::default();
 
-    blockchain.set_balance(ChainId::Base, "A", 1_000_000);
-    blockchain.set_balance(ChainId::Arbitrum, "A", 0);
-    blockchain.set_balance(ChainId::Arbitrum, "B", 500_000);
-    blockchain.set_balance(ChainId::Arbitrum, "C", 300_000);
-    blockchain.set_balance(ChainId::Arbitrum, "D", 200_000);
-
-    let executor = Arc::new(Executor::new(blockchain.clone()));
-    let ctx = AppContext::new(state.clone(), risk_engine, executor);
-
-    let a_intent = ctx
-        .create_intent(CreateIntentRequest {
-            user_id: "A".into(),
-            from_chain: ChainId::Base,
-            to_chain: ChainId::Arbitrum,
-            amount: 1_000_000,
-        })
-        .await;
-
-    let b_intent = ctx
-        .create_intent(CreateIntentRequest {
-            user_id: "B".into(),
-            from_chain: ChainId::Arbitrum,
-            to_chain: ChainId::Base,
-            amount: 500_000,
-        })
-        .await;
-
-    let c_intent = ctx
-        .create_intent(CreateIntentRequest {
-            user_id: "C".into(),
-            from_chain: ChainId::Arbitrum,
-            to_chain: ChainId::Base,
-            amount: 300_000,
-        })
-        .await;
-
-    let d_intent = ctx
-        .create_intent(CreateIntentRequest {
-            user_id: "D".into(),
-            from_chain: ChainId::Arbitrum,
-            to_chain: ChainId::Base,
-            amount: 200_000,
-        })
-        .await;
+    blockchain.set_balance(ChainId::BASE, "A", 1_000_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "A", 0);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 500_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "C", 300_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "D", 200_000);
+
+    let ctx = common::context::context(blockchain.clone());
+
+    let a_intent = create(
+        &ctx,
+        request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000_000),
+    )
+    .await;
+
+    let b_intent = create(
+        &ctx,
+        request("B", ChainId::ARBITRUM, ChainId::BASE, 500_000),
+    )
+    .await;
+
+    let c_intent = create(
+        &ctx,
+        request("C", ChainId::ARBITRUM, ChainId::BASE, 300_000),
+    )
+    .await;
+
+    let d_intent = create(
+        &ctx,
+        request("D", ChainId::ARBITRUM, ChainId::BASE, 200_000),
+    )
+    .await;
 
     assert_eq!(a_intent.status, IntentStatus::InOrderbook);
     assert_eq!(b_intent.status, IntentStatus::InOrderbook);
@@ -88,41 +70,41 @@ async fn nets_four_intents_end_to_end() {
     actual_txs.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
 
     let mut expected = vec![
-        LocalTx {
-            chain: ChainId::Base,
+        Leg {
+            chain: ChainId::BASE,
             from_user: "A".into(),
             to_user: "B".into(),
-            amount: 500_000,
+            amount: 500_000.into(),
         },
-        LocalTx {
-            chain: ChainId::Base,
+        Leg {
+            chain: ChainId::BASE,
             from_user: "A".into(),
             to_user: "C".into(),
-            amount: 300_000,
+            amount: 300_000.into(),
         },
-        LocalTx {
-            chain: ChainId::Base,
+        Leg {
+            chain: ChainId::BASE,
             from_user: "A".into(),
             to_user: "D".into(),
-            amount: 200_000,
+            amount: 200_000.into(),
         },
-        LocalTx {
-            chain: ChainId::Arbitrum,
+        Leg {
+            chain: ChainId::ARBITRUM,
             from_user: "B".into(),
             to_user: "A".into(),
-            amount: 500_000,
+            amount: 500_000.into(),
         },
-        LocalTx {
-            chain: ChainId::Arbitrum,
+        Leg {
+            chain: ChainId::ARBITRUM,
             from_user: "C".into(),
             to_user: "A".into(),
-            amount: 300_000,
+            amount: 300_000.into(),
         },
-        LocalTx {
-            chain: ChainId::Arbitrum,
+        Leg {
+            chain: ChainId::ARBITRUM,
             from_user: "D".into(),
             to_user: "A".into(),
-            amount: 200_000,
+            amount: 200_000.into(),
         },
     ];
     expected.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
@@ -134,17 +116,17 @@ async fn nets_four_intents_end_to_end() {
 
     let mut sorted_recorded = recorded.clone();
     sorted_recorded.sort_by(|a, b| {
-        sort_key(&LocalTx {
+        sort_key(&Leg {
             chain: a.chain,
             from_user: a.from_user.clone(),
             to_user: a.to_user.clone(),
-            amount: a.amount,
+            amount: a.amount.into(),
         })
-        .cmp(&sort_key(&LocalTx {
+        .cmp(&sort_key(&Leg {
             chain: b.chain,
             from_user: b.from_user.clone(),
             to_user: b.to_user.clone(),
-            amount: b.amount,
+            amount: b.amount.into(),
         }))
     });
 
@@ -155,22 +137,98 @@ async fn nets_four_intents_end_to_end() {
         assert_eq!(tx.amount, expected_tx.amount);
     }
 
-    let guard = ctx.state.lock().await;
     for intent_id in solution.involved_intent_ids {
-        let intent = guard
+        let intent = ctx
+            .state
             .get(&intent_id)
             .expect("intent should exist after execution");
         assert_eq!(intent.status, IntentStatus::Executed);
     }
-    drop(guard);
 
-    assert_eq!(blockchain.balance(ChainId::Base, "A"), 0);
-    assert_eq!(blockchain.balance(ChainId::Base, "B"), 500_000);
-    assert_eq!(blockchain.balance(ChainId::Base, "C"), 300_000);
-    assert_eq!(blockchain.balance(ChainId::Base, "D"), 200_000);
+    assert_eq!(blockchain.balance(ChainId::BASE, "A"), 0);
+    assert_eq!(blockchain.balance(ChainId::BASE, "B"), 500_000);
+    assert_eq!(blockchain.balance(ChainId::BASE, "C"), 300_000);
+    assert_eq!(blockchain.balance(ChainId::BASE, "D"), 200_000);
+
+    assert_eq!(blockchain.balance(ChainId::ARBITRUM, "A"), 1_000_000);
+    assert_eq!(blockchain.balance(ChainId::ARBITRUM, "B"), 0);
+    assert_eq!(blockchain.balance(ChainId::ARBITRUM, "C"), 0);
+    assert_eq!(blockchain.balance(ChainId::ARBITRUM, "D"), 0);
+}
+
+#[test]
+fn each_pair_of_chains_nets_on_its_own() {
+    let intents = [
+        intent("taker", ChainId::SEPOLIA, ChainId::BASE_SEPOLIA, 700),
+        intent("maker", ChainId::BASE_SEPOLIA, ChainId::SEPOLIA, 500),
+        // Nothing goes the other way on these routes, so they wait.
+        intent("A", ChainId::BASE, ChainId::ARBITRUM, 100),
+        intent("B", ChainId::BASE, ChainId::SEPOLIA, 100),
+    ];
+
+    let solution = compute_solution(&intents).expect("the testnet pair nets");
+    assert_eq!(
+        solution.txs,
+        [
+            Leg {
+                chain: ChainId::BASE_SEPOLIA,
+                from_user: "maker".into(),
+                to_user: "taker".into(),
+                amount: 500.into(),
+            },
+            Leg {
+                chain: ChainId::SEPOLIA,
+                from_user: "taker".into(),
+                to_user: "maker".into(),
+                amount: 500.into(),
+            },
+        ]
+    );
+    let mut involved = solution.involved_intent_ids;
+    involved.sort();
+    let mut expected = vec![intents[0].id, intents[1].id];
+    expected.sort();
+    assert_eq!(involved, expected);
+}
+
+#[tokio::test]
+async fn unnetted_remainder_stays_in_the_book() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 600);
+    blockchain.set_balance(ChainId::ARBITRUM, "C", 400);
+    let ctx = common::context::context(blockchain);
+
+    let a = create(&ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000)).await;
+    let b = create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 600)).await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("solution expected");
+
+    let stored = ctx.state.get(&a.id).expect("intent exists");
+    assert_eq!(
+        stored.status,
+        IntentStatus::PartiallyFilled { filled: 600.into() }
+    );
+    assert_eq!(stored.amount, Amount::from(1_000));
+    assert_eq!(
+        ctx.state.get(&b.id).expect("intent exists").status,
+        IntentStatus::Executed
+    );
+    assert_eq!(ctx.orderbook_depth().await, (1, 400));
+
+    create(&ctx, request("C", ChainId::ARBITRUM, ChainId::BASE, 400)).await;
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("solution expected");
 
-    assert_eq!(blockchain.balance(ChainId::Arbitrum, "A"), 1_000_000);
-    assert_eq!(blockchain.balance(ChainId::Arbitrum, "B"), 0);
-    assert_eq!(blockchain.balance(ChainId::Arbitrum, "C"), 0);
-    assert_eq!(blockchain.balance(ChainId::Arbitrum, "D"), 0);
+    assert!(solution.txs.iter().all(|tx| tx.amount == 400));
+    assert_eq!(
+        ctx.state.get(&a.id).expect("intent exists").status,
+        IntentStatus::Executed
+    );
+    assert_eq!(ctx.orderbook_depth().await, (0, 0));
 }
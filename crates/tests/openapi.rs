@@ -0,0 +1,251 @@
+mod common {
+    pub mod context;
+    pub mod keys;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::context::context;
+use common::keys::{ADMIN_KEY, authenticator};
+use ocean_link_backend::api::{ApiDoc, router};
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use serde_json::Value;
+use tower::ServiceExt;
+use utoipa::OpenApi;
+
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+/// Routes the spec leaves out: it does not describe itself.
+const UNDOCUMENTED: [&str; 1] = ["/openapi.json"];
+
+fn app() -> axum::Router {
+    let auth = authenticator(RateLimit {
+        per_second: 1_000.0,
+        burst: 1_000,
+    });
+    router(context(MockBlockchainClient::default()).with_authenticator(auth))
+}
+
+/// Fills `{param}` segments so the path can be requested.
+fn concrete(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with('{') {
+                uuid::Uuid::nil().to_string()
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+async fn call(app: &axum::Router, method: &str, uri: &str) -> (StatusCode, Vec<u8>) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{}"))
+        .expect("valid request");
+    let response = app.clone().oneshot(req).await.expect("infallible");
+    let status = response.status();
+    // Only 404 bodies are inspected; others may be endless streams.
+    if status != StatusCode::NOT_FOUND {
+        return (status, Vec::new());
+    }
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (status, body.to_vec())
+}
+
+fn documented_operations() -> Vec<(String, Vec<&'static str>)> {
+    let spec = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+    spec["paths"]
+        .as_object()
+        .expect("spec has paths")
+        .iter()
+        .map(|(path, item)| {
+            let methods = METHODS
+                .into_iter()
+                .filter(|method| item.get(method.to_lowercase()).is_some())
+                .collect();
+            (path.clone(), methods)
+        })
+        .collect()
+}
+
+/// The body of [`router`]. axum cannot list a router's routes, so they are
+/// read from the source.
+fn router_source() -> &'static str {
+    let source = include_str!("../src/api.rs");
+    let start = source.find("pub fn router(").expect("router is defined");
+    let len = source[start..].find("\n}\n").expect("router ends");
+    &source[start..start + len]
+}
+
+/// Whether `handlers` calls the method router `name`, e.g. `get(..)`.
+fn calls(handlers: &str, name: &str) -> bool {
+    handlers
+        .match_indices(&format!("{name}("))
+        .any(|(at, _)| at == 0 || matches!(handlers.as_bytes()[at - 1], b' ' | b'.'))
+}
+
+/// Every `.route(..)` of the router, with axum's `:param` segments written
+/// as OpenAPI `{param}`.
+fn routed_operations() -> Vec<(String, Vec<&'static str>)> {
+    router_source()
+        .lines()
+        .filter_map(|line| {
+            let (path, handlers) = line.trim().strip_prefix(".route(\"")?.split_once('"')?;
+            let path = path
+                .split('/')
+                .map(|segment| match segment.strip_prefix(':') {
+                    Some(param) => format!("{{{param}}}"),
+                    None => segment.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let methods = METHODS
+                .into_iter()
+                .filter(|method| calls(handlers, &method.to_lowercase()))
+                .collect();
+            Some((path, methods))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn spec_is_served_without_a_key() {
+    let req = Request::builder()
+        .uri("/openapi.json")
+        .body(Body::empty())
+        .expect("valid request");
+    let response = app().oneshot(req).await.expect("infallible");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    let served: Value = serde_json::from_slice(&body).expect("spec is JSON");
+    let expected = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+    assert_eq!(served, expected);
+    assert!(
+        served["openapi"]
+            .as_str()
+            .is_some_and(|v| v.starts_with("3."))
+    );
+}
+
+#[tokio::test]
+async fn every_documented_operation_is_routed() {
+    let app = app();
+    for (path, methods) in documented_operations() {
+        assert!(!methods.is_empty(), "{path} documents no operations");
+        for method in methods {
+            let (status, body) = call(&app, method, &concrete(&path)).await;
+            // An unmatched route is an empty 404; handlers answer 404 with an
+            // error body.
+            let unrouted = status == StatusCode::METHOD_NOT_ALLOWED
+                || (status == StatusCode::NOT_FOUND && body.is_empty());
+            assert!(!unrouted, "{method} {path} is documented but not routed");
+        }
+    }
+}
+
+#[tokio::test]
+async fn every_routed_operation_is_documented() {
+    let routed = routed_operations();
+    assert_eq!(
+        routed.len(),
+        router_source().matches(".route(").count(),
+        "every route fits on one line"
+    );
+    let documented = documented_operations();
+    let app = app();
+    for (path, methods) in routed {
+        assert!(!methods.is_empty(), "{path} routes no methods");
+        if UNDOCUMENTED.contains(&path.as_str()) {
+            continue;
+        }
+        let documented = documented
+            .iter()
+            .find(|(documented, _)| *documented == path)
+            .map(|(_, methods)| methods)
+            .unwrap_or_else(|| panic!("{path} is routed but not documented"));
+        for method in methods {
+            assert!(
+                documented.contains(&method),
+                "{method} {path} is routed but not documented"
+            );
+            // Guards the source scan itself: what it found must be served.
+            let (status, body) = call(&app, method, &concrete(&path)).await;
+            assert!(
+                status != StatusCode::METHOD_NOT_ALLOWED
+                    && !(status == StatusCode::NOT_FOUND && body.is_empty()),
+                "{method} {path} was read from the router but is not served"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn undocumented_methods_are_not_routed() {
+    let app = app();
+    for (path, documented) in documented_operations() {
+        for method in METHODS.into_iter().filter(|m| !documented.contains(m)) {
+            let (status, _) = call(&app, method, &concrete(&path)).await;
+            assert_eq!(
+                status,
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{method} {path} is routed but not documented"
+            );
+        }
+    }
+}
+
+#[test]
+fn request_and_response_types_are_described() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+    let schemas = &spec["components"]["schemas"];
+    for name in [
+        "SignedIntentRequest",
+        "Intent",
+        "IntentStatus",
+        "NettingSolution",
+        "ApiError",
+    ] {
+        assert!(schemas.get(name).is_some(), "missing schema {name}");
+    }
+}
+
+#[test]
+fn every_schema_reference_resolves() {
+    fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    found.push(target);
+                }
+                map.values().for_each(|child| refs(child, found));
+            }
+            Value::Array(items) => items.iter().for_each(|child| refs(child, found)),
+            _ => {}
+        }
+    }
+
+    let spec = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+    let mut found = Vec::new();
+    refs(&spec, &mut found);
+    assert!(!found.is_empty());
+    for target in found {
+        let name = target
+            .strip_prefix("#/components/schemas/")
+            .unwrap_or_else(|| panic!("unexpected reference {target}"));
+        assert!(
+            spec["components"]["schemas"].get(name).is_some(),
+            "{target} is not defined"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod intent;
+    pub mod keys;
+    pub mod request;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::context::context;
+use common::create::create;
+use common::intent::intent;
+use common::keys::{USER_KEY, authenticator};
+use common::request::request;
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::bridge::{MockBridge, Rebalancer};
+use ocean_link_backend::inventory::{ChainTarget, Inventory};
+use ocean_link_backend::models::{Amount, ChainId, IntentKind, Route};
+use ocean_link_backend::quote::{Quote, QuoteError, Quoter};
+use ocean_link_backend::signing::{SignedIntent, SigningDomain, unix_now};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const ROUND: Duration = Duration::from_secs(5);
+const BASE_TO_ARBITRUM: Route = Route {
+    from_chain: ChainId::BASE,
+    to_chain: ChainId::ARBITRUM,
+};
+
+#[tokio::test]
+async fn quotes_net_what_they_can_and_fill_the_rest_from_inventory() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::ARBITRUM, "solver", 10_000);
+    let target = ChainTarget {
+        target_ratio: 0.5,
+        min_balance: 0,
+    };
+    let ctx = context(blockchain)
+        .with_authenticator(authenticator(RateLimit::default()))
+        .with_inventory(
+            Inventory::new("solver", 50)
+                .with_chain(ChainId::BASE, target)
+                .with_chain(ChainId::ARBITRUM, target),
+        )
+        .with_quoter(
+            Quoter::new("secret", Duration::from_secs(30)).with_round_interval(Some(ROUND)),
+        );
+    // 600 flows the other way, but C's 200 is queued ahead on the same route.
+    for (user_id, from_chain, to_chain, amount) in [
+        ("B", ChainId::ARBITRUM, ChainId::BASE, 600),
+        ("C", ChainId::BASE, ChainId::ARBITRUM, 200),
+    ] {
+        create(&ctx, request(user_id, from_chain, to_chain, amount)).await;
+    }
+
+    let request = Request::builder()
+        .uri("/quote?from_chain=base&to_chain=arbitrum&amount=1000")
+        .header(API_KEY_HEADER, USER_KEY)
+        .body(Body::empty())
+        .expect("valid request");
+    let response = router(ctx.clone())
+        .oneshot(request)
+        .await
+        .expect("infallible");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    let quote: Quote = serde_json::from_slice(&body).expect("quote is JSON");
+
+    assert_eq!(quote.nettable, 400);
+    assert_eq!(quote.inventory_fill, 600);
+    assert_eq!(quote.fee, 3);
+    assert_eq!(quote.receive, 997);
+    assert!(!quote.uses_bridge);
+    assert_eq!(quote.expected_wait_ms, Some(5_000));
+    assert_eq!(quote.expires_at, quote.issued_at + 30);
+    assert_eq!(
+        ctx.verify_quote(&quote, BASE_TO_ARBITRUM, 1_000.into()),
+        Ok(())
+    );
+
+    let tampered = Quote {
+        receive: Amount::from(1_000),
+        ..quote
+    };
+    assert_eq!(
+        ctx.verify_quote(&tampered, BASE_TO_ARBITRUM, 1_000.into()),
+        Err(QuoteError::BadSignature)
+    );
+}
+
+#[test]
+fn unfilled_volume_is_bridged_or_left_waiting() {
+    let quoter = Quoter::new("secret", Duration::from_secs(30)).with_round_interval(Some(ROUND));
+    let orderbook = [intent("B", ChainId::ARBITRUM, ChainId::BASE, 300)];
+    let rebalancer = Rebalancer::new(Arc::new(MockBridge::default()), Duration::from_secs(60));
+
+    let bridged = quoter.quote(
+        BASE_TO_ARBITRUM,
+        1_000.into(),
+        &orderbook,
+        None,
+        Some(&rebalancer),
+        1_000,
+    );
+    assert_eq!(bridged.nettable, 300);
+    assert_eq!(bridged.bridged, 700);
+    assert!(bridged.uses_bridge);
+    assert_eq!(bridged.receive, 1_000);
+    // The residual of a partly netted intent is bridged in the next round.
+    assert_eq!(bridged.expected_wait_ms, Some(5_000));
+
+    let unmatched = quoter.quote(
+        Route {
+            from_chain: ChainId::ARBITRUM,
+            to_chain: ChainId::BASE,
+        },
+        1_000.into(),
+        &orderbook,
+        None,
+        Some(&rebalancer),
+        1_000,
+    );
+    assert_eq!(unmatched.nettable, 0);
+    assert_eq!(unmatched.bridged, 1_000);
+    assert_eq!(unmatched.expected_wait_ms, Some(65_000));
+
+    let waiting = quoter.quote(
+        BASE_TO_ARBITRUM,
+        1_000.into(),
+        &orderbook,
+        None,
+        None,
+        1_000,
+    );
+    assert_eq!(waiting.bridged, 0);
+    assert!(!waiting.uses_bridge);
+    assert_eq!(waiting.receive, 300);
+    assert_eq!(waiting.expected_wait_ms, None);
+
+    assert_eq!(
+        quoter.verify(&waiting, BASE_TO_ARBITRUM, 1_000.into(), 1_030),
+        Ok(())
+    );
+    assert_eq!(
+        quoter.verify(&waiting, BASE_TO_ARBITRUM, 1_000.into(), 1_031),
+        Err(QuoteError::Expired { expires_at: 1_030 })
+    );
+    let other = Quoter::new("other secret", Duration::from_secs(30));
+    assert_eq!(
+        other.verify(&waiting, BASE_TO_ARBITRUM, 1_000.into(), 1_000),
+        Err(QuoteError::BadSignature)
+    );
+    assert_eq!(
+        quoter.verify(&waiting, BASE_TO_ARBITRUM, 999.into(), 1_000),
+        Err(QuoteError::WrongIntent)
+    );
+}
+
+async fn submit(
+    ctx: &AppContext,
+    wallet: &LocalWallet,
+    nonce: u64,
+    quote: &Quote,
+) -> (StatusCode, Value) {
+    let mut req = SignedIntent {
+        user_id: format!("{:#x}", wallet.address()),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 1_000,
+        nonce,
+        deadline: unix_now() + 60,
+        signature: String::new(),
+        kind: IntentKind::Taker,
+    };
+    let message = req.message(&SigningDomain::new()).expect("valid address");
+    req.signature = wallet
+        .sign_typed_data(&message)
+        .await
+        .expect("signing succeeds")
+        .to_string();
+    let body = json!({
+        "user_id": req.user_id,
+        "from_chain": "base",
+        "to_chain": "arbitrum",
+        "amount": req.amount,
+        "nonce": req.nonce,
+        "deadline": req.deadline,
+        "signature": req.signature,
+        "quote": quote,
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/intents")
+        .header(API_KEY_HEADER, USER_KEY)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid request");
+    let response = router(ctx.clone())
+        .oneshot(request)
+        .await
+        .expect("infallible");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (status, serde_json::from_slice(&body).expect("body is JSON"))
+}
+
+#[tokio::test]
+async fn intents_are_refused_against_a_quote_for_something_else() {
+    let ctx = context(MockBlockchainClient::default())
+        .with_authenticator(authenticator(RateLimit::default()));
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let quote = ctx.quote(BASE_TO_ARBITRUM, 1_000.into()).await;
+    let (status, _) = submit(&ctx, &wallet, 1, &quote).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let smaller = ctx.quote(BASE_TO_ARBITRUM, 999.into()).await;
+    let (status, body) = submit(&ctx, &wallet, 2, &smaller).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+    assert_eq!(body["details"]["field"], "quote");
+}
@@ -0,0 +1,147 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod intent;
+    pub mod request;
+}
+
+use common::context::context;
+use common::create::create;
+use common::intent::intent;
+use common::request::request;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::bridge::{MockBridge, Rebalancer};
+use ocean_link_backend::inventory::{ChainTarget, Inventory};
+use ocean_link_backend::models::{ChainId, Intent};
+use ocean_link_backend::solver::{ReplayError, SolverSnapshot, compute_solution, replay};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[test]
+fn identical_inputs_give_identical_solutions() {
+    let intents: Vec<Intent> = [
+        ("A", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+        ("B", ChainId::ARBITRUM, ChainId::BASE, 400),
+        ("C", ChainId::ARBITRUM, ChainId::BASE, 300),
+        ("D", ChainId::ARBITRUM, ChainId::BASE, 300),
+    ]
+    .into_iter()
+    .map(|(user, from, to, amount)| intent(user, from, to, amount))
+    .collect();
+
+    let first = compute_solution(&intents).expect("intents net");
+    for _ in 0..20 {
+        assert_eq!(compute_solution(&intents), Some(first.clone()));
+    }
+    let mut ids: Vec<Uuid> = intents.iter().map(|intent| intent.id).collect();
+    ids.sort();
+    assert_eq!(first.involved_intent_ids, ids);
+    assert!(first.id.starts_with("0x") && first.id.len() == 66);
+
+    let mut changed = intents.clone();
+    changed[3].amount = 200.into();
+    let other = compute_solution(&changed).expect("intents net");
+    assert_ne!(other.id, first.id, "the id covers the inputs");
+}
+
+#[tokio::test]
+async fn logged_solutions_replay_from_their_snapshot() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+    let ctx = context(blockchain);
+    for (user_id, from_chain, to_chain) in [
+        ("A", ChainId::BASE, ChainId::ARBITRUM),
+        ("B", ChainId::ARBITRUM, ChainId::BASE),
+    ] {
+        create(&ctx, request(user_id, from_chain, to_chain, 1_000)).await;
+    }
+
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("intents net");
+    assert_eq!(ctx.replay(&solution.id), Ok(solution.clone()));
+    assert_eq!(
+        ctx.replay("0xunknown"),
+        Err(ReplayError::NotFound("0xunknown".into()))
+    );
+
+    // A snapshot that was tampered with no longer reproduces the solution.
+    let (mut snapshot, recorded): (SolverSnapshot, _) = ctx
+        .state
+        .solutions()
+        .get(&solution.id)
+        .expect("solution is logged");
+    snapshot.intents[0].amount = 500.into();
+    let err = replay(&snapshot, &recorded).expect_err("inputs differ");
+    assert!(matches!(
+        err,
+        ReplayError::Mismatch { ref expected, actual: Some(_) } if *expected == solution.id
+    ));
+}
+
+#[tokio::test]
+async fn snapshots_keep_the_inventory_and_bridge_settings() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 400);
+    blockchain.set_balance(ChainId::BASE, "D", 50);
+    blockchain.set_balance(ChainId::ARBITRUM, "solver", 100);
+    let target = ChainTarget {
+        target_ratio: 0.5,
+        min_balance: 0,
+    };
+    let inventory = Inventory::new("solver", 50)
+        .with_chain(ChainId::BASE, target)
+        .with_chain(ChainId::ARBITRUM, target);
+    let ctx = context(blockchain)
+        .with_inventory(inventory.clone())
+        .with_rebalancer(Rebalancer::new(
+            Arc::new(MockBridge::default()),
+            Duration::from_secs(60),
+        ));
+    for (user_id, from_chain, to_chain, amount) in [
+        ("A", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+        ("B", ChainId::ARBITRUM, ChainId::BASE, 400),
+        ("D", ChainId::BASE, ChainId::ARBITRUM, 50),
+    ] {
+        create(&ctx, request(user_id, from_chain, to_chain, amount)).await;
+    }
+
+    // The inventory cannot pay out the rest of A, which is bridged, but it
+    // fills D.
+    let solution = ctx
+        .match_and_execute()
+        .await
+        .expect("round succeeds")
+        .expect("intents settle");
+    assert_eq!(solution.inventory_fills.len(), 1);
+    assert_eq!(solution.bridged.len(), 1);
+
+    let (snapshot, recorded) = ctx
+        .state
+        .solutions()
+        .get(&solution.id)
+        .expect("solution is logged");
+    assert_eq!(snapshot.inventory_config, Some(inventory));
+    let bridge = snapshot.bridge.clone().expect("bridge is recorded");
+    assert_eq!((bridge.via.as_str(), bridge.wait.as_secs()), ("mock", 60));
+    // The snapshot alone reproduces the solution.
+    assert_eq!(replay(&snapshot, &recorded), Ok(solution.clone()));
+
+    let mut wider_spread = snapshot.clone();
+    if let Some(inventory) = &mut wider_spread.inventory_config {
+        *inventory = Inventory::new("solver", 100)
+            .with_chain(ChainId::BASE, target)
+            .with_chain(ChainId::ARBITRUM, target);
+    }
+    assert!(replay(&wider_spread, &recorded).is_err());
+    let unbridged = SolverSnapshot {
+        bridge: None,
+        ..snapshot
+    };
+    assert!(replay(&unbridged, &recorded).is_err());
+}
@@ -0,0 +1,216 @@
+mod common {
+    pub mod context_with;
+    pub mod create;
+    pub mod request;
+}
+
+use async_trait::async_trait;
+use common::create::create;
+use common::request::request;
+use ocean_link_backend::app::{AppContext, CreateIntentRequest};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::models::{ChainId, Intent, IntentKind, IntentStatus, Route};
+use ocean_link_backend::risk::{
+    AllowedRoutesRule, DailyNotionalRule, ManualReviewRule, MaxIntentSizeRule, RiskDecision,
+    RiskEngine, RiskRejection, RiskRule, RuleBasedRiskEngine, SanctionsListRule,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn context(engine: RuleBasedRiskEngine) -> AppContext {
+    context_on(engine, MockBlockchainClient::default())
+}
+
+fn context_on(engine: RuleBasedRiskEngine, blockchain: MockBlockchainClient) -> AppContext {
+    common::context_with::context_with(Arc::new(engine), Executor::new(blockchain))
+}
+
+fn base_to_arbitrum(user: &str, amount: u64) -> CreateIntentRequest {
+    request(user, ChainId::BASE, ChainId::ARBITRUM, amount)
+}
+
+#[tokio::test]
+async fn oversized_intent_is_rejected_with_reason() {
+    let ctx = context(RuleBasedRiskEngine::new().with_rule(MaxIntentSizeRule::new(1_000)));
+
+    let accepted = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    let rejected = create(&ctx, base_to_arbitrum("A", 1_001)).await;
+
+    assert_eq!(accepted.status, IntentStatus::InOrderbook);
+    assert_eq!(
+        rejected.status,
+        IntentStatus::Rejected {
+            reason: RiskRejection::MaxIntentSize {
+                amount: 1_001,
+                max: 1_000
+            }
+            .to_string()
+        }
+    );
+    assert_eq!(ctx.orderbook_depth().await, (1, 1_000));
+
+    let listed = ctx
+        .list_intents()
+        .await
+        .into_iter()
+        .find(|intent| intent.id == rejected.id)
+        .expect("rejected intent is still listed");
+    assert!(matches!(listed.status, IntentStatus::Rejected { .. }));
+}
+
+#[tokio::test]
+async fn daily_notional_accumulates_per_user() {
+    let ctx = context(RuleBasedRiskEngine::new().with_rule(DailyNotionalRule::new(1_500)));
+
+    let first = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    let second = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    let other_user = create(&ctx, base_to_arbitrum("B", 1_000)).await;
+    let fits = create(&ctx, base_to_arbitrum("A", 500)).await;
+
+    assert_eq!(first.status, IntentStatus::InOrderbook);
+    assert!(matches!(second.status, IntentStatus::Rejected { .. }));
+    assert_eq!(other_user.status, IntentStatus::InOrderbook);
+    assert_eq!(fits.status, IntentStatus::InOrderbook);
+}
+
+#[tokio::test]
+async fn rejected_intents_do_not_consume_daily_notional() {
+    let ctx = context(
+        RuleBasedRiskEngine::new()
+            .with_rule(DailyNotionalRule::new(1_000))
+            .with_rule(MaxIntentSizeRule::new(600)),
+    );
+
+    let too_big = create(&ctx, base_to_arbitrum("A", 800)).await;
+    let fits = create(&ctx, base_to_arbitrum("A", 600)).await;
+
+    assert!(matches!(too_big.status, IntentStatus::Rejected { .. }));
+    assert_eq!(fits.status, IntentStatus::InOrderbook);
+}
+
+/// Hands the runtime to other checks, as a rule that calls out would.
+struct YieldingRule;
+
+#[async_trait]
+impl RiskRule for YieldingRule {
+    async fn check(&self, _intent: &Intent) -> RiskDecision {
+        tokio::task::yield_now().await;
+        RiskDecision::Approve
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_checks_cannot_share_daily_headroom() {
+    let engine = Arc::new(
+        RuleBasedRiskEngine::new()
+            .with_rule(DailyNotionalRule::new(1_000))
+            .with_rule(YieldingRule),
+    );
+
+    let checks: Vec<_> = (0..16)
+        .map(|_| {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                let intent = Intent {
+                    id: Uuid::new_v4(),
+                    user_id: "A".into(),
+                    from_chain: ChainId::BASE,
+                    to_chain: ChainId::ARBITRUM,
+                    amount: 500.into(),
+                    kind: IntentKind::Taker,
+                    status: IntentStatus::PendingRisk,
+                };
+                engine.check_intent(&intent).await
+            })
+        })
+        .collect();
+    let mut approved = 0;
+    for check in checks {
+        if check.await.expect("check task panicked") == RiskDecision::Approve {
+            approved += 1;
+        }
+    }
+
+    assert_eq!(approved, 2);
+}
+
+#[tokio::test]
+async fn rejected_reviews_give_back_daily_notional() {
+    let ctx = context(
+        RuleBasedRiskEngine::new()
+            .with_rule(DailyNotionalRule::new(1_000))
+            .with_rule(ManualReviewRule::new(1_000)),
+    );
+
+    let held = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    assert!(matches!(held.status, IntentStatus::PendingReview { .. }));
+    let over = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    assert!(matches!(over.status, IntentStatus::Rejected { .. }));
+
+    ctx.reject_intent(held.id, "source of funds unclear".into())
+        .await
+        .expect("intent is under review");
+    let again = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    assert!(matches!(again.status, IntentStatus::PendingReview { .. }));
+}
+
+#[tokio::test]
+async fn failed_execution_gives_back_daily_notional() {
+    // B holds nothing on Arbitrum, so the round fails.
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    let ctx = context_on(
+        RuleBasedRiskEngine::new().with_rule(DailyNotionalRule::new(1_000)),
+        blockchain,
+    );
+    create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 1_000)).await;
+
+    ctx.match_and_execute().await.expect_err("B cannot pay");
+
+    let again = create(&ctx, base_to_arbitrum("A", 1_000)).await;
+    assert_eq!(again.status, IntentStatus::InOrderbook);
+}
+
+#[tokio::test]
+async fn only_allowed_routes_enter_the_orderbook() {
+    let ctx = context(
+        RuleBasedRiskEngine::new().with_rule(AllowedRoutesRule::new([Route {
+            from_chain: ChainId::BASE,
+            to_chain: ChainId::ARBITRUM,
+        }])),
+    );
+
+    let allowed = create(&ctx, base_to_arbitrum("A", 100)).await;
+    let blocked = create(&ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+
+    assert_eq!(allowed.status, IntentStatus::InOrderbook);
+    assert!(matches!(blocked.status, IntentStatus::Rejected { .. }));
+}
+
+#[tokio::test]
+async fn sanctions_list_is_loaded_from_file() {
+    let path = std::env::temp_dir().join(format!("sanctions-{}.txt", Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        "# OFAC extract\n0xABCDEF0000000000000000000000000000000001\n\n0x02 # inline comment\n",
+    )
+    .expect("write sanctions file");
+    let rule = SanctionsListRule::from_file(&path).expect("load sanctions file");
+    std::fs::remove_file(&path).ok();
+
+    let ctx = context(RuleBasedRiskEngine::new().with_rule(rule));
+
+    let sanctioned = create(
+        &ctx,
+        base_to_arbitrum("0xabcdef0000000000000000000000000000000001", 100),
+    )
+    .await;
+    let inline = create(&ctx, base_to_arbitrum("0x02", 100)).await;
+    let clean = create(&ctx, base_to_arbitrum("0x03", 100)).await;
+
+    assert!(matches!(sanctioned.status, IntentStatus::Rejected { .. }));
+    assert!(matches!(inline.status, IntentStatus::Rejected { .. }));
+    assert_eq!(clean.status, IntentStatus::InOrderbook);
+}
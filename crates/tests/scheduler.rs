@@ -0,0 +1,162 @@
+mod common {
+    pub mod context;
+    pub mod request;
+}
+
+use common::request::request;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::models::{ChainId, Intent, IntentKind, IntentStatus};
+use ocean_link_backend::scheduler::{Scheduler, SchedulerConfig, SchedulerError};
+use ocean_link_backend::state::State;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn context() -> (AppContext, MockBlockchainClient) {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 1_000);
+    (common::context::context(blockchain.clone()), blockchain)
+}
+
+async fn submit_pair(ctx: &AppContext) {
+    ctx.create_intent(request("A", ChainId::BASE, ChainId::ARBITRUM, 1_000))
+        .await;
+    ctx.create_intent(request("B", ChainId::ARBITRUM, ChainId::BASE, 1_000))
+        .await;
+}
+
+async fn wait_for_execution(ctx: &AppContext) -> bool {
+    for _ in 0..100 {
+        let intents = ctx.list_intents().await;
+        if !intents.is_empty() && intents.iter().all(|i| i.status == IntentStatus::Executed) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn rejects_config_without_trigger() {
+    let (ctx, _) = context();
+    let config = SchedulerConfig {
+        interval: None,
+        min_intents: None,
+        min_notional: None,
+    };
+    assert!(matches!(
+        Scheduler::spawn(ctx, config),
+        Err(SchedulerError::NoTrigger)
+    ));
+}
+
+#[tokio::test]
+async fn interval_trigger_runs_rounds() {
+    let (ctx, blockchain) = context();
+    let handle = Scheduler::spawn(
+        ctx.clone(),
+        SchedulerConfig {
+            interval: Some(Duration::from_millis(20)),
+            min_intents: None,
+            min_notional: None,
+        },
+    )
+    .expect("valid config");
+
+    submit_pair(&ctx).await;
+    assert!(wait_for_execution(&ctx).await);
+    handle.shutdown().await;
+
+    assert_eq!(blockchain.recorded_txs().len(), 2);
+}
+
+#[tokio::test]
+async fn intent_count_threshold_triggers_round() {
+    let (ctx, blockchain) = context();
+    let handle = Scheduler::spawn(
+        ctx.clone(),
+        SchedulerConfig {
+            interval: None,
+            min_intents: Some(2),
+            min_notional: None,
+        },
+    )
+    .expect("valid config");
+
+    submit_pair(&ctx).await;
+    assert!(wait_for_execution(&ctx).await);
+    handle.shutdown().await;
+
+    assert_eq!(blockchain.recorded_txs().len(), 2);
+}
+
+#[tokio::test]
+async fn notional_threshold_waits_until_reached() {
+    let (ctx, _) = context();
+    let handle = Scheduler::spawn(
+        ctx.clone(),
+        SchedulerConfig {
+            interval: None,
+            min_intents: None,
+            min_notional: Some(5_000),
+        },
+    )
+    .expect("valid config");
+
+    submit_pair(&ctx).await;
+    assert!(!wait_for_execution(&ctx).await);
+    handle.shutdown().await;
+
+    assert!(ctx.last_solution().await.is_none());
+}
+
+#[tokio::test]
+async fn shutdown_stops_the_loop() {
+    let (ctx, blockchain) = context();
+    let handle = Scheduler::spawn(
+        ctx.clone(),
+        SchedulerConfig {
+            interval: Some(Duration::from_millis(20)),
+            min_intents: Some(1),
+            min_notional: None,
+        },
+    )
+    .expect("valid config");
+    handle.shutdown().await;
+
+    submit_pair(&ctx).await;
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    assert!(blockchain.recorded_txs().is_empty());
+    assert!(ctx.last_solution().await.is_none());
+}
+
+#[test]
+fn orderbook_depth_saturates_instead_of_wrapping() {
+    let state = State::new();
+    for (from_chain, to_chain) in [
+        (ChainId::BASE, ChainId::ARBITRUM),
+        (ChainId::ARBITRUM, ChainId::BASE),
+    ] {
+        let intent = Intent {
+            id: Uuid::new_v4(),
+            user_id: "A".into(),
+            from_chain,
+            to_chain,
+            amount: u64::MAX.into(),
+            kind: IntentKind::Taker,
+            status: IntentStatus::InOrderbook,
+        };
+        state.insert_intent(intent.clone());
+        state.add_to_orderbook(&intent);
+    }
+
+    assert_eq!(state.orderbook_depth(), (2, u64::MAX));
+    assert!(
+        state
+            .orderbook_depth_by_route()
+            .iter()
+            .all(|&(_, count, notional)| count == 1 && notional == u64::MAX)
+    );
+}
@@ -0,0 +1,191 @@
+mod common {
+    pub mod context;
+}
+
+use ethers::core::rand::thread_rng;
+use ethers::signers::{LocalWallet, Signer};
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::models::{ChainId, IntentKind};
+use ocean_link_backend::signing::{
+    MAX_DEADLINE_WINDOW, SignatureError, SignedIntent, SigningDomain, unix_now,
+};
+
+fn context() -> AppContext {
+    common::context::context(MockBlockchainClient::default())
+}
+
+fn address(wallet: &LocalWallet) -> String {
+    format!("{:#x}", wallet.address())
+}
+
+async fn sign(wallet: &LocalWallet, nonce: u64, deadline: u64) -> SignedIntent {
+    sign_in(&SigningDomain::new(), wallet, nonce, deadline).await
+}
+
+async fn sign_in(
+    domain: &SigningDomain,
+    wallet: &LocalWallet,
+    nonce: u64,
+    deadline: u64,
+) -> SignedIntent {
+    let mut req = SignedIntent {
+        user_id: address(wallet),
+        from_chain: ChainId::BASE,
+        to_chain: ChainId::ARBITRUM,
+        amount: 1_000,
+        nonce,
+        deadline,
+        signature: String::new(),
+        kind: IntentKind::Taker,
+    };
+    let message = req.message(domain).expect("valid address");
+    let signature = wallet
+        .sign_typed_data(&message)
+        .await
+        .expect("signing succeeds");
+    req.signature = signature.to_string();
+    req
+}
+
+#[tokio::test]
+async fn accepts_signature_from_the_claimed_user() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let req = sign(&wallet, 1, unix_now() + 60).await;
+    let verified = ctx.verify_signed_intent(req).expect("valid signature");
+
+    assert_eq!(verified.user_id, address(&wallet));
+    assert_eq!(verified.amount, 1_000);
+}
+
+#[tokio::test]
+async fn checksummed_user_ids_are_accepted() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let mut req = sign(&wallet, 1, unix_now() + 60).await;
+    req.user_id = ethers::utils::to_checksum(&wallet.address(), None);
+
+    let verified = ctx.verify_signed_intent(req).expect("valid signature");
+    assert_eq!(verified.user_id, address(&wallet));
+}
+
+#[tokio::test]
+async fn rejects_intent_submitted_on_behalf_of_someone_else() {
+    let ctx = context();
+    let signer = LocalWallet::new(&mut thread_rng());
+    let victim = LocalWallet::new(&mut thread_rng());
+
+    let mut req = sign(&signer, 1, unix_now() + 60).await;
+    req.user_id = address(&victim);
+
+    assert!(matches!(
+        ctx.verify_signed_intent(req),
+        Err(SignatureError::SignerMismatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn rejects_tampered_amount() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let mut req = sign(&wallet, 1, unix_now() + 60).await;
+    req.amount = 1_000_000;
+
+    assert!(matches!(
+        ctx.verify_signed_intent(req),
+        Err(SignatureError::SignerMismatch { .. })
+    ));
+}
+
+#[tokio::test]
+async fn rejects_replayed_nonce() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let req = sign(&wallet, 7, unix_now() + 60).await;
+    ctx.verify_signed_intent(req.clone())
+        .expect("first use succeeds");
+
+    assert_eq!(
+        ctx.verify_signed_intent(req).unwrap_err(),
+        SignatureError::NonceReused {
+            user: address(&wallet),
+            nonce: 7
+        }
+    );
+
+    let next = sign(&wallet, 8, unix_now() + 60).await;
+    assert!(ctx.verify_signed_intent(next).is_ok());
+}
+
+#[tokio::test]
+async fn rejects_expired_deadline() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+    let deadline = unix_now() - 1;
+
+    let req = sign(&wallet, 1, deadline).await;
+
+    assert_eq!(
+        ctx.verify_signed_intent(req).unwrap_err(),
+        SignatureError::Expired { deadline }
+    );
+}
+
+#[tokio::test]
+async fn rejects_garbage_inputs() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let mut bad_signature = sign(&wallet, 1, unix_now() + 60).await;
+    bad_signature.signature = "0x123".into();
+    assert!(matches!(
+        ctx.verify_signed_intent(bad_signature),
+        Err(SignatureError::Malformed(_))
+    ));
+
+    let mut bad_user = sign(&wallet, 2, unix_now() + 60).await;
+    bad_user.user_id = "A".into();
+    assert!(matches!(
+        ctx.verify_signed_intent(bad_user),
+        Err(SignatureError::InvalidAddress(_))
+    ));
+}
+
+#[tokio::test]
+async fn rejects_deadlines_beyond_the_window() {
+    let ctx = context();
+    let wallet = LocalWallet::new(&mut thread_rng());
+    let window = MAX_DEADLINE_WINDOW.as_secs();
+    let deadline = unix_now() + window + 60;
+
+    let req = sign(&wallet, 1, deadline).await;
+
+    assert_eq!(
+        ctx.verify_signed_intent(req).unwrap_err(),
+        SignatureError::DeadlineTooFar { deadline, window }
+    );
+}
+
+#[tokio::test]
+async fn signatures_are_bound_to_the_from_chain_vault() {
+    let vault = "0x00000000000000000000000000000000000000aa"
+        .parse()
+        .expect("valid address");
+    let domain = SigningDomain::new().with_vault(ChainId::BASE, vault);
+    let ctx = context().with_signing_domain(domain.clone());
+    let wallet = LocalWallet::new(&mut thread_rng());
+
+    let elsewhere = sign(&wallet, 1, unix_now() + 60).await;
+    assert!(matches!(
+        ctx.verify_signed_intent(elsewhere),
+        Err(SignatureError::SignerMismatch { .. })
+    ));
+
+    let here = sign_in(&domain, &wallet, 2, unix_now() + 60).await;
+    assert!(ctx.verify_signed_intent(here).is_ok());
+}
@@ -0,0 +1,142 @@
+mod common {
+    pub mod context;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::create::create;
+use common::keys::{ADMIN_KEY, authenticator};
+use common::request::request;
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::inventory::{ChainTarget, Inventory};
+use ocean_link_backend::models::{ChainId, IntentStatus};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+fn context(blockchain: &MockBlockchainClient) -> AppContext {
+    common::context::context(blockchain.clone())
+        .with_authenticator(authenticator(RateLimit::default()))
+}
+
+async fn simulate(ctx: &AppContext, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/match/simulate")
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid request");
+    let response = router(ctx.clone())
+        .oneshot(request)
+        .await
+        .expect("infallible");
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("body is JSON"),
+    )
+}
+
+#[tokio::test]
+async fn simulating_the_orderbook_reports_shortfalls_without_touching_state() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 1_000);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 100);
+    let ctx = context(&blockchain);
+    for (user_id, from_chain, to_chain, amount) in [
+        ("A", ChainId::BASE, ChainId::ARBITRUM, 1_000),
+        ("B", ChainId::ARBITRUM, ChainId::BASE, 600),
+    ] {
+        create(&ctx, request(user_id, from_chain, to_chain, amount)).await;
+    }
+
+    let (status, body) = simulate(&ctx, json!({})).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["legs"],
+        json!([
+            { "chain": "Base", "from_user": "A", "to_user": "B", "amount": 600, "would_fail": false },
+            { "chain": "Arbitrum", "from_user": "B", "to_user": "A", "amount": 600, "would_fail": true },
+        ])
+    );
+    assert_eq!(
+        body["balance_deltas"],
+        json!([
+            { "chain": "Base", "user": "A", "before": 1_000, "after": 400, "delta": -600 },
+            { "chain": "Base", "user": "B", "before": 0, "after": 600, "delta": 600 },
+            { "chain": "Arbitrum", "user": "A", "before": 0, "after": 0, "delta": 0 },
+            { "chain": "Arbitrum", "user": "B", "before": 100, "after": 100, "delta": 0 },
+        ])
+    );
+    assert_eq!(body["residuals"][0]["user_id"], "A");
+    assert_eq!(body["residuals"][0]["amount"], 400);
+
+    assert_eq!(blockchain.balance(ChainId::BASE, "A"), 1_000);
+    assert!(blockchain.recorded_txs().is_empty());
+    assert!(ctx.last_solution().await.is_none());
+    assert!(
+        ctx.list_intents()
+            .await
+            .iter()
+            .all(|intent| intent.status == IntentStatus::InOrderbook)
+    );
+}
+
+#[tokio::test]
+async fn simulations_fill_from_the_configured_inventory() {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::ARBITRUM, "solver", 1_000);
+    let target = ChainTarget {
+        target_ratio: 0.5,
+        min_balance: 0,
+    };
+    let ctx = context(&blockchain).with_inventory(
+        Inventory::new("solver", 50)
+            .with_chain(ChainId::BASE, target)
+            .with_chain(ChainId::ARBITRUM, target),
+    );
+    let intents = json!([
+        { "user_id": "A", "from_chain": "Base", "to_chain": "Arbitrum", "amount": 1_000 },
+        { "user_id": "B", "from_chain": "Arbitrum", "to_chain": "Base", "amount": 600 },
+    ]);
+
+    let (status, body) = simulate(&ctx, json!({ "intents": intents })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["solution"]["inventory_fills"][0]["amount"], 400);
+    assert_eq!(body["solution"]["inventory_fills"][0]["fee"], 2);
+    assert!(
+        body["legs"]
+            .as_array()
+            .expect("legs")
+            .iter()
+            .any(|leg| leg["from_user"] == "solver" && leg["amount"] == 398)
+    );
+    assert_eq!(body["residuals"], json!([]));
+}
+
+#[tokio::test]
+async fn supplied_intents_are_validated_per_field() {
+    let ctx = context(&MockBlockchainClient::default());
+    let intent = |from_chain: &str| json!({ "user_id": "A", "from_chain": from_chain, "to_chain": "arbitrum", "amount": 1_000 });
+
+    let (status, body) = simulate(
+        &ctx,
+        json!({ "intents": [intent("base"), intent("solana")] }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_chain");
+    assert_eq!(body["details"]["field"], "intents[1].from_chain");
+}
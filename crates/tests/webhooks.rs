@@ -0,0 +1,395 @@
+mod common {
+    pub mod context_with;
+    pub mod create;
+    pub mod keys;
+    pub mod request;
+}
+
+use async_trait::async_trait;
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use common::create::create;
+use common::keys::{ADMIN_KEY, USER_KEY, authenticator};
+use common::request::request;
+use ocean_link_backend::api::router;
+use ocean_link_backend::app::AppContext;
+use ocean_link_backend::auth::{API_KEY_HEADER, RateLimit, Role};
+use ocean_link_backend::blockchain::MockBlockchainClient;
+use ocean_link_backend::executor::Executor;
+use ocean_link_backend::models::{ChainId, Intent, IntentStatus};
+use ocean_link_backend::risk::{
+    AlwaysPassRiskEngine, MaxIntentSizeRule, RiskEngine, RuleBasedRiskEngine,
+};
+use ocean_link_backend::webhooks::{
+    DELIVERY_HEADER, HttpTransport, RetryPolicy, SIGNATURE_HEADER, WebhookCreated,
+    WebhookDispatcher, WebhookError, WebhookPayload, WebhookTransport, sign,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower::ServiceExt;
+
+const OTHER_KEY: &str = "other-secret";
+
+#[derive(Clone, Debug)]
+struct Attempt {
+    url: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Records every POST and fails the first `failures` attempts per url.
+#[derive(Clone, Default)]
+struct RecordingTransport {
+    attempts: Arc<Mutex<Vec<Attempt>>>,
+    failures: Arc<Mutex<HashMap<String, u32>>>,
+}
+
+impl RecordingTransport {
+    fn fail(&self, url: &str, times: u32) {
+        self.failures.lock().unwrap().insert(url.to_string(), times);
+    }
+
+    fn attempts(&self) -> Vec<Attempt> {
+        self.attempts.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl WebhookTransport for RecordingTransport {
+    async fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<(), String> {
+        self.attempts.lock().unwrap().push(Attempt {
+            url: url.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            body: body.to_vec(),
+        });
+        let mut failures = self.failures.lock().unwrap();
+        match failures.get_mut(url) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                Err("endpoint answered 503 Service Unavailable".into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(4),
+    }
+}
+
+fn context(transport: RecordingTransport, max_attempts: u32) -> AppContext {
+    context_with(
+        Arc::new(AlwaysPassRiskEngine),
+        WebhookDispatcher::new(transport, policy(max_attempts)),
+    )
+}
+
+fn context_with(
+    risk_engine: Arc<dyn RiskEngine + Send + Sync>,
+    webhooks: WebhookDispatcher,
+) -> AppContext {
+    let blockchain = MockBlockchainClient::default();
+    blockchain.set_balance(ChainId::BASE, "A", 100);
+    blockchain.set_balance(ChainId::ARBITRUM, "B", 100);
+    common::context_with::context_with(risk_engine, Executor::new(blockchain))
+        .with_authenticator(authenticator(RateLimit::default()).with_key(OTHER_KEY, Role::User))
+        .with_webhooks(webhooks)
+}
+
+/// Opens a netting pair, with A's intent owned by `owner`, and settles it.
+async fn settle_pair(ctx: &AppContext, owner: &str) -> Intent {
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    let a = create(ctx, request("A", ChainId::BASE, ChainId::ARBITRUM, 100)).await;
+    webhooks.track(a.id, owner);
+    create(ctx, request("B", ChainId::ARBITRUM, ChainId::BASE, 100)).await;
+    ctx.match_and_execute()
+        .await
+        .expect("execution succeeds")
+        .expect("intents net");
+    webhooks.idle().await;
+    a
+}
+
+fn payload(attempt: &Attempt) -> WebhookPayload {
+    serde_json::from_slice(&attempt.body).expect("payload is JSON")
+}
+
+#[tokio::test]
+async fn matched_and_executed_intents_are_delivered_signed() {
+    let transport = RecordingTransport::default();
+    let ctx = context(transport.clone(), 3);
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    let hook = webhooks
+        .register("owner", "https://example.com/hook")
+        .expect("valid url");
+    webhooks
+        .register("someone-else", "https://example.com/other")
+        .expect("valid url");
+
+    let a = settle_pair(&ctx, "owner").await;
+
+    let attempts = transport.attempts();
+    assert!(attempts.iter().all(|a| a.url == hook.url));
+    let mut statuses: Vec<(u64, IntentStatus)> = attempts
+        .iter()
+        .map(|attempt| {
+            let payload = payload(attempt);
+            assert_eq!(payload.intent.id, a.id);
+            assert_eq!(payload.webhook_id, hook.id);
+            assert_eq!(
+                attempt.headers[SIGNATURE_HEADER],
+                sign(&hook.secret, &attempt.body)
+            );
+            assert_eq!(
+                attempt.headers[DELIVERY_HEADER],
+                payload.delivery_id.to_string()
+            );
+            (payload.sequence, payload.intent.status)
+        })
+        .collect();
+    // Deliveries may land in any order; the sequence restores it.
+    statuses.sort_by_key(|(sequence, _)| *sequence);
+    assert_eq!(
+        statuses,
+        [(1, IntentStatus::Matched), (2, IntentStatus::Executed)]
+    );
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_with_the_same_delivery_id() {
+    let transport = RecordingTransport::default();
+    transport.fail("https://example.com/flaky", 2);
+    let ctx = context(transport.clone(), 5);
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    webhooks
+        .register("owner", "https://example.com/flaky")
+        .expect("valid url");
+
+    settle_pair(&ctx, "owner").await;
+
+    let attempts = transport.attempts();
+    // Two failures, then one success for each of Matched and Executed.
+    assert_eq!(attempts.len(), 4);
+    let mut ids: Vec<_> = attempts.iter().map(|a| payload(a).delivery_id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 2);
+    assert!(webhooks.dead_letters().is_empty());
+}
+
+#[tokio::test]
+async fn exhausted_deliveries_land_in_the_dead_letter_queue() {
+    let transport = RecordingTransport::default();
+    transport.fail("https://example.com/down", u32::MAX);
+    let ctx = context(transport.clone(), 3);
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    webhooks
+        .register("owner", "https://example.com/down")
+        .expect("valid url");
+
+    let a = settle_pair(&ctx, "owner").await;
+
+    assert_eq!(transport.attempts().len(), 6);
+    let dead = webhooks.dead_letters();
+    assert_eq!(dead.len(), 2);
+    for letter in dead {
+        assert_eq!(letter.attempts, 3);
+        assert_eq!(letter.payload.intent.id, a.id);
+        assert!(letter.last_error.contains("503"));
+    }
+}
+
+#[tokio::test]
+async fn the_dead_letter_queue_is_capped() {
+    let transport = RecordingTransport::default();
+    transport.fail("https://example.com/down", u32::MAX);
+    let webhooks = WebhookDispatcher::new(transport.clone(), policy(1)).with_dead_letter_limit(1);
+    let ctx = context_with(Arc::new(AlwaysPassRiskEngine), webhooks);
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    webhooks
+        .register("owner", "https://example.com/down")
+        .expect("valid url");
+
+    settle_pair(&ctx, "owner").await;
+
+    assert_eq!(transport.attempts().len(), 2);
+    assert_eq!(webhooks.dead_letters().len(), 1);
+}
+
+#[tokio::test]
+async fn rejected_intents_are_delivered_and_forgotten() {
+    let transport = RecordingTransport::default();
+    let engine = RuleBasedRiskEngine::new().with_rule(MaxIntentSizeRule::new(50));
+    let ctx = context_with(
+        Arc::new(engine),
+        WebhookDispatcher::new(transport.clone(), policy(1)),
+    );
+    let key = ctx.auth.keys.authenticate(USER_KEY).expect("key exists");
+    let webhooks = ctx.webhooks().expect("webhooks enabled");
+    webhooks
+        .register(&key.id, "https://example.com/hook")
+        .expect("valid url");
+
+    let request = request("A", ChainId::BASE, ChainId::ARBITRUM, 100);
+    let intent = ctx.submit_intent(&key, request).await;
+    ctx.wait_for_risk(intent.id).await.expect("intent exists");
+    webhooks.idle().await;
+
+    let statuses: Vec<IntentStatus> = transport
+        .attempts()
+        .iter()
+        .map(|attempt| payload(attempt).intent.status)
+        .collect();
+    assert!(
+        matches!(statuses.as_slice(), [IntentStatus::Rejected { .. }]),
+        "{statuses:?}"
+    );
+    assert_eq!(
+        webhooks.tracked(),
+        0,
+        "terminal intents are no longer tracked"
+    );
+}
+
+#[test]
+fn local_and_private_hosts_are_refused() {
+    let webhooks = WebhookDispatcher::new(RecordingTransport::default(), policy(1));
+    for url in [
+        "http://127.0.0.1/hook",
+        "http://2130706433/hook",
+        "http://localhost:8080/hook",
+        "http://api.localhost/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.5/hook",
+        "http://172.16.0.1/hook",
+        "http://192.168.1.1/hook",
+        "http://100.64.0.1/hook",
+        "http://0.0.0.0/hook",
+        "http://[::1]/hook",
+        "http://[fe80::1]/hook",
+        "http://[fd00::1]/hook",
+        "http://[::ffff:127.0.0.1]/hook",
+    ] {
+        assert_eq!(
+            webhooks.register("owner", url).map(|_| ()),
+            Err(WebhookError::PrivateHost(url.to_string())),
+            "{url}"
+        );
+    }
+    for url in ["https://example.com/hook", "http://8.8.8.8/hook"] {
+        assert!(webhooks.register("owner", url).is_ok(), "{url}");
+    }
+}
+
+#[tokio::test]
+async fn deliveries_never_resolve_to_private_addresses() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let port = listener.local_addr().expect("bound").port();
+
+    let transport = HttpTransport::new(Duration::from_secs(5));
+    let url = format!("http://localhost:{port}/hook");
+    let result = transport.post(&url, &[], b"{}").await;
+
+    assert!(result.is_err(), "{result:?}");
+    let accepted = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+    assert!(accepted.is_err(), "no connection reached the listener");
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+    };
+    let delays: Vec<u128> = (1..=5).map(|n| policy.backoff(n).as_millis()).collect();
+    assert_eq!(delays, [100, 200, 400, 500, 500]);
+}
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    key: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(API_KEY_HEADER, key);
+    let body = match body {
+        Some(body) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).expect("valid request"))
+        .await
+        .expect("infallible");
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body is readable");
+    let value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, value)
+}
+
+#[tokio::test]
+async fn registrations_are_scoped_to_the_api_key() {
+    let app = router(context(RecordingTransport::default(), 1));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/webhooks",
+        USER_KEY,
+        Some(json!({ "url": "ftp://example.com" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/webhooks",
+        USER_KEY,
+        Some(json!({ "url": "https://example.com/hook" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let created: WebhookCreated = serde_json::from_value(body).expect("registration body");
+    assert!(created.secret.starts_with("whsec_"));
+
+    let (_, mine) = send(&app, "GET", "/webhooks", USER_KEY, None).await;
+    assert_eq!(mine.as_array().map(Vec::len), Some(1));
+    assert!(mine[0].get("secret").is_none());
+    let (_, theirs) = send(&app, "GET", "/webhooks", OTHER_KEY, None).await;
+    assert_eq!(theirs, json!([]));
+
+    let uri = format!("/webhooks/{}", created.id);
+    let (status, _) = send(&app, "DELETE", &uri, OTHER_KEY, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, "DELETE", &uri, USER_KEY, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = send(&app, "GET", "/admin/webhooks/dead-letters", USER_KEY, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = send(&app, "GET", "/admin/webhooks/dead-letters", ADMIN_KEY, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!([]));
+}
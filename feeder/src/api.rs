@@ -1,11 +1,21 @@
+use crate::auth::{require_admin, require_user, API_KEY_HEADER};
 use crate::balances::{self, BalanceSnapshot};
-use crate::blockchain::SharedBlockchainClient;
+use crate::blockchain::{SharedBlockchainClient, SimulationError};
+use crate::error::{
+    parse_chain, require_distinct_chains, require_positive_amount, ApiError, ApiJson, ErrorCode,
+};
+use crate::health::{self, Check, Readiness};
 use crate::matching::{match_a_against_makers, plan_for_chain};
-use crate::models::{Chain, Intent, IntentKind, TransferPlanEntry, USER_A};
-use crate::orderbook::{add_intent, AppState, SharedState};
+use crate::models::{
+    Amount, ChainId, Intent, IntentKind, IntentStatus, Leg, SETTLEMENT_CHAIN, TAKER_CHAIN, USER_A,
+};
+use crate::orderbook::MAKERS;
+use crate::orderbook::{add_intent, orderbook_snapshot, SharedState};
+use crate::signing::{parse_address, unix_now, verify_signature, IntentMessage};
 use axum::{
     extract::State,
-    http::StatusCode,
+    http::{header, StatusCode},
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
@@ -13,77 +23,168 @@ use axum::{
 
 type ApiState = (SharedState, SharedBlockchainClient);
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
-use tokio::sync::MutexGuard;
+use serde_json::json;
+use tower_http::trace::TraceLayer;
+use tracing::{info, info_span, Instrument};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize)]
-struct DepositRequest {
-    user: String,
-    chain: String,
-    amount: u64,
-    recipient_on_other_chain: Option<String>,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DepositRequest {
+    pub user: String,
+    #[schema(example = "sepolia")]
+    pub chain: String,
+    pub amount: u64,
+    pub recipient_on_other_chain: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DepositResponse {
     pub user: String,
-    pub chain: Chain,
+    pub chain: ChainId,
     pub amount: u64,
     pub recipient_on_other_chain: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct OrderRequest {
     pub user: String,
+    #[schema(example = "sepolia")]
     pub from_chain: String,
+    #[schema(example = "base-sepolia")]
     pub to_chain: String,
     pub amount: u64,
+    pub nonce: u64,
+    /// Unix timestamp in seconds after which the signature is void, at most
+    /// an hour ahead.
+    pub deadline: u64,
+    /// EIP-712 signature by `user` over the order fields.
+    pub signature: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OrderResponse {
     pub intent_id: Uuid,
     pub transfers: Vec<TransferReceipt>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct MatchResponse {
-    pub solution: Vec<TransferPlanEntry>,
+    pub solution: Vec<Leg>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TransferReceipt {
-    pub chain: Chain,
+    pub chain: ChainId,
     pub from: String,
     pub to: String,
-    pub amount: u64,
+    pub amount: Amount,
     pub tx_hash: String,
 }
 
+/// OpenAPI description of [`router`], served at `/openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "OceanLink feeder"),
+    paths(
+        create_order,
+        list_orderbook,
+        list_balances,
+        deposit,
+        run_matching,
+        send_leg,
+        metrics,
+        healthz,
+        readyz
+    ),
+    components(schemas(
+        ApiError,
+        BalanceSnapshot,
+        ChainId,
+        Check,
+        DepositRequest,
+        DepositResponse,
+        ErrorCode,
+        Intent,
+        IntentKind,
+        MatchResponse,
+        OrderRequest,
+        OrderResponse,
+        Readiness,
+        Leg,
+        TransferReceipt
+    )),
+    modifiers(&ApiKeyAuth),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
+
+/// Every route except `/openapi.json`, `/healthz` and `/readyz` needs an API
+/// key. `/deposit` mints balances, `/match` runs the engine, `/legs` spends
+/// maker funds and `/metrics` exposes internals, so those are admin-only.
 pub fn router(state: SharedState, blockchain: SharedBlockchainClient) -> Router {
-    Router::new()
-        .route("/deposit", post(deposit))
+    let user_routes = Router::new()
         .route("/order", post(create_order))
-        .route("/match", post(run_matching))
         .route("/orderbook", get(list_orderbook))
         .route("/balances", get(list_balances))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_user));
+
+    let admin_routes = Router::new()
+        .route("/deposit", post(deposit))
+        .route("/match", post(run_matching))
+        .route("/legs", post(send_leg))
+        .route("/metrics", get(metrics))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_admin));
+
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .merge(user_routes)
+        .merge(admin_routes)
+        .layer(TraceLayer::new_for_http())
         .with_state((state, blockchain))
 }
 
-fn parse_chain(value: &str) -> Result<Chain, String> {
-    Chain::from_str(value)
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
 }
 
+#[utoipa::path(
+    post,
+    path = "/deposit",
+    request_body = DepositRequest,
+    responses(
+        (status = 200, description = "Balance credited", body = DepositResponse),
+        (status = 400, description = "Invalid chain or amount", body = ApiError),
+        (status = 403, description = "Admin key required", body = ApiError)
+    )
+)]
 async fn deposit(
     State((state, _)): State<ApiState>,
-    Json(payload): Json<DepositRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let chain = parse_chain(&payload.chain).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
+    ApiJson(payload): ApiJson<DepositRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let chain = parse_chain("chain", &payload.chain)?;
+    require_positive_amount("amount", payload.amount)?;
     let recipient = payload.recipient_on_other_chain.clone();
 
-    let mut guard = state.lock().await;
-    balances::mint(&mut guard.balances, chain, &payload.user, payload.amount);
+    {
+        let mut guard = state.balances.lock().expect("balances lock poisoned");
+        balances::mint(&mut guard, chain, &payload.user, payload.amount);
+    }
 
     Ok((
         StatusCode::OK,
@@ -96,70 +197,108 @@ async fn deposit(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/order",
+    request_body = OrderRequest,
+    responses(
+        (status = 201, description = "Order placed and settled", body = OrderResponse),
+        (status = 400, description = "Invalid chain, amount or route", body = ApiError),
+        (status = 401, description = "Missing API key or bad signature", body = ApiError),
+        (status = 409, description = "Nonce already used", body = ApiError),
+        (status = 422, description = "A settlement transfer would revert", body = ApiError),
+        (status = 502, description = "A settlement transfer failed", body = ApiError)
+    )
+)]
 async fn create_order(
     State((state, blockchain)): State<ApiState>,
-    Json(payload): Json<OrderRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let from_chain =
-        parse_chain(&payload.from_chain).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
-    let to_chain = parse_chain(&payload.to_chain).map_err(|err| (StatusCode::BAD_REQUEST, err))?;
-
-    if payload.user != USER_A {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "only the configured taker address may place orders in this demo".into(),
+    ApiJson(payload): ApiJson<OrderRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let from_chain = parse_chain("from_chain", &payload.from_chain)?;
+    let to_chain = parse_chain("to_chain", &payload.to_chain)?;
+    require_distinct_chains(from_chain, to_chain)?;
+    require_positive_amount("amount", payload.amount)?;
+
+    let message = IntentMessage {
+        user: parse_address(&payload.user)?,
+        from_chain,
+        to_chain,
+        amount: payload.amount,
+        nonce: payload.nonce,
+        deadline: payload.deadline,
+        verifying_contract: state.signing.verifying_contract(from_chain),
+    };
+    let now = unix_now();
+    let signer = verify_signature(&message, &payload.signature, now)?;
+    let user = format!("{signer:#x}");
+
+    if user != USER_A {
+        return Err(ApiError::new(
+            ErrorCode::Forbidden,
+            "only the configured taker address may place orders in this demo",
         ));
     }
 
     if payload.amount < 1_000_000 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "minimum taker amount is 1,000,000 USDC for this demo".into(),
-        ));
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            "minimum taker amount is 1,000,000 USDC for this demo",
+        )
+        .with_details(json!({ "field": "amount", "min": 1_000_000 })));
     }
 
-    if from_chain != Chain::Sepolia || to_chain != Chain::Base {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "taker orders must be from Sepolia to Base in this demo".into(),
+    if from_chain != TAKER_CHAIN || to_chain != SETTLEMENT_CHAIN {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "taker orders must be from Sepolia to Base Sepolia in this demo",
         ));
     }
 
     let intent = Intent {
         id: Uuid::new_v4(),
-        user: payload.user.clone(),
+        user_id: user,
         from_chain,
         to_chain,
-        amount: payload.amount,
+        amount: payload.amount.into(),
         kind: IntentKind::Taker,
+        status: IntentStatus::InOrderbook,
     };
 
-    let intent_id = intent.id;
-    {
-        let mut guard = state.lock().await;
-        add_intent(&mut guard, intent);
-    }
-
     let settlement_plan = plan_for_chain(to_chain);
     if settlement_plan.is_empty() {
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "no settlement plan defined for target chain".into(),
+        return Err(ApiError::new(
+            ErrorCode::Internal,
+            "no settlement plan defined for target chain",
         ));
     }
+    simulate_legs(&blockchain, &settlement_plan).await?;
+    // Spent only once every check has passed, so a refused order leaves its
+    // nonce usable.
+    state
+        .nonces
+        .consume(signer, payload.nonce, payload.deadline, now)?;
+
+    let intent_id = intent.id;
+    add_intent(&state, intent);
+    info!(%intent_id, amount = payload.amount, "taker order accepted");
 
-    // Send real blockchain transactions from B, C, D to A on the to_chain (Base)
+    // Send real blockchain transactions from B, C, D to A on the to_chain (Base Sepolia)
     let mut receipts = Vec::new();
-    for entry in settlement_plan {
+    for (leg, entry) in settlement_plan.into_iter().enumerate() {
+        let span = info_span!("leg", %intent_id, leg, chain = %entry.chain);
         let tx_hash = blockchain
-            .send_erc20_transfer(&entry.from, &entry.to, entry.amount)
+            .send_erc20_transfer(&entry.from_user, &entry.to_user, entry.amount.get())
+            .instrument(span)
             .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
-        
+            .map_err(|e| {
+                state.metrics.record_transfer_failure(entry.chain);
+                ApiError::new(ErrorCode::TransferFailed, e)
+            })?;
+
         receipts.push(TransferReceipt {
             chain: entry.chain,
-            from: entry.from,
-            to: entry.to,
+            from: entry.from_user,
+            to: entry.to_user,
             amount: entry.amount,
             tx_hash,
         });
@@ -174,26 +313,716 @@ async fn create_order(
     ))
 }
 
-async fn run_matching(
-    State((state, _)): State<ApiState>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let guard = state.lock().await;
-    let solution = match_a_against_makers(&guard.orderbook).ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            "insufficient taker liquidity from user A (need 1,000,000 USDC)".to_string(),
+/// Simulates every leg against the latest block before any is sent, so a
+/// plan with a leg that would revert costs no gas. Legs are simulated on
+/// their own, which holds as long as no sender pays twice in one plan.
+async fn simulate_legs(blockchain: &SharedBlockchainClient, legs: &[Leg]) -> Result<(), ApiError> {
+    for (index, leg) in legs.iter().enumerate() {
+        let span = info_span!("simulate_leg", leg = index, chain = %leg.chain);
+        blockchain
+            .simulate_erc20_transfer(&leg.from_user, &leg.to_user, leg.amount.get())
+            .instrument(span)
+            .await
+            .map_err(|err| match err {
+                SimulationError::Reverted(reason) => ApiError::new(
+                    ErrorCode::TransferWouldRevert,
+                    format!("leg {index} would revert: {reason}"),
+                )
+                .with_details(json!({
+                    "leg": index,
+                    "from": leg.from_user,
+                    "to": leg.to_user,
+                    "amount": leg.amount,
+                    "reason": reason,
+                })),
+                SimulationError::Failed(err) => ApiError::new(ErrorCode::TransferFailed, err),
+            })?;
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/match",
+    responses(
+        (status = 200, description = "Transfers that settle the orderbook", body = MatchResponse),
+        (status = 409, description = "Not enough taker liquidity", body = ApiError)
+    )
+)]
+async fn run_matching(State((state, _)): State<ApiState>) -> Result<impl IntoResponse, ApiError> {
+    let orderbook = orderbook_snapshot(&state);
+    let solution = match_a_against_makers(&orderbook).ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::Conflict,
+            "insufficient taker liquidity from user A (need 1,000,000 USDC)",
         )
     })?;
     Ok((StatusCode::OK, Json(MatchResponse { solution })))
 }
 
+/// Signs and sends one leg of a backend netting solution from a maker's
+/// key. The backend's executor calls this for every leg its makers pay.
+#[utoipa::path(
+    post,
+    path = "/legs",
+    request_body = Leg,
+    responses(
+        (status = 200, description = "Leg sent", body = TransferReceipt),
+        (status = 400, description = "Leg is not on the settlement chain", body = ApiError),
+        (status = 403, description = "Sender is not one of our makers", body = ApiError),
+        (status = 422, description = "The transfer would revert", body = ApiError),
+        (status = 502, description = "The transfer failed", body = ApiError)
+    )
+)]
+async fn send_leg(
+    State((state, blockchain)): State<ApiState>,
+    ApiJson(leg): ApiJson<Leg>,
+) -> Result<impl IntoResponse, ApiError> {
+    if leg.chain != SETTLEMENT_CHAIN {
+        return Err(ApiError::new(
+            ErrorCode::InvalidChain,
+            format!("makers only pay on {SETTLEMENT_CHAIN}, not {}", leg.chain),
+        )
+        .with_details(json!({ "field": "chain", "value": leg.chain })));
+    }
+    if !MAKERS
+        .iter()
+        .any(|(maker, _)| leg.from_user.eq_ignore_ascii_case(maker))
+    {
+        return Err(ApiError::new(
+            ErrorCode::Forbidden,
+            format!("no maker key for {}", leg.from_user),
+        ));
+    }
+    require_positive_amount("amount", leg.amount.get())?;
+    simulate_legs(&blockchain, std::slice::from_ref(&leg)).await?;
+
+    let span = info_span!("leg", chain = %leg.chain, from = %leg.from_user, to = %leg.to_user);
+    let tx_hash = blockchain
+        .send_erc20_transfer(&leg.from_user, &leg.to_user, leg.amount.get())
+        .instrument(span)
+        .await
+        .map_err(|e| {
+            state.metrics.record_transfer_failure(leg.chain);
+            ApiError::new(ErrorCode::TransferFailed, e)
+        })?;
+    info!(%tx_hash, "maker leg sent");
+
+    Ok(Json(TransferReceipt {
+        chain: leg.chain,
+        from: leg.from_user,
+        to: leg.to_user,
+        amount: leg.amount,
+        tx_hash,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/orderbook",
+    responses((status = 200, description = "Open intents", body = Vec<Intent>))
+)]
 async fn list_orderbook(State((state, _)): State<ApiState>) -> impl IntoResponse {
-    let guard = state.lock().await;
-    Json(guard.orderbook.clone())
+    Json(orderbook_snapshot(&state))
 }
 
+#[utoipa::path(
+    get,
+    path = "/balances",
+    responses((status = 200, description = "Balances per chain and user", body = Vec<BalanceSnapshot>))
+)]
 async fn list_balances(State((state, _)): State<ApiState>) -> impl IntoResponse {
-    let guard: MutexGuard<'_, AppState> = state.lock().await;
-    let snapshot: Vec<BalanceSnapshot> = balances::snapshot(&guard.balances);
+    let snapshot: Vec<BalanceSnapshot> = {
+        let guard = state.balances.lock().expect("balances lock poisoned");
+        balances::snapshot(&guard)
+    };
     Json(snapshot)
 }
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain"),
+        (status = 403, description = "Admin key required", body = ApiError)
+    )
+)]
+async fn metrics(State((state, _)): State<ApiState>) -> impl IntoResponse {
+    let body = state.metrics.render(&orderbook_snapshot(&state));
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+/// Liveness: answers as long as the process serves requests.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    security(()),
+    responses((status = 200, description = "Process is alive"))
+)]
+async fn healthz() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Readiness: checks the store, every configured chain's RPC endpoint and
+/// chain id, and the maker signing keys. Failures only carry a generic
+/// reason; the details are logged.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    security(()),
+    responses(
+        (status = 200, description = "Ready for traffic", body = Readiness),
+        (status = 503, description = "A dependency check failed", body = Readiness)
+    )
+)]
+async fn readyz(State((state, blockchain)): State<ApiState>) -> (StatusCode, Json<Readiness>) {
+    let readiness = health::readiness(&state, &blockchain).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{Authenticator, RateLimit, Role};
+    use crate::blockchain::BlockchainClient;
+    use crate::orderbook::init_state;
+    use crate::signing::{SigningDomain, MAX_DEADLINE_WINDOW};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request};
+    use ethers::abi::AbiEncode;
+    use ethers::contract::EthError;
+    use ethers::core::rand::thread_rng;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::Address;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    const ADMIN_KEY: &str = "admin-secret";
+    const USER_KEY: &str = "user-secret";
+    const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+    // A fresh router per request keeps the rate limit out of the way.
+    // The blockchain client only connects when a transfer is sent.
+    fn app() -> Router {
+        app_on("http://127.0.0.1:0")
+    }
+
+    fn app_on(rpc: &str) -> Router {
+        app_with(state(SigningDomain::new()), rpc)
+    }
+
+    fn state(signing: SigningDomain) -> SharedState {
+        state_limited(signing, RateLimit::default())
+    }
+
+    fn state_limited(signing: SigningDomain, limit: RateLimit) -> SharedState {
+        let auth = Authenticator::new(limit)
+            .with_key(ADMIN_KEY, Role::Admin)
+            .with_key(USER_KEY, Role::User);
+        init_state(auth, signing)
+    }
+
+    fn app_with(state: SharedState, rpc: &str) -> Router {
+        let blockchain = Arc::new(BlockchainClient::new(
+            rpc.into(),
+            Address::zero(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ));
+        router(state, blockchain)
+    }
+
+    /// A taker order from `wallet`, signed in `domain`.
+    async fn signed_order(
+        wallet: &LocalWallet,
+        domain: &SigningDomain,
+        nonce: u64,
+        deadline: u64,
+    ) -> Value {
+        let message = IntentMessage {
+            user: wallet.address(),
+            from_chain: TAKER_CHAIN,
+            to_chain: SETTLEMENT_CHAIN,
+            amount: 1_000_000,
+            nonce,
+            deadline,
+            verifying_contract: domain.verifying_contract(TAKER_CHAIN),
+        };
+        let signature = wallet
+            .sign_typed_data(&message)
+            .await
+            .expect("signing succeeds");
+        json!({
+            "user": format!("{:#x}", wallet.address()),
+            "from_chain": "sepolia",
+            "to_chain": "base-sepolia",
+            "amount": message.amount,
+            "nonce": nonce,
+            "deadline": deadline,
+            "signature": signature.to_string(),
+        })
+    }
+
+    async fn place_order(app: Router, order: &Value) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/order")
+            .header(API_KEY_HEADER, ADMIN_KEY)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(order.to_string()))
+            .expect("valid request");
+        let response = app.oneshot(req).await.expect("infallible");
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        (
+            status,
+            serde_json::from_slice(&body).expect("error is JSON"),
+        )
+    }
+
+    async fn call(method: &str, uri: &str) -> (StatusCode, Vec<u8>) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(API_KEY_HEADER, ADMIN_KEY)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{}"))
+            .expect("valid request");
+        let response = app().oneshot(req).await.expect("infallible");
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        (status, body.to_vec())
+    }
+
+    async fn get_as(app: Router, uri: &str, key: Option<&str>) -> axum::response::Response {
+        let mut req = Request::builder().uri(uri);
+        if let Some(key) = key {
+            req = req.header(API_KEY_HEADER, key);
+        }
+        app.oneshot(req.body(Body::empty()).expect("valid request"))
+            .await
+            .expect("infallible")
+    }
+
+    fn documented_operations() -> Vec<(String, Vec<&'static str>)> {
+        let spec = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+        spec["paths"]
+            .as_object()
+            .expect("spec has paths")
+            .iter()
+            .map(|(path, item)| {
+                let methods = METHODS
+                    .into_iter()
+                    .filter(|method| item.get(method.to_lowercase()).is_some())
+                    .collect();
+                (path.clone(), methods)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn spec_is_served_without_a_key() {
+        let req = Request::builder()
+            .uri("/openapi.json")
+            .body(Body::empty())
+            .expect("valid request");
+        let response = app().oneshot(req).await.expect("infallible");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        let served: Value = serde_json::from_slice(&body).expect("spec is JSON");
+        let expected = serde_json::to_value(ApiDoc::openapi()).expect("spec serializes");
+        assert_eq!(served, expected);
+    }
+
+    #[tokio::test]
+    async fn routes_match_the_spec() {
+        for (path, documented) in documented_operations() {
+            for method in METHODS {
+                let (status, body) = call(method, &path).await;
+                let unrouted = status == StatusCode::METHOD_NOT_ALLOWED
+                    || (status == StatusCode::NOT_FOUND && body.is_empty());
+                if documented.contains(&method) {
+                    assert!(!unrouted, "{method} {path} is documented but not routed");
+                } else {
+                    assert_eq!(
+                        status,
+                        StatusCode::METHOD_NOT_ALLOWED,
+                        "{method} {path} is routed but not documented"
+                    );
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn keys_are_required_and_admin_routes_refuse_user_keys() {
+        let app = app();
+        let status = |response: axum::response::Response| response.status();
+        assert_eq!(
+            status(get_as(app.clone(), "/orderbook", None).await),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(get_as(app.clone(), "/orderbook", Some("wrong-key")).await),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(get_as(app.clone(), "/orderbook", Some(USER_KEY)).await),
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(get_as(app.clone(), "/metrics", Some(USER_KEY)).await),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(get_as(app.clone(), "/metrics", Some(ADMIN_KEY)).await),
+            StatusCode::OK
+        );
+
+        let bearer = Request::builder()
+            .uri("/orderbook")
+            .header(header::AUTHORIZATION, format!("Bearer {USER_KEY}"))
+            .body(Body::empty())
+            .expect("valid request");
+        let response = app.oneshot(bearer).await.expect("infallible");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn each_key_has_its_own_configured_rate_limit() {
+        let limit = RateLimit {
+            per_second: 1.0,
+            burst: 2,
+        };
+        let app = app_with(
+            state_limited(SigningDomain::new(), limit),
+            "http://127.0.0.1:0",
+        );
+        for _ in 0..2 {
+            let response = get_as(app.clone(), "/orderbook", Some(USER_KEY)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = get_as(app.clone(), "/orderbook", Some(USER_KEY)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        let error: Value = serde_json::from_slice(&body).expect("error is JSON");
+        assert_eq!(error["code"], "rate_limited");
+        assert_eq!(error["details"]["retry_after_secs"], 1);
+
+        let response = get_as(app, "/orderbook", Some(ADMIN_KEY)).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::OK,
+            "other keys are unaffected"
+        );
+    }
+
+    #[tokio::test]
+    async fn legs_are_only_sent_for_our_makers_on_the_settlement_chain() {
+        let send = |chain: ChainId, from: &str| {
+            let leg = json!({ "chain": chain, "from_user": from, "to_user": USER_A, "amount": 1 });
+            Request::builder()
+                .method("POST")
+                .uri("/legs")
+                .header(API_KEY_HEADER, ADMIN_KEY)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(leg.to_string()))
+                .expect("valid request")
+        };
+
+        let response = app()
+            .oneshot(send(TAKER_CHAIN, crate::models::USER_B))
+            .await
+            .expect("infallible");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app()
+            .oneshot(send(SETTLEMENT_CHAIN, USER_A))
+            .await
+            .expect("infallible");
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // A maker leg reaches the RPC, which nothing serves here.
+        let response = app()
+            .oneshot(send(SETTLEMENT_CHAIN, crate::models::USER_B))
+            .await
+            .expect("infallible");
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn legs_that_would_revert_are_refused_with_the_reason() {
+        // An RPC node on which every call reverts with an ERC20 message.
+        let reason = "ERC20: transfer amount exceeds balance";
+        let data = [
+            &<String as EthError>::selector()[..],
+            &reason.to_string().encode(),
+        ]
+        .concat();
+        let data = format!("0x{}", hex::encode(data));
+        let rpc = Router::new().route(
+            "/",
+            post(move |Json(req): Json<Value>| async move {
+                Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": req["id"],
+                    "error": { "code": 3, "message": "execution reverted", "data": data },
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("port is free");
+        let addr = listener.local_addr().expect("bound");
+        tokio::spawn(async move { axum::serve(listener, rpc).await });
+
+        let leg = json!({
+            "chain": SETTLEMENT_CHAIN,
+            "from_user": crate::models::USER_B,
+            "to_user": USER_A,
+            "amount": 1,
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/legs")
+            .header(API_KEY_HEADER, ADMIN_KEY)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(leg.to_string()))
+            .expect("valid request");
+        let response = app_on(&format!("http://{addr}"))
+            .oneshot(req)
+            .await
+            .expect("infallible");
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        let body: Value = serde_json::from_slice(&body).expect("error is JSON");
+        assert_eq!(body["code"], "transfer_would_revert");
+        assert_eq!(body["details"]["leg"], 0);
+        assert_eq!(body["details"]["reason"], reason);
+    }
+
+    #[tokio::test]
+    async fn refused_orders_leave_the_nonce_unspent() {
+        let state = state(SigningDomain::new());
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let deadline = unix_now() + 60;
+        let order = signed_order(&wallet, &SigningDomain::new(), 7, deadline).await;
+
+        // Correctly signed, but not by the demo's taker.
+        let (status, body) =
+            place_order(app_with(state.clone(), "http://127.0.0.1:0"), &order).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+
+        state
+            .nonces
+            .consume(wallet.address(), 7, deadline, unix_now())
+            .expect("nonce is still unspent");
+    }
+
+    #[tokio::test]
+    async fn order_signatures_are_checked_before_anything_else() {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let mut order = signed_order(&wallet, &SigningDomain::new(), 1, unix_now() + 60).await;
+        order["amount"] = json!(2_000_000);
+
+        let (status, body) = place_order(app(), &order).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], "invalid_signature");
+    }
+
+    #[tokio::test]
+    async fn orders_must_change_chain() {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let mut order = signed_order(&wallet, &SigningDomain::new(), 1, unix_now() + 60).await;
+        order["to_chain"] = order["from_chain"].clone();
+
+        let (status, body) = place_order(app(), &order).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_request");
+        assert_eq!(body["details"]["field"], "to_chain");
+    }
+
+    #[tokio::test]
+    async fn order_deadlines_are_capped() {
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let deadline = unix_now() + MAX_DEADLINE_WINDOW.as_secs() + 60;
+        let order = signed_order(&wallet, &SigningDomain::new(), 1, deadline).await;
+
+        let (status, body) = place_order(app(), &order).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_request");
+
+        let expired = signed_order(&wallet, &SigningDomain::new(), 2, unix_now() - 1).await;
+        let (status, body) = place_order(app(), &expired).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "signature_expired");
+    }
+
+    #[tokio::test]
+    async fn order_signatures_are_bound_to_the_vault() {
+        let vault = "0x00000000000000000000000000000000000000aa"
+            .parse()
+            .expect("valid address");
+        let domain = SigningDomain::new().with_vault(TAKER_CHAIN, vault);
+        let wallet = LocalWallet::new(&mut thread_rng());
+
+        let elsewhere = signed_order(&wallet, &SigningDomain::new(), 1, unix_now() + 60).await;
+        let app = app_with(state(domain.clone()), "http://127.0.0.1:0");
+        let (status, _) = place_order(app.clone(), &elsewhere).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Signed in the right domain, it gets as far as the taker check.
+        let here = signed_order(&wallet, &domain, 2, unix_now() + 60).await;
+        let (status, _) = place_order(app, &here).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn metrics_report_depth_and_rpc_latency() {
+        let state = state(SigningDomain::new());
+        let blockchain = BlockchainClient::new(
+            "http://127.0.0.1:0".into(),
+            Address::zero(),
+            String::new(),
+            String::new(),
+            String::new(),
+        )
+        .with_metrics(state.metrics.clone());
+        blockchain
+            .send_erc20_transfer(crate::models::USER_B, crate::models::USER_A, 1)
+            .await
+            .expect_err("nothing listens on port 0");
+        state.metrics.record_transfer_failure(SETTLEMENT_CHAIN);
+
+        let body = state.metrics.render(&orderbook_snapshot(&state));
+        assert!(
+            body.contains(
+                r#"feeder_orderbook_intents{from_chain="Base Sepolia",to_chain="Sepolia"} 3"#
+            ),
+            "{body}"
+        );
+        assert!(body.contains(
+            r#"feeder_orderbook_notional{from_chain="Base Sepolia",to_chain="Sepolia"} 1000000"#
+        ));
+        assert!(body.contains(
+            r#"feeder_rpc_duration_seconds_count{method="send_erc20_transfer",outcome="error"} 1"#
+        ));
+        assert!(body.contains(r#"feeder_transfer_failures_total{chain="Base Sepolia"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn readiness_reports_unreachable_rpc_and_missing_keys() {
+        let req = Request::builder()
+            .uri("/healthz")
+            .body(Body::empty())
+            .expect("valid request");
+        let response = app().oneshot(req).await.expect("infallible");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .uri("/readyz")
+            .body(Body::empty())
+            .expect("valid request");
+        let response = app().oneshot(req).await.expect("infallible");
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        let body: Value = serde_json::from_slice(&body).expect("readiness is JSON");
+        assert_eq!(body["ready"], false);
+
+        let checks = body["checks"].as_array().expect("checks array");
+        let status_of = |name: &str| -> Vec<bool> {
+            checks
+                .iter()
+                .filter(|check| check["name"] == name)
+                .map(|check| check["ok"].as_bool().expect("ok flag"))
+                .collect()
+        };
+        assert_eq!(status_of("store"), [true]);
+        assert_eq!(status_of("rpc"), [false]);
+        assert_eq!(status_of("signer"), [false, false, false]);
+    }
+
+    #[tokio::test]
+    async fn readiness_probes_every_chain_without_leaking_urls() {
+        // The settlement chain's node answers with its chain id; nothing
+        // listens on the taker chain's endpoint.
+        let chain_id = format!("{:#x}", SETTLEMENT_CHAIN.as_u64());
+        let rpc = Router::new().route(
+            "/",
+            post(move |Json(req): Json<Value>| async move {
+                Json(json!({ "jsonrpc": "2.0", "id": req["id"], "result": chain_id }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("port is free");
+        let addr = listener.local_addr().expect("bound");
+        tokio::spawn(async move { axum::serve(listener, rpc).await });
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("port is free")
+            .local_addr()
+            .expect("bound");
+        let blockchain = BlockchainClient::new(
+            format!("http://{addr}"),
+            Address::zero(),
+            String::new(),
+            String::new(),
+            String::new(),
+        )
+        .with_rpc_url(TAKER_CHAIN, format!("http://{closed}/v2/secret-key"));
+        let app = router(state(SigningDomain::new()), Arc::new(blockchain));
+
+        let req = Request::builder()
+            .uri("/readyz")
+            .body(Body::empty())
+            .expect("valid request");
+        let response = app.oneshot(req).await.expect("infallible");
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body is readable");
+        let body: Value = serde_json::from_slice(&body).expect("readiness is JSON");
+        assert!(!body.to_string().contains("secret-key"), "{body}");
+
+        let checks = body["checks"].as_array().expect("checks array");
+        let rpc_on = |chain: ChainId| {
+            checks
+                .iter()
+                .find(|check| check["name"] == "rpc" && check["chain"] == json!(chain))
+                .unwrap_or_else(|| panic!("no rpc check for {chain} in {body}"))
+        };
+        assert_eq!(rpc_on(SETTLEMENT_CHAIN)["ok"], true);
+        assert_eq!(rpc_on(TAKER_CHAIN)["ok"], false);
+        assert_eq!(rpc_on(TAKER_CHAIN)["message"], "rpc unreachable");
+        assert!(checks
+            .iter()
+            .filter(|check| check["name"] == "signer")
+            .all(|check| check["message"]
+                .as_str()
+                .is_some_and(|message| message.ends_with(": invalid key"))));
+    }
+}
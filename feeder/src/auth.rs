@@ -0,0 +1,18 @@
+use crate::orderbook::SharedState;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+pub use oceanlink_core::auth::{
+    authorize, ApiKey, AuthError, Authenticator, RateLimit, Role, API_KEY_HEADER,
+};
+
+pub async fn require_user(State(state): State<SharedState>, req: Request, next: Next) -> Response {
+    authorize(&state.auth, req, next, Role::User).await
+}
+
+pub async fn require_admin(State(state): State<SharedState>, req: Request, next: Next) -> Response {
+    authorize(&state.auth, req, next, Role::Admin).await
+}
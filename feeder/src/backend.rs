@@ -0,0 +1,176 @@
+use crate::auth::API_KEY_HEADER;
+use crate::config::BackendConfig;
+use crate::models::{ChainId, Intent, IntentKind, SETTLEMENT_CHAIN, TAKER_CHAIN};
+use crate::orderbook::MAKERS;
+use crate::signing::{unix_now, IntentMessage, SigningDomain};
+use ethers::signers::{LocalWallet, Signer};
+use serde::Serialize;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// How long the signature on a posted maker intent stays valid. The backend
+/// checks it once, at intake.
+const SIGNATURE_TTL: Duration = Duration::from_secs(300);
+
+/// The backend's signed intent request, posted with `kind: maker`.
+#[derive(Debug, Serialize)]
+pub struct MakerIntentRequest {
+    pub user_id: String,
+    pub from_chain: ChainId,
+    pub to_chain: ChainId,
+    pub amount: u64,
+    pub nonce: u64,
+    pub deadline: u64,
+    pub signature: String,
+    pub kind: IntentKind,
+}
+
+impl MakerIntentRequest {
+    /// Signs an offer of `amount` from Base Sepolia to Sepolia with the
+    /// maker's `private_key`, in the backend's signing `domain`.
+    pub async fn sign(
+        private_key: &str,
+        amount: u64,
+        nonce: u64,
+        domain: &SigningDomain,
+    ) -> Result<Self, String> {
+        let wallet =
+            LocalWallet::from_str(private_key).map_err(|e| format!("Invalid private key: {e}"))?;
+        let deadline = unix_now() + SIGNATURE_TTL.as_secs();
+        let message = IntentMessage {
+            user: wallet.address(),
+            from_chain: SETTLEMENT_CHAIN,
+            to_chain: TAKER_CHAIN,
+            amount,
+            nonce,
+            deadline,
+            verifying_contract: domain.verifying_contract(SETTLEMENT_CHAIN),
+        };
+        let signature = wallet
+            .sign_typed_data(&message)
+            .await
+            .map_err(|e| format!("Failed to sign intent: {e}"))?;
+        Ok(Self {
+            user_id: format!("{:#x}", wallet.address()),
+            from_chain: SETTLEMENT_CHAIN,
+            to_chain: TAKER_CHAIN,
+            amount,
+            nonce,
+            deadline,
+            signature: signature.to_string(),
+            kind: IntentKind::Maker,
+        })
+    }
+}
+
+/// Client for the backend's HTTP API, used to act as its liquidity provider.
+pub struct BackendClient {
+    client: reqwest::Client,
+    url: String,
+    api_key: String,
+}
+
+impl BackendClient {
+    pub fn new(config: &BackendConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("http client builds");
+        Self {
+            client,
+            url: config.url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone(),
+        }
+    }
+
+    /// Submits a signed maker intent and returns the backend's record of it.
+    pub async fn post_intent(&self, request: &MakerIntentRequest) -> Result<Intent, String> {
+        let body = serde_json::to_vec(request).expect("requests serialize");
+        let response = self
+            .client
+            .post(format!("{}/intents", self.url))
+            .header("content-type", "application/json")
+            .header(API_KEY_HEADER, &self.api_key)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("Backend unreachable: {e}"))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Backend unreachable: {e}"))?;
+        if !status.is_success() {
+            return Err(format!(
+                "Backend answered {status}: {}",
+                String::from_utf8_lossy(&body)
+            ));
+        }
+        serde_json::from_slice(&body).map_err(|e| format!("Unreadable intent: {e}"))
+    }
+
+    /// Posts each maker's inventory, signed with its key from `signers` (B,
+    /// C and D in order). The backend sends the resulting maker legs back to
+    /// `POST /legs`.
+    pub async fn post_inventory(
+        &self,
+        signers: &[String; 3],
+        domain: &SigningDomain,
+    ) -> Vec<Result<Intent, String>> {
+        // Millisecond nonces stay fresh across restarts.
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        let mut posted = Vec::new();
+        for ((maker, amount), key) in MAKERS.into_iter().zip(signers) {
+            let result = match MakerIntentRequest::sign(key, amount, nonce, domain).await {
+                Ok(request) => self.post_intent(&request).await,
+                Err(err) => Err(err),
+            };
+            match &result {
+                Ok(intent) => {
+                    info!(maker, intent_id = %intent.id, amount, "maker inventory posted")
+                }
+                Err(err) => warn!(maker, error = %err, "maker inventory not posted"),
+            }
+            posted.push(result);
+        }
+        posted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::verify_signature;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[tokio::test]
+    async fn maker_intents_are_signed_for_the_backend() {
+        let domain = SigningDomain::new();
+        let request = MakerIntentRequest::sign(KEY, 500_000, 7, &domain)
+            .await
+            .expect("key is valid");
+        let json = serde_json::to_value(&request).expect("serializes");
+        assert_eq!(json["kind"], "maker");
+        assert_eq!(json["from_chain"], "Base Sepolia");
+        assert_eq!(json["to_chain"], "Sepolia");
+
+        // Both services verify the same EIP-712 message.
+        let message = IntentMessage {
+            user: request.user_id.parse().expect("address"),
+            from_chain: request.from_chain,
+            to_chain: request.to_chain,
+            amount: request.amount,
+            nonce: request.nonce,
+            deadline: request.deadline,
+            verifying_contract: domain.verifying_contract(request.from_chain),
+        };
+        let signer =
+            verify_signature(&message, &request.signature, unix_now()).expect("signature verifies");
+        assert_eq!(format!("{signer:#x}"), request.user_id);
+    }
+}
@@ -1,18 +1,19 @@
-use crate::models::Chain;
+use crate::models::ChainId;
 use serde::Serialize;
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
-pub type BalanceKey = (Chain, String);
+pub type BalanceKey = (ChainId, String);
 pub type Balances = HashMap<BalanceKey, u64>;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BalanceSnapshot {
-    pub chain: Chain,
+    pub chain: ChainId,
     pub user: String,
     pub amount: u64,
 }
 
-pub fn mint(balances: &mut Balances, chain: Chain, user: &str, amount: u64) {
+pub fn mint(balances: &mut Balances, chain: ChainId, user: &str, amount: u64) {
     let entry = balances
         .entry((chain, user.to_string()))
         .or_insert_with(|| 0u64);
@@ -1,19 +1,81 @@
+use crate::metrics::Metrics;
+use crate::models::{ChainId, SETTLEMENT_CHAIN, USER_B, USER_C, USER_D};
 use ethers::{
-    core::types::{Address, Bytes, U256},
+    abi::AbiDecode,
+    contract::EthError,
+    core::types::{Address, BlockNumber, Bytes, U256},
     middleware::SignerMiddleware,
-    providers::{Http, Middleware, Provider},
+    providers::{Http, Middleware, Provider, ProviderError, RpcError},
     signers::{LocalWallet, Signer},
     types::TransactionRequest,
 };
+use std::collections::BTreeMap;
+use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info_span, warn, Instrument};
+
+/// Selector of `Panic(uint256)`, raised by failed asserts and arithmetic.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Why a transfer could not be simulated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    /// The transfer reverts against the latest block, for the decoded
+    /// reason.
+    Reverted(String),
+    /// The simulation itself failed, e.g. the RPC endpoint is down.
+    Failed(String),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::Reverted(reason) => write!(f, "transfer would revert: {reason}"),
+            SimulationError::Failed(err) => f.write_str(err),
+        }
+    }
+}
+
+/// Decodes the revert data of a call: an `Error(string)` message, a
+/// `Panic(uint256)` code or the selector of a custom error.
+pub fn decode_revert(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "reverted without a reason".into();
+    }
+    if let Some(message) = <String as EthError>::decode_with_selector(data) {
+        return message;
+    }
+    if let Some(code) = data
+        .strip_prefix(&PANIC_SELECTOR)
+        .and_then(|code| U256::decode(code).ok())
+    {
+        return format!("panic {code:#x}");
+    }
+    match data.get(..4) {
+        Some(selector) => format!("custom error 0x{}", hex::encode(selector)),
+        None => format!("malformed revert data 0x{}", hex::encode(data)),
+    }
+}
+
+fn simulation_error(err: ProviderError) -> SimulationError {
+    match err.as_error_response().and_then(|err| err.as_revert_data()) {
+        Some(data) => SimulationError::Reverted(decode_revert(&data)),
+        None => SimulationError::Failed(format!("Failed to simulate transfer: {err}")),
+    }
+}
 
 pub struct BlockchainClient {
     base_rpc: String,
+    /// Endpoint of every configured chain, for readiness. Transfers only go
+    /// through `base_rpc`.
+    rpc_urls: BTreeMap<ChainId, String>,
     base_token_address: Address,
     b_private_key: String,
     c_private_key: String,
     d_private_key: String,
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl BlockchainClient {
@@ -25,15 +87,143 @@ impl BlockchainClient {
         d_private_key: String,
     ) -> Self {
         Self {
+            rpc_urls: BTreeMap::from([(SETTLEMENT_CHAIN, base_rpc.clone())]),
             base_rpc,
             base_token_address,
             b_private_key,
             c_private_key,
             d_private_key,
+            metrics: None,
         }
     }
 
-    pub async fn send_erc20_transfer(&self, from: &str, to: &str, amount: u64) -> Result<String, String> {
+    /// Adds the endpoint of another configured chain, which readiness
+    /// probes alongside the settlement chain's.
+    pub fn with_rpc_url(mut self, chain: ChainId, rpc_url: String) -> Self {
+        self.rpc_urls.insert(chain, rpc_url);
+        self
+    }
+
+    /// Chains with a configured RPC endpoint.
+    pub fn chains(&self) -> impl Iterator<Item = ChainId> + '_ {
+        self.rpc_urls.keys().copied()
+    }
+
+    /// Chain id reported by `chain`'s RPC endpoint.
+    pub async fn chain_id(&self, chain: ChainId) -> Result<u64, String> {
+        let rpc_url = self
+            .rpc_urls
+            .get(&chain)
+            .ok_or_else(|| format!("No RPC endpoint configured for {chain}"))?;
+        let provider = Provider::<Http>::try_from(rpc_url.as_str())
+            .map_err(|e| format!("Failed to create provider: {e}"))?;
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|e| format!("Failed to fetch chain id: {e}"))?;
+        Ok(chain_id.as_u64())
+    }
+
+    /// Checks that each maker's private key parses and controls the maker's
+    /// address.
+    pub fn check_signers(&self) -> Vec<(&'static str, Result<(), String>)> {
+        [
+            (USER_B, &self.b_private_key),
+            (USER_C, &self.c_private_key),
+            (USER_D, &self.d_private_key),
+        ]
+        .into_iter()
+        .map(|(maker, key)| {
+            let result = LocalWallet::from_str(key)
+                .map_err(|e| format!("Invalid private key: {e}"))
+                .and_then(|wallet| {
+                    let address = format!("{:#x}", wallet.address());
+                    if address == maker {
+                        Ok(())
+                    } else {
+                        Err(format!("key belongs to {address}"))
+                    }
+                });
+            (maker, result)
+        })
+        .collect()
+    }
+
+    /// Records the latency of every transfer in `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Runs the transfer with `eth_call` and `estimate_gas` against the
+    /// latest block without sending it. Returns the gas it would use.
+    pub async fn simulate_erc20_transfer(
+        &self,
+        from: &str,
+        to: &str,
+        amount: u64,
+    ) -> Result<U256, SimulationError> {
+        let span = info_span!("erc20_simulation", from, to, amount);
+        let started = Instant::now();
+        let result = self
+            .run_simulation(from, to, amount)
+            .instrument(span.clone())
+            .await;
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_rpc("simulate_erc20_transfer", result.is_ok(), started.elapsed());
+        }
+        if let Err(err) = &result {
+            span.in_scope(|| warn!(error = %err, "transfer simulation failed"));
+        }
+        result
+    }
+
+    async fn run_simulation(
+        &self,
+        from: &str,
+        to: &str,
+        amount: u64,
+    ) -> Result<U256, SimulationError> {
+        let provider = Provider::<Http>::try_from(&self.base_rpc)
+            .map_err(|e| SimulationError::Failed(format!("Failed to create provider: {e}")))?;
+        let from_addr = Address::from_str(from)
+            .map_err(|e| SimulationError::Failed(format!("Invalid from address: {e}")))?;
+        let tx = TransactionRequest::new()
+            .from(from_addr)
+            .to(self.base_token_address)
+            .data(transfer_data(to, amount).map_err(SimulationError::Failed)?)
+            .into();
+
+        let latest = Some(BlockNumber::Latest.into());
+        provider.call(&tx, latest).await.map_err(simulation_error)?;
+        provider
+            .estimate_gas(&tx, latest)
+            .await
+            .map_err(simulation_error)
+    }
+
+    pub async fn send_erc20_transfer(
+        &self,
+        from: &str,
+        to: &str,
+        amount: u64,
+    ) -> Result<String, String> {
+        let span = info_span!("erc20_transfer", from, to, amount);
+        let started = Instant::now();
+        let result = self
+            .submit_transfer(from, to, amount)
+            .instrument(span.clone())
+            .await;
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_rpc("send_erc20_transfer", result.is_ok(), started.elapsed());
+        }
+        if let Err(err) = &result {
+            span.in_scope(|| warn!(error = %err, "transfer failed"));
+        }
+        result
+    }
+
+    async fn submit_transfer(&self, from: &str, to: &str, amount: u64) -> Result<String, String> {
         let provider = Provider::<Http>::try_from(&self.base_rpc)
             .map_err(|e| format!("Failed to create provider: {e}"))?;
 
@@ -44,15 +234,12 @@ impl BlockchainClient {
             .map_err(|e| format!("Failed to fetch chain id: {e}"))?;
 
         let private_key = match from {
-            "0x3aca6e32bd6268ba2b834e6f23405e10575d19b2" | "0x3ACa6E32BD6268ba2b834e6F23405e10575d19B2" => {
-                &self.b_private_key
-            }
-            "0x7cb386178d13e21093fdc988c7e77102d6464f3e" | "0x7CB386178D13e21093FDc988C7e77102D6464F3E" => {
-                &self.c_private_key
-            }
-            "0xe08745df99d3563821b633aa93ee02f7f883f25c" | "0xE08745df99d3563821b633aA93Ee02F7F883F25c" => {
-                &self.d_private_key
-            }
+            "0x3aca6e32bd6268ba2b834e6f23405e10575d19b2"
+            | "0x3ACa6E32BD6268ba2b834e6F23405e10575d19B2" => &self.b_private_key,
+            "0x7cb386178d13e21093fdc988c7e77102d6464f3e"
+            | "0x7CB386178D13e21093FDc988C7e77102D6464F3E" => &self.c_private_key,
+            "0xe08745df99d3563821b633aa93ee02f7f883f25c"
+            | "0xE08745df99d3563821b633aA93Ee02F7F883F25c" => &self.d_private_key,
             _ => return Err(format!("Unknown sender address: {from}")),
         };
 
@@ -60,30 +247,13 @@ impl BlockchainClient {
         let wallet = LocalWallet::from_str(private_key)
             .map_err(|e| format!("Invalid private key: {e}"))?
             .with_chain_id(chain_id.as_u64());
-        let to_addr = Address::from_str(to)
-            .map_err(|e| format!("Invalid to address: {e}"))?;
-
-        // ERC20 transfer function signature: transfer(address to, uint256 amount)
-        // Function selector: 0xa9059cbb
-        let mut data = vec![0xa9u8, 0x05u8, 0x9cu8, 0xbbu8];
-
-        // Encode to address (32 bytes, right-aligned)
-        let mut to_bytes = [0u8; 32];
-        to_bytes[12..].copy_from_slice(to_addr.as_bytes());
-        data.extend_from_slice(&to_bytes);
-
-        // Encode amount (32 bytes) - USDT has 6 decimals
-        let amount_u256 = U256::from(amount) * U256::from(1_000_000u64); // Convert to wei-equivalent
-        let mut amount_bytes = [0u8; 32];
-        amount_u256.to_big_endian(&mut amount_bytes);
-        data.extend_from_slice(&amount_bytes);
 
         // Combine wallet + provider into a signing client
         let wallet_with_provider = SignerMiddleware::new(provider, wallet);
 
         let tx = TransactionRequest::new()
             .to(self.base_token_address)
-            .data(Bytes::from(data));
+            .data(transfer_data(to, amount)?);
 
         let pending_tx = wallet_with_provider
             .send_transaction(tx, None)
@@ -95,4 +265,52 @@ impl BlockchainClient {
     }
 }
 
+/// Calldata of an ERC20 `transfer(to, amount)`, with `amount` in whole
+/// tokens.
+fn transfer_data(to: &str, amount: u64) -> Result<Bytes, String> {
+    let to_addr = Address::from_str(to).map_err(|e| format!("Invalid to address: {e}"))?;
+
+    // ERC20 transfer function signature: transfer(address to, uint256 amount)
+    // Function selector: 0xa9059cbb
+    let mut data = vec![0xa9u8, 0x05u8, 0x9cu8, 0xbbu8];
+
+    // Encode to address (32 bytes, right-aligned)
+    let mut to_bytes = [0u8; 32];
+    to_bytes[12..].copy_from_slice(to_addr.as_bytes());
+    data.extend_from_slice(&to_bytes);
+
+    // Encode amount (32 bytes) - USDT has 6 decimals
+    let amount_u256 = U256::from(amount) * U256::from(1_000_000u64); // Convert to wei-equivalent
+    let mut amount_bytes = [0u8; 32];
+    amount_u256.to_big_endian(&mut amount_bytes);
+    data.extend_from_slice(&amount_bytes);
+
+    Ok(Bytes::from(data))
+}
+
 pub type SharedBlockchainClient = Arc<BlockchainClient>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiEncode;
+
+    #[test]
+    fn revert_reasons_are_decoded() {
+        let message = [
+            &<String as EthError>::selector()[..],
+            &"Pausable: paused".to_string().encode(),
+        ]
+        .concat();
+        assert_eq!(decode_revert(&message), "Pausable: paused");
+
+        let panic = [&PANIC_SELECTOR[..], &U256::from(0x11).encode()].concat();
+        assert_eq!(decode_revert(&panic), "panic 0x11");
+
+        assert_eq!(
+            decode_revert(&[0xe4, 0x50, 0xd3, 0x8c, 0, 1]),
+            "custom error 0xe450d38c"
+        );
+        assert_eq!(decode_revert(&[]), "reverted without a reason");
+    }
+}
@@ -0,0 +1,639 @@
+use crate::auth::RateLimit;
+use crate::models::{ChainId, SETTLEMENT_CHAIN};
+use crate::signing::SigningDomain;
+use ethers::core::types::Address;
+use ethers::signers::LocalWallet;
+use oceanlink_core::chain::{ChainInfo, ChainRegistry};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+use toml::{Table, Value};
+
+/// Environment variables starting with this override settings; `__`
+/// separates keys, so `OCEANLINK__CHAINS__BASE_SEPOLIA__RPC_URL` sets
+/// `chains.base_sepolia.rpc_url`.
+pub const ENV_PREFIX: &str = "OCEANLINK__";
+pub const DEFAULT_BIND: &str = "127.0.0.1:8081";
+
+/// Variables the feeder read before it had a config file, still honoured
+/// below the `OCEANLINK__` ones.
+const LEGACY_ENV: [(&str, &str); 6] = [
+    ("BASE_RPC_URL", "chains.base_sepolia.rpc_url"),
+    ("BASE_CHAIN_ID", "chains.base_sepolia.chain_id"),
+    ("BASE_TOKEN_ADDRESS", "chains.base_sepolia.tokens.usdc"),
+    ("B_PRIVATE_KEY", "signers.b.private_key"),
+    ("C_PRIVATE_KEY", "signers.c.private_key"),
+    ("D_PRIVATE_KEY", "signers.d.private_key"),
+];
+
+/// Section configuring [`SETTLEMENT_CHAIN`].
+const SETTLEMENT_KEY: &str = "chains.base_sepolia";
+
+/// Makers whose keys sign the settlement transfers.
+const MAKERS: [&str; 3] = ["b", "c", "d"];
+
+/// One invalid setting, named by its dotted key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Everything wrong with a configuration, so it can be fixed in one pass.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration ({} errors):", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validated feeder settings.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub bind: SocketAddr,
+    /// Built-in chains plus those defined or amended under `chains`.
+    pub registry: ChainRegistry,
+    /// Every chain defined under `chains`, the settlement chain included.
+    pub chains: Vec<ChainConfig>,
+    /// [`SETTLEMENT_CHAIN`]; transfers are sent through its RPC endpoint.
+    pub settlement: ChainConfig,
+    /// Private keys of makers B, C and D, in that order.
+    pub signers: [String; 3],
+    /// Backend the makers provide liquidity to, if any.
+    pub backend: Option<BackendConfig>,
+    /// Per API key, or per IP for callers without one.
+    pub rate_limit: RateLimit,
+}
+
+/// Where maker inventory is posted. `Debug` hides the key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BackendConfig {
+    pub url: String,
+    /// A maker API key of the backend.
+    pub api_key: String,
+}
+
+impl fmt::Debug for BackendConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendConfig")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainConfig {
+    pub chain: ChainId,
+    pub rpc_url: String,
+    pub vault: Option<Address>,
+    /// Token contracts by symbol; `usdc` is the one transferred.
+    pub tokens: BTreeMap<String, Address>,
+}
+
+impl ChainConfig {
+    pub fn usdc(&self) -> Address {
+        self.tokens["usdc"]
+    }
+}
+
+impl Config {
+    /// Builds the configuration from, lowest precedence first: the TOML
+    /// `file`, the legacy variables and then [`ENV_PREFIX`] variables from
+    /// `env`, and `overrides` of the form `section.key=value`. Every invalid
+    /// setting is reported, not just the first.
+    pub fn load<I>(file: Option<&Path>, env: I, overrides: &[String]) -> Result<Self, ConfigErrors>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut errors = Errors::default();
+        let mut table = match file {
+            Some(path) => read_file(path, &mut errors),
+            None => Table::new(),
+        };
+
+        let env: Vec<(String, String)> = env.into_iter().collect();
+        for (name, key) in LEGACY_ENV {
+            if let Some((_, value)) = env.iter().find(|(var, _)| var == name) {
+                let keys: Vec<String> = key.split('.').map(str::to_string).collect();
+                set(&mut table, &keys, value.clone(), &mut errors);
+            }
+        }
+        for (name, value) in env {
+            if let Some(path) = name.strip_prefix(ENV_PREFIX) {
+                let keys: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+                set(&mut table, &keys, value, &mut errors);
+            }
+        }
+        for setting in overrides {
+            match setting.split_once('=') {
+                Some((key, value)) => {
+                    let keys: Vec<String> = key.trim().split('.').map(str::to_string).collect();
+                    set(&mut table, &keys, value.to_string(), &mut errors);
+                }
+                None => errors.push(setting, "expected key=value"),
+            }
+        }
+
+        let config = Self::from_table(table, &mut errors);
+        if errors.0.is_empty() {
+            Ok(config.expect("complete when no errors were found"))
+        } else {
+            Err(ConfigErrors(errors.0))
+        }
+    }
+
+    fn from_table(table: Table, errors: &mut Errors) -> Option<Self> {
+        let mut raw = RawConfig::default();
+        for (key, value) in table {
+            match key.as_str() {
+                "bind" => raw.bind = Some(value),
+                "chains" => raw.chains = section(&key, value, errors).unwrap_or_default(),
+                "signers" => raw.signers = section(&key, value, errors).unwrap_or_default(),
+                "backend" => raw.backend = section(&key, value, errors),
+                "rate_limit" => raw.rate_limit = section(&key, value, errors).unwrap_or_default(),
+                _ => errors.push(&key, "unknown setting"),
+            }
+        }
+
+        let bind = match text(errors, "bind", raw.bind) {
+            Some(bind) => bind
+                .parse()
+                .map_err(|_| {
+                    errors.push("bind", format!("expected a socket address, got '{bind}'"))
+                })
+                .ok(),
+            None => Some(DEFAULT_BIND.parse().expect("default bind address is valid")),
+        };
+        let mut registry = ChainRegistry::builtin();
+        let chains = chains(raw.chains, &mut registry, errors);
+        let settlement = chains
+            .iter()
+            .find(|chain| chain.chain == SETTLEMENT_CHAIN)
+            .cloned();
+        if settlement.is_none()
+            && !errors
+                .0
+                .iter()
+                .any(|error| error.key.starts_with(SETTLEMENT_KEY))
+        {
+            errors.push(SETTLEMENT_KEY, "required");
+        }
+        let signers = signers(raw.signers, errors);
+        let rate_limit = rate_limit(raw.rate_limit, errors);
+        let backend = match raw.backend {
+            Some(raw) => Some(backend(raw, errors)?),
+            None => None,
+        };
+
+        Some(Self {
+            bind: bind?,
+            registry,
+            chains,
+            settlement: settlement?,
+            signers: signers?,
+            backend,
+            rate_limit,
+        })
+    }
+
+    /// Intents from the settlement chain are signed against its vault; the
+    /// backend must be configured with the same one.
+    pub fn signing_domain(&self) -> SigningDomain {
+        match self.settlement.vault {
+            Some(vault) => SigningDomain::new().with_vault(self.settlement.chain, vault),
+            None => SigningDomain::new(),
+        }
+    }
+}
+
+/// The configuration as written, before validation. Leaves stay untyped
+/// so that every bad value can be reported instead of the first.
+#[derive(Default)]
+struct RawConfig {
+    bind: Option<Value>,
+    chains: BTreeMap<String, RawChain>,
+    signers: BTreeMap<String, RawSigner>,
+    backend: Option<RawBackend>,
+    rate_limit: RawRateLimit,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawChain {
+    chain_id: Option<Value>,
+    name: Option<Value>,
+    aliases: Option<Value>,
+    rpc_url: Option<Value>,
+    finality_depth: Option<Value>,
+    explorer_url: Option<Value>,
+    vault: Option<Value>,
+    tokens: BTreeMap<String, Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawSigner {
+    private_key: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawBackend {
+    url: Option<Value>,
+    api_key: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRateLimit {
+    per_second: Option<Value>,
+    burst: Option<Value>,
+}
+
+#[derive(Default)]
+struct Errors(Vec<ConfigError>);
+
+impl Errors {
+    fn push(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigError {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+}
+
+fn read_file(path: &Path, errors: &mut Errors) -> Table {
+    let key = format!("config file {}", path.display());
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            errors.push(key, err.to_string());
+            return Table::new();
+        }
+    };
+    source.parse::<Table>().unwrap_or_else(|err| {
+        errors.push(key, err.message());
+        Table::new()
+    })
+}
+
+/// Sets `keys` in `table` to the string `value`, creating tables on the way.
+/// Typed settings accept strings, so overrides need no quoting.
+fn set(table: &mut Table, keys: &[String], value: String, errors: &mut Errors) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for (depth, key) in parents.iter().enumerate() {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        current = match entry {
+            Value::Table(table) => table,
+            _ => {
+                errors.push(keys[..=depth].join("."), "is a value, not a section");
+                return;
+            }
+        };
+    }
+    current.insert(last.clone(), Value::String(value));
+}
+
+fn section<T>(key: &str, value: Value, errors: &mut Errors) -> Option<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    T::deserialize(value)
+        .map_err(|err| errors.push(key, err.message()))
+        .ok()
+}
+
+fn text(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => Some(text),
+        other => {
+            errors.push(key, format!("expected a string, got {other}"));
+            None
+        }
+    }
+}
+
+/// Like [`text`], but pushes `required` when the setting is missing.
+fn required(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<String> {
+    if value.is_none() {
+        errors.push(key, "required");
+    }
+    text(errors, key, value)
+}
+
+fn positive(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<u64> {
+    let number = match value? {
+        Value::Integer(n) if n > 0 => Some(n as u64),
+        Value::String(text) => text.trim().parse().ok().filter(|n| *n > 0),
+        _ => None,
+    };
+    if number.is_none() {
+        errors.push(key, "expected a positive integer");
+    }
+    number
+}
+
+fn number(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<u64> {
+    let number = match value? {
+        Value::Integer(n) if n >= 0 => Some(n as u64),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    };
+    if number.is_none() {
+        errors.push(key, "expected a non-negative integer");
+    }
+    number
+}
+
+/// An array of strings, or one comma-separated string as given in env vars.
+fn list(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<Vec<String>> {
+    match value? {
+        Value::String(text) => Some(
+            text.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ),
+        Value::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| text(errors, &format!("{key}[{i}]"), Some(item)))
+            .collect(),
+        other => {
+            errors.push(key, format!("expected a list of strings, got {other}"));
+            None
+        }
+    }
+}
+
+fn address(errors: &mut Errors, key: &str, value: Option<Value>) -> Option<Address> {
+    let text = text(errors, key, value)?;
+    let valid = text.len() == 42 && text.starts_with("0x");
+    match Address::from_str(&text) {
+        Ok(address) if valid => Some(address),
+        _ => {
+            errors.push(
+                key,
+                format!("expected a 0x-prefixed 20-byte address, got '{text}'"),
+            );
+            None
+        }
+    }
+}
+
+/// Applies each `[chains.<name>]` section to `registry`. A name the registry
+/// knows amends that chain; any other name needs a `chain_id`, and either
+/// adds a chain or, if the id is known, becomes an alias of it.
+fn chains(
+    raw: BTreeMap<String, RawChain>,
+    registry: &mut ChainRegistry,
+    errors: &mut Errors,
+) -> Vec<ChainConfig> {
+    let mut chains = Vec::new();
+    for (name, raw) in raw {
+        let prefix = format!("chains.{name}");
+        let key = |field: &str| format!("{prefix}.{field}");
+
+        let chain_id = positive(errors, &key("chain_id"), raw.chain_id);
+        let known = registry.resolve(&name);
+        let chain = match (known, chain_id) {
+            (Some(chain), Some(id)) if chain.as_u64() != id => {
+                errors.push(
+                    key("chain_id"),
+                    format!("'{name}' is chain {}, not {id}", chain.as_u64()),
+                );
+                None
+            }
+            (Some(chain), _) => Some(chain),
+            (None, Some(id)) => Some(ChainId::new(id)),
+            (None, None) => {
+                errors.push(
+                    &prefix,
+                    format!("unknown chain '{name}'; set chain_id to add it"),
+                );
+                None
+            }
+        };
+        let display_name = text(errors, &key("name"), raw.name);
+        let aliases = list(errors, &key("aliases"), raw.aliases).unwrap_or_default();
+        let rpc_url = required(errors, &key("rpc_url"), raw.rpc_url);
+        let finality_depth = number(errors, &key("finality_depth"), raw.finality_depth);
+        let explorer_url = text(errors, &key("explorer_url"), raw.explorer_url);
+        let vault = address(errors, &key("vault"), raw.vault);
+        let mut tokens = BTreeMap::new();
+        for (symbol, value) in raw.tokens {
+            if let Some(address) = address(errors, &key(&format!("tokens.{symbol}")), Some(value)) {
+                tokens.insert(symbol, address);
+            }
+        }
+        if chain == Some(SETTLEMENT_CHAIN) && !tokens.contains_key("usdc") {
+            let key = key("tokens.usdc");
+            if !errors.0.iter().any(|error| error.key == key) {
+                errors.push(key, "required");
+            }
+        }
+
+        let (Some(chain), Some(rpc_url)) = (chain, rpc_url) else {
+            continue;
+        };
+        let mut info = match registry.get(chain) {
+            Some(info) => info.clone(),
+            None => ChainInfo::new(chain, &name),
+        };
+        if known.is_none() && info.name != name {
+            info.aliases.push(name.clone());
+        }
+        if let Some(display_name) = display_name {
+            // The old name keeps working on input.
+            let previous = std::mem::replace(&mut info.name, display_name);
+            info.aliases.push(previous);
+        }
+        info.aliases.extend(aliases);
+        info.rpc_url = Some(rpc_url.clone());
+        if let Some(depth) = finality_depth {
+            info.finality_depth = depth;
+        }
+        if let Some(explorer_url) = explorer_url {
+            info.explorer_url = Some(explorer_url);
+        }
+        if let Err(err) = registry.insert(info) {
+            errors.push(&prefix, err.to_string());
+            continue;
+        }
+        chains.push(ChainConfig {
+            chain,
+            rpc_url,
+            vault,
+            tokens,
+        });
+    }
+    chains
+}
+
+fn signers(mut raw: BTreeMap<String, RawSigner>, errors: &mut Errors) -> Option<[String; 3]> {
+    for name in raw.keys().filter(|name| !MAKERS.contains(&name.as_str())) {
+        errors.push(
+            format!("signers.{name}"),
+            "unknown maker (expected b, c or d)",
+        );
+    }
+    let keys = MAKERS.map(|maker| {
+        let key = format!("signers.{maker}.private_key");
+        let private_key = required(
+            errors,
+            &key,
+            raw.remove(maker).and_then(|signer| signer.private_key),
+        )?;
+        // The message never echoes the key itself.
+        match LocalWallet::from_str(private_key.trim()) {
+            Ok(_) => Some(private_key),
+            Err(_) => {
+                errors.push(key, "expected a 32-byte hex private key");
+                None
+            }
+        }
+    });
+    let [b, c, d] = keys;
+    Some([b?, c?, d?])
+}
+
+fn backend(raw: RawBackend, errors: &mut Errors) -> Option<BackendConfig> {
+    let url =
+        required(errors, "backend.url", raw.url).and_then(|url| match reqwest::Url::parse(&url) {
+            Ok(parsed) if ["http", "https"].contains(&parsed.scheme()) => Some(url),
+            _ => {
+                errors.push(
+                    "backend.url",
+                    format!("expected an http(s) url, got '{url}'"),
+                );
+                None
+            }
+        });
+    let api_key = required(errors, "backend.api_key", raw.api_key);
+    Some(BackendConfig {
+        url: url?,
+        api_key: api_key?,
+    })
+}
+
+fn rate_limit(raw: RawRateLimit, errors: &mut Errors) -> RateLimit {
+    let default = RateLimit::default();
+    let per_second = positive(errors, "rate_limit.per_second", raw.per_second);
+    let burst = positive(errors, "rate_limit.burst", raw.burst).and_then(|burst| {
+        u32::try_from(burst)
+            .map_err(|_| errors.push("rate_limit.burst", format!("must be at most {}", u32::MAX)))
+            .ok()
+    });
+    RateLimit {
+        per_second: per_second.map_or(default.per_second, |n| n as f64),
+        burst: burst.unwrap_or(default.burst),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const USDC: &str = "0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn legacy_variables_still_configure_the_feeder() {
+        let config = Config::load(
+            None,
+            env(&[
+                ("BASE_RPC_URL", "https://sepolia.base.org"),
+                ("BASE_TOKEN_ADDRESS", USDC),
+                ("B_PRIVATE_KEY", KEY),
+                ("C_PRIVATE_KEY", KEY),
+                ("D_PRIVATE_KEY", KEY),
+                (
+                    "OCEANLINK__CHAINS__BASE_SEPOLIA__RPC_URL",
+                    "https://rpc.example.com",
+                ),
+                ("OCEANLINK__CHAINS__BASE_SEPOLIA__FINALITY_DEPTH", "3"),
+            ]),
+            &["bind=0.0.0.0:9001".to_string()],
+        )
+        .unwrap_or_else(|errors| panic!("{errors}"));
+
+        assert_eq!(config.bind.to_string(), "0.0.0.0:9001");
+        assert_eq!(
+            config.settlement.rpc_url, "https://rpc.example.com",
+            "prefixed variables win"
+        );
+        assert_eq!(format!("{:?}", config.settlement.usdc()), USDC);
+        assert_eq!(config.chains, std::slice::from_ref(&config.settlement));
+        let info = config.registry.get(SETTLEMENT_CHAIN).expect("built in");
+        assert_eq!(info.rpc_url.as_deref(), Some("https://rpc.example.com"));
+        assert_eq!(info.finality_depth, 3);
+        assert_eq!(config.signers, [KEY, KEY, KEY]);
+        assert!(config.backend.is_none());
+        assert_eq!(config.rate_limit, RateLimit::default());
+    }
+
+    #[test]
+    fn every_error_is_reported_at_once() {
+        let errors = Config::load(
+            None,
+            env(&[
+                ("BASE_TOKEN_ADDRESS", "0x1234"),
+                ("B_PRIVATE_KEY", "0xsecret"),
+                ("BASE_CHAIN_ID", "8453"),
+                ("OCEANLINK__SIGNERS__E__PRIVATE_KEY", KEY),
+                ("OCEANLINK__BACKEND__URL", "localhost:8080"),
+                ("OCEANLINK__RATE_LIMIT__BURST", "0"),
+            ]),
+            &["bind=nowhere".to_string()],
+        )
+        .expect_err("config is invalid");
+
+        let mut keys: Vec<&str> = errors.0.iter().map(|error| error.key.as_str()).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "backend.api_key",
+                "backend.url",
+                "bind",
+                "chains.base_sepolia.chain_id",
+                "chains.base_sepolia.rpc_url",
+                "chains.base_sepolia.tokens.usdc",
+                "rate_limit.burst",
+                "signers.b.private_key",
+                "signers.c.private_key",
+                "signers.d.private_key",
+                "signers.e",
+            ]
+        );
+        assert!(!errors.to_string().contains("secret"));
+    }
+}
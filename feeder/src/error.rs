@@ -0,0 +1,41 @@
+use crate::models::ChainId;
+use axum::extract::FromRequest;
+use serde_json::json;
+use std::str::FromStr;
+
+pub use oceanlink_core::error::{ApiError, ErrorCode};
+
+/// `axum::Json` with rejections rendered as [`ApiError`].
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+/// Parses a chain name, naming the offending `field` on failure.
+pub fn parse_chain(field: &str, value: &str) -> Result<ChainId, ApiError> {
+    ChainId::from_str(value).map_err(|err| {
+        ApiError::new(ErrorCode::InvalidChain, format!("{field}: {err}"))
+            .with_details(json!({ "field": field, "value": value }))
+    })
+}
+
+pub fn require_distinct_chains(from_chain: ChainId, to_chain: ChainId) -> Result<(), ApiError> {
+    if from_chain == to_chain {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("to_chain: must differ from from_chain '{from_chain}'"),
+        )
+        .with_details(json!({ "field": "to_chain", "value": to_chain.to_string() })));
+    }
+    Ok(())
+}
+
+pub fn require_positive_amount(field: &str, amount: u64) -> Result<(), ApiError> {
+    if amount == 0 {
+        return Err(ApiError::new(
+            ErrorCode::InvalidAmount,
+            format!("{field} must be greater than zero"),
+        )
+        .with_details(json!({ "field": field })));
+    }
+    Ok(())
+}
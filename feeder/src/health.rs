@@ -0,0 +1,135 @@
+use crate::blockchain::BlockchainClient;
+use crate::models::{ChainId, SETTLEMENT_CHAIN};
+use crate::orderbook::AppState;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Upper bound on a single dependency probe, so a hung RPC node fails
+/// readiness instead of hanging it.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of one dependency probe.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Check {
+    /// `store`, `rpc` or `signer`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainId>,
+    pub ok: bool,
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+/// Body of `/readyz`. `ready` is true only if every check passed.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<Check>,
+}
+
+/// Why a probe failed. Only `reason` is returned to callers: RPC errors can
+/// carry endpoint URLs with API keys in them, so `detail` is only logged.
+struct Failure {
+    reason: &'static str,
+    detail: String,
+}
+
+impl Failure {
+    fn new(reason: &'static str, detail: impl ToString) -> Self {
+        Self {
+            reason,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Checks the in-memory store, the RPC endpoint and chain id of every
+/// configured chain, and the maker signing keys.
+pub async fn readiness(state: &AppState, blockchain: &BlockchainClient) -> Readiness {
+    let mut checks = vec![
+        probe("store", None, async {
+            if state.balances.is_poisoned() {
+                return Err(Failure::new("store unavailable", "balances lock poisoned"));
+            }
+            if state.orderbook.is_poisoned() {
+                return Err(Failure::new("store unavailable", "orderbook lock poisoned"));
+            }
+            Ok("ok".to_string())
+        })
+        .await,
+    ];
+
+    for chain in blockchain.chains() {
+        checks.push(
+            probe("rpc", Some(chain), async {
+                let id = blockchain
+                    .chain_id(chain)
+                    .await
+                    .map_err(|err| Failure::new("rpc unreachable", err))?;
+                let expected = chain.as_u64();
+                if id != expected {
+                    return Err(Failure::new(
+                        "unexpected chain id",
+                        format!("chain id {id}, expected {expected}"),
+                    ));
+                }
+                Ok(format!("chain id {id}"))
+            })
+            .await,
+        );
+    }
+
+    for (maker, result) in blockchain.check_signers() {
+        let (ok, message) = match result {
+            Ok(()) => (true, format!("{maker}: ok")),
+            Err(err) => {
+                warn!(maker, error = %err, "readiness check failed");
+                (false, format!("{maker}: invalid key"))
+            }
+        };
+        checks.push(Check {
+            name: "signer".into(),
+            chain: Some(SETTLEMENT_CHAIN),
+            ok,
+            message,
+            latency_ms: 0,
+        });
+    }
+
+    Readiness {
+        ready: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
+
+async fn probe<F>(name: &str, chain: Option<ChainId>, check: F) -> Check
+where
+    F: Future<Output = Result<String, Failure>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Failure::new(
+                "timed out",
+                format!("no answer within {}s", CHECK_TIMEOUT.as_secs()),
+            ))
+        });
+    let (ok, message) = match result {
+        Ok(message) => (true, message),
+        Err(failure) => {
+            warn!(check = name, chain = ?chain, error = %failure.detail, "readiness check failed");
+            (false, failure.reason.to_string())
+        }
+    };
+    Check {
+        name: name.into(),
+        chain,
+        ok,
+        message,
+        latency_ms: started.elapsed().as_millis() as u64,
+    }
+}
@@ -0,0 +1,17 @@
+//! The OceanLink feeder demo as a library: the HTTP API, orderbook, matching
+//! and settlement client behind the `feeder` binary. Domain types come from
+//! `oceanlink-core`, so intents and legs read the same as the backend's.
+
+pub mod api;
+pub mod auth;
+pub mod backend;
+pub mod balances;
+pub mod blockchain;
+pub mod config;
+pub mod error;
+pub mod health;
+pub mod matching;
+pub mod metrics;
+pub mod models;
+pub mod orderbook;
+pub mod signing;
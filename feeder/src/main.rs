@@ -1,57 +1,105 @@
-mod api;
-mod balances;
-mod blockchain;
-mod matching;
-mod models;
-mod orderbook;
-
-use api::router;
-use blockchain::{BlockchainClient, SharedBlockchainClient};
-use orderbook::init_state;
-use ethers::core::types::Address;
+use clap::Parser;
+use feeder::api::router;
+use feeder::auth::{Authenticator, RateLimit, Role};
+use feeder::backend::BackendClient;
+use feeder::blockchain::{BlockchainClient, SharedBlockchainClient};
+use feeder::config::Config;
+use feeder::orderbook::init_state;
+use oceanlink_core::chain;
 use std::env;
 use std::net::SocketAddr;
-use std::str::FromStr;
+use std::path::PathBuf;
+use std::process;
 use std::sync::Arc;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+/// OceanLink feeder demo.
+#[derive(Parser)]
+struct Cli {
+    /// TOML configuration file.
+    #[arg(long, env = "OCEANLINK_CONFIG")]
+    config: Option<PathBuf>,
+    /// Address to listen on; overrides `bind`.
+    #[arg(long)]
+    bind: Option<String>,
+    /// Overrides any setting, e.g. `--set chains.base_sepolia.finality_depth=5`.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+}
 
 #[tokio::main]
 async fn main() {
-    let state = init_state();
-    
-    // Initialize blockchain client from environment variables
-    let base_rpc = env::var("BASE_RPC_URL")
-        .expect("BASE_RPC_URL environment variable must be set");
-    let base_token_address = env::var("BASE_TOKEN_ADDRESS")
-        .expect("BASE_TOKEN_ADDRESS environment variable must be set");
-    let base_token_address = Address::from_str(&base_token_address)
-        .expect("Invalid BASE_TOKEN_ADDRESS");
-    
-    let b_private_key = env::var("B_PRIVATE_KEY")
-        .expect("B_PRIVATE_KEY environment variable must be set");
-    let c_private_key = env::var("C_PRIVATE_KEY")
-        .expect("C_PRIVATE_KEY environment variable must be set");
-    let d_private_key = env::var("D_PRIVATE_KEY")
-        .expect("D_PRIVATE_KEY environment variable must be set");
-    
-    let blockchain: SharedBlockchainClient = Arc::new(BlockchainClient::new(
-        base_rpc,
-        base_token_address,
-        b_private_key,
-        c_private_key,
-        d_private_key,
-    ));
-    
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    let mut overrides = cli.overrides;
+    if let Some(bind) = cli.bind {
+        overrides.push(format!("bind={bind}"));
+    }
+    let config = match Config::load(cli.config.as_deref(), env::vars(), &overrides) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("{errors}");
+            process::exit(2);
+        }
+    };
+
+    chain::install(config.registry.clone());
+    let signing = config.signing_domain();
+    let state = init_state(authenticator(config.rate_limit), signing.clone());
+    if let Some(backend) = &config.backend {
+        // Makers provide liquidity to the backend, which sends their legs
+        // back to `POST /legs`.
+        let client = BackendClient::new(backend);
+        let signers = config.signers.clone();
+        info!(url = %backend.url, "posting maker inventory to the backend");
+        tokio::spawn(async move { client.post_inventory(&signers, &signing).await });
+    }
+    let [b_private_key, c_private_key, d_private_key] = config.signers;
+    let token = config.settlement.usdc();
+    let blockchain = config.chains.into_iter().fold(
+        BlockchainClient::new(
+            config.settlement.rpc_url,
+            token,
+            b_private_key,
+            c_private_key,
+            d_private_key,
+        ),
+        |client, chain| client.with_rpc_url(chain.chain, chain.rpc_url),
+    );
+    let blockchain: SharedBlockchainClient =
+        Arc::new(blockchain.with_metrics(state.metrics.clone()));
+
     let app = router(state, blockchain);
 
-    let addr: SocketAddr = "127.0.0.1:8081".parse().expect("valid address");
-    println!("feeder running on http://{addr}");
+    info!("feeder running on http://{}", config.bind);
 
     axum::serve(
-        tokio::net::TcpListener::bind(addr)
+        tokio::net::TcpListener::bind(config.bind)
             .await
             .expect("failed to bind listener"),
-        app,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
     )
     .await
     .expect("server crashed");
 }
+
+/// Loads `ADMIN_API_KEY` and the comma-separated `API_KEYS` from the
+/// environment. With neither set, every request is refused.
+fn authenticator(limit: RateLimit) -> Authenticator {
+    let auth = Authenticator::new(limit);
+    if let Ok(key) = env::var("ADMIN_API_KEY") {
+        auth.keys.insert(&key, Role::Admin);
+    }
+    if let Ok(keys) = env::var("API_KEYS") {
+        for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            auth.keys.insert(key, Role::User);
+        }
+    }
+    auth
+}
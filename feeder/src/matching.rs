@@ -1,22 +1,25 @@
-use crate::models::{Chain, Intent, IntentKind, TransferPlanEntry, USER_A, USER_B, USER_C, USER_D};
+use crate::models::{
+    Amount, ChainId, Intent, IntentKind, Leg, SETTLEMENT_CHAIN, TAKER_CHAIN, USER_A, USER_B,
+    USER_C, USER_D,
+};
 
 const REQUIRED_TOTAL: u64 = 1_000_000;
-const PLAN: [(Chain, &str, &str, u64); 6] = [
-    (Chain::Sepolia, USER_A, USER_B, 500_000),
-    (Chain::Sepolia, USER_A, USER_C, 300_000),
-    (Chain::Sepolia, USER_A, USER_D, 200_000),
-    (Chain::Base, USER_B, USER_A, 500_000),
-    (Chain::Base, USER_C, USER_A, 300_000),
-    (Chain::Base, USER_D, USER_A, 200_000),
+const PLAN: [(ChainId, &str, &str, u64); 6] = [
+    (TAKER_CHAIN, USER_A, USER_B, 500_000),
+    (TAKER_CHAIN, USER_A, USER_C, 300_000),
+    (TAKER_CHAIN, USER_A, USER_D, 200_000),
+    (SETTLEMENT_CHAIN, USER_B, USER_A, 500_000),
+    (SETTLEMENT_CHAIN, USER_C, USER_A, 300_000),
+    (SETTLEMENT_CHAIN, USER_D, USER_A, 200_000),
 ];
 
-pub fn match_a_against_makers(intents: &[Intent]) -> Option<Vec<TransferPlanEntry>> {
-    let total_a: u64 = intents
+pub fn match_a_against_makers(intents: &[Intent]) -> Option<Vec<Leg>> {
+    let total_a: Amount = intents
         .iter()
         .filter(|intent| {
-            intent.user == USER_A
-                && intent.from_chain == Chain::Sepolia
-                && intent.to_chain == Chain::Base
+            intent.user_id == USER_A
+                && intent.from_chain == TAKER_CHAIN
+                && intent.to_chain == SETTLEMENT_CHAIN
                 && intent.kind == IntentKind::Taker
         })
         .map(|intent| intent.amount)
@@ -28,24 +31,24 @@ pub fn match_a_against_makers(intents: &[Intent]) -> Option<Vec<TransferPlanEntr
 
     Some(
         PLAN.iter()
-            .map(|(chain, from, to, amount)| TransferPlanEntry {
+            .map(|(chain, from, to, amount)| Leg {
                 chain: *chain,
-                from: (*from).to_string(),
-                to: (*to).to_string(),
-                amount: *amount,
+                from_user: (*from).to_string(),
+                to_user: (*to).to_string(),
+                amount: Amount::new(*amount),
             })
             .collect(),
     )
 }
 
-pub fn plan_for_chain(chain: Chain) -> Vec<TransferPlanEntry> {
+pub fn plan_for_chain(chain: ChainId) -> Vec<Leg> {
     PLAN.iter()
         .filter(|(entry_chain, _, _, _)| *entry_chain == chain)
-        .map(|(entry_chain, from, to, amount)| TransferPlanEntry {
+        .map(|(entry_chain, from, to, amount)| Leg {
             chain: *entry_chain,
-            from: (*from).to_string(),
-            to: (*to).to_string(),
-            amount: *amount,
+            from_user: (*from).to_string(),
+            to_user: (*to).to_string(),
+            amount: Amount::new(*amount),
         })
         .collect()
 }
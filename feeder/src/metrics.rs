@@ -0,0 +1,118 @@
+use crate::models::{ChainId, Intent};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Prometheus metrics for the feeder, rendered by `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    orderbook_intents: IntGaugeVec,
+    orderbook_notional: IntGaugeVec,
+    rpc_duration: HistogramVec,
+    transfer_failures: IntCounterVec,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let route = ["from_chain", "to_chain"];
+        let orderbook_intents = IntGaugeVec::new(
+            Opts::new("feeder_orderbook_intents", "Open intents per route"),
+            &route,
+        )
+        .expect("valid metric");
+        let orderbook_notional = IntGaugeVec::new(
+            Opts::new(
+                "feeder_orderbook_notional",
+                "Total amount of open intents per route",
+            ),
+            &route,
+        )
+        .expect("valid metric");
+        let rpc_duration = HistogramVec::new(
+            HistogramOpts::new("feeder_rpc_duration_seconds", "Latency of chain RPC calls"),
+            &["method", "outcome"],
+        )
+        .expect("valid metric");
+        let transfer_failures = IntCounterVec::new(
+            Opts::new(
+                "feeder_transfer_failures_total",
+                "Settlement transfers that failed",
+            ),
+            &["chain"],
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(orderbook_intents.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(orderbook_notional.clone()),
+            Box::new(rpc_duration.clone()),
+            Box::new(transfer_failures.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            orderbook_intents,
+            orderbook_notional,
+            rpc_duration,
+            transfer_failures,
+        }
+    }
+
+    pub fn observe_rpc(&self, method: &str, ok: bool, elapsed: Duration) {
+        let outcome = if ok { "ok" } else { "error" };
+        self.rpc_duration
+            .with_label_values(&[method, outcome])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_transfer_failure(&self, chain: ChainId) {
+        self.transfer_failures
+            .with_label_values(&[chain.to_string().as_str()])
+            .inc();
+    }
+
+    /// Refreshes the orderbook gauges from `orderbook` and encodes every
+    /// metric in the Prometheus text format.
+    pub fn render(&self, orderbook: &[Intent]) -> String {
+        let mut depth: HashMap<(ChainId, ChainId), (i64, u64)> = HashMap::new();
+        for intent in orderbook {
+            let entry = depth
+                .entry((intent.from_chain, intent.to_chain))
+                .or_default();
+            entry.0 += 1;
+            entry.1 = entry.1.saturating_add(intent.amount.get());
+        }
+
+        self.orderbook_intents.reset();
+        self.orderbook_notional.reset();
+        for ((from, to), (intents, notional)) in depth {
+            let (from, to) = (from.to_string(), to.to_string());
+            let labels = [from.as_str(), to.as_str()];
+            self.orderbook_intents
+                .with_label_values(&labels)
+                .set(intents);
+            self.orderbook_notional
+                .with_label_values(&labels)
+                .set(notional.min(i64::MAX as u64) as i64);
+        }
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding cannot fail");
+        String::from_utf8(buffer).expect("metrics are utf-8")
+    }
+}
@@ -1,51 +1,82 @@
+use crate::auth::Authenticator;
 use crate::balances::{self, Balances};
-use crate::models::{Chain, Intent, IntentKind, USER_B, USER_C, USER_D};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use crate::metrics::Metrics;
+use crate::models::{
+    Intent, IntentKind, IntentStatus, SETTLEMENT_CHAIN, TAKER_CHAIN, USER_B, USER_C, USER_D,
+};
+use crate::signing::{NonceRegistry, SigningDomain};
+use std::sync::{Arc, Mutex, RwLock};
 use uuid::Uuid;
 
-const MAKERS: [(&str, u64); 3] = [(USER_B, 500_000), (USER_C, 300_000), (USER_D, 200_000)];
+/// Makers B, C and D with the inventory each offers from Base Sepolia.
+pub const MAKERS: [(&str, u64); 3] = [(USER_B, 500_000), (USER_C, 300_000), (USER_D, 200_000)];
 const MAKER_MINT_AMOUNT: u64 = 1_000_000_000;
 
+/// Balances and the orderbook are locked independently so deposits never
+/// wait on order intake. Both use blocking locks: their guards are not
+/// `Send`, which keeps them from being held across an `.await`.
 pub struct AppState {
-    pub balances: Balances,
-    pub orderbook: Vec<Intent>,
+    pub balances: Mutex<Balances>,
+    pub orderbook: RwLock<Vec<Intent>>,
+    pub nonces: NonceRegistry,
+    pub signing: SigningDomain,
+    pub auth: Authenticator,
+    pub metrics: Arc<Metrics>,
 }
 
-pub type SharedState = Arc<Mutex<AppState>>;
+pub type SharedState = Arc<AppState>;
 
-pub fn init_state() -> SharedState {
-    let mut state = AppState {
-        balances: Balances::new(),
-        orderbook: Vec::new(),
+pub fn init_state(auth: Authenticator, signing: SigningDomain) -> SharedState {
+    let state = AppState {
+        balances: Mutex::new(Balances::new()),
+        orderbook: RwLock::new(Vec::new()),
+        nonces: NonceRegistry::default(),
+        signing,
+        auth,
+        metrics: Arc::new(Metrics::new()),
     };
 
-    preload_balances(&mut state);
-    preload_maker_intents(&mut state);
+    preload_balances(&state);
+    preload_maker_intents(&state);
 
-    Arc::new(Mutex::new(state))
+    Arc::new(state)
 }
 
-fn preload_balances(state: &mut AppState) {
+fn preload_balances(state: &AppState) {
+    let mut guard = state.balances.lock().expect("balances lock poisoned");
     for (maker, _) in MAKERS {
-        balances::mint(&mut state.balances, Chain::Base, maker, MAKER_MINT_AMOUNT);
+        balances::mint(&mut guard, SETTLEMENT_CHAIN, maker, MAKER_MINT_AMOUNT);
     }
 }
 
-fn preload_maker_intents(state: &mut AppState) {
+fn preload_maker_intents(state: &AppState) {
+    let mut orderbook = state.orderbook.write().expect("orderbook lock poisoned");
     for (maker, amount) in MAKERS {
         let intent = Intent {
             id: Uuid::new_v4(),
-            user: maker.to_string(),
-            from_chain: Chain::Base,
-            to_chain: Chain::Sepolia,
-            amount,
+            user_id: maker.to_string(),
+            from_chain: SETTLEMENT_CHAIN,
+            to_chain: TAKER_CHAIN,
+            amount: amount.into(),
             kind: IntentKind::Maker,
+            status: IntentStatus::InOrderbook,
         };
-        state.orderbook.push(intent);
+        orderbook.push(intent);
     }
 }
 
-pub fn add_intent(state: &mut AppState, intent: Intent) {
-    state.orderbook.push(intent);
+pub fn add_intent(state: &AppState, intent: Intent) {
+    state
+        .orderbook
+        .write()
+        .expect("orderbook lock poisoned")
+        .push(intent);
+}
+
+pub fn orderbook_snapshot(state: &AppState) -> Vec<Intent> {
+    state
+        .orderbook
+        .read()
+        .expect("orderbook lock poisoned")
+        .clone()
 }
@@ -0,0 +1,7 @@
+//! Intent signatures, verified with the backend's implementation in
+//! `oceanlink-core` so one signed intent is valid for either service.
+
+pub use oceanlink_core::signing::{
+    parse_address, unix_now, verify_signature, IntentMessage, NonceRegistry, SignatureError,
+    SigningDomain, MAX_DEADLINE_WINDOW,
+};